@@ -136,9 +136,17 @@
 //! [`Error`]: ../struct.Error.html
 
 use crate::codec::{Codec, SendError, UserError};
-use crate::ext::{Protocol, PseudoHeadersOverride};
-use crate::frame::{Headers, Pseudo, Reason, Settings, StreamId};
+use crate::ext::{
+    AltSvc, ExtensionFrame, ExtensionFrameHandlers, HeaderIndexingOverride, OrderedHeaders,
+    Protocol, PseudoHeaderOrder, PseudoHeadersOverride, StreamDependency,
+};
+use crate::frame::{self, Headers, Pseudo, Reason, Settings, StreamId};
+use crate::padding::Padding;
+use crate::profiles::Profile;
 use crate::proto::{self, Error};
+use crate::scheduler::{NewScheduler, SendScheduler};
+use crate::stats::ConnectionStats;
+use crate::timer::{SharedTimer, Timer};
 use crate::{FlowControl, PingPong, RecvStream, SendStream};
 
 use bytes::{Buf, Bytes};
@@ -147,6 +155,7 @@ use http::{uri, HeaderMap, Method, Request, Response, Version};
 use std::fmt;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
@@ -233,6 +242,16 @@ pub struct Connection<T, B: Buf = Bytes> {
     inner: proto::Connection<T, Peer, B>,
 }
 
+/// A `GOAWAY` frame received from the server.
+///
+/// See [`Connection::poll_goaway`].
+#[derive(Clone, Debug)]
+pub struct GoAway {
+    last_stream_id: crate::StreamId,
+    reason: Reason,
+    debug_data: Bytes,
+}
+
 /// A future of an HTTP response.
 #[derive(Debug)]
 #[must_use = "futures do nothing unless polled"]
@@ -335,6 +354,20 @@ pub struct Builder {
     /// Initial `Settings` frame to send as part of the handshake.
     settings: Settings,
 
+    /// Explicit `(id, value)` pairs to send in the initial `Settings` frame
+    /// instead of `settings`.
+    initial_settings: Option<Vec<(u16, u32)>>,
+
+    /// Frames to send right after the initial `Settings` frame.
+    preface: Vec<proto::PrefaceFrame>,
+
+    /// The sum of the increments of the `WINDOW_UPDATE` frames in `preface`.
+    preface_window_increment: u32,
+
+    /// Whether an invalid `WINDOW_UPDATE` frame was queued, failing the
+    /// handshake.
+    invalid_preface_window_update: bool,
+
     /// The stream ID of the first (lowest) stream. Subsequent streams will use
     /// monotonically increasing stream IDs.
     stream_id: StreamId,
@@ -344,11 +377,66 @@ pub struct Builder {
     ///
     /// When this gets exceeded, we issue GOAWAYs.
     local_max_error_reset_streams: Option<usize>,
+
+    /// Order in which request pseudo headers are encoded.
+    pseudo_header_order: PseudoHeaderOrder,
+
+    /// Stream dependency sent with request HEADERS by default.
+    request_stream_dependency: Option<StreamDependency>,
+
+    /// Whether the receive windows grow to the estimated bandwidth-delay
+    /// product.
+    adaptive_window: bool,
+
+    /// Interval of keep-alive PINGs, if enabled.
+    keep_alive_interval: Option<Duration>,
+
+    /// Time to wait for a response to a keep-alive PING.
+    keep_alive_timeout: Duration,
+
+    /// Whether keep-alive PINGs are sent without open streams.
+    keep_alive_while_idle: bool,
+
+    /// Timer of the keep-alive PINGs, instead of the one of Tokio.
+    timer: Option<SharedTimer>,
+
+    /// Padding of the sent DATA, HEADERS and PUSH_PROMISE frames.
+    padding: Padding,
+
+    /// Decides whether promised requests are accepted.
+    push_policy: Option<PushPolicy>,
+
+    /// Maximum number of open pushed streams per request.
+    max_concurrent_pushes_per_stream: Option<usize>,
+
+    /// Maximum number of pushed responses kept for `SendRequest::claim_push`.
+    push_cache_size: usize,
+
+    /// Creates the scheduler of sent frames of each connection.
+    scheduler: Option<NewScheduler>,
+
+    /// Handlers of received extension frames.
+    extension_frame_handlers: ExtensionFrameHandlers,
 }
 
 #[derive(Debug)]
 pub(crate) struct Peer;
 
+/// Decides whether the requests promised by the server are accepted.
+#[derive(Clone)]
+pub(crate) struct PushPolicy(Arc<dyn Fn(&Request<()>) -> bool + Send + Sync>);
+
+/// Request extensions that control how the request `HEADERS` frame is
+/// encoded.
+#[derive(Debug, Default)]
+pub(crate) struct SendExtensions {
+    pub protocol: Option<Protocol>,
+    pub pseudo_overrides: Option<PseudoHeadersOverride>,
+    pub stream_dep: Option<StreamDependency>,
+    pub ordered_headers: Option<OrderedHeaders>,
+    pub header_indexing: Option<HeaderIndexingOverride>,
+}
+
 // ===== impl SendRequest =====
 
 impl<B> SendRequest<B>
@@ -536,6 +624,47 @@ where
             })
     }
 
+    /// Claims the pushed response to `request` kept in the push cache, if
+    /// any.
+    ///
+    /// A push matches a request with the same method and URI. The claimed
+    /// push is removed from the cache, and `request` does not need to be
+    /// sent. See [`Builder::push_cache_size`] to enable the cache.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use h2::client::*;
+    /// # use http::*;
+    /// # async fn doc(mut send_request: SendRequest<&'static [u8]>)
+    /// # {
+    /// let request = Request::get("https://example.com/style.css")
+    ///     .body(())
+    ///     .unwrap();
+    ///
+    /// let response = match send_request.claim_push(&request) {
+    ///     Some(pushed) => pushed.await,
+    ///     None => {
+    ///         let (response, _) = send_request.send_request(request, true).unwrap();
+    ///         response.await
+    ///     }
+    /// };
+    /// # }
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`Builder::push_cache_size`]: struct.Builder.html#method.push_cache_size
+    pub fn claim_push<T>(&mut self, request: &Request<T>) -> Option<PushedResponseFuture> {
+        self.inner
+            .claim_push(request.method(), request.uri())
+            .map(|stream| PushedResponseFuture {
+                inner: ResponseFuture {
+                    inner: stream,
+                    push_promise_consumed: false,
+                },
+            })
+    }
+
     /// Returns whether the [extended CONNECT protocol][1] is enabled or not.
     ///
     /// This setting is configured by the server peer by sending the
@@ -662,8 +791,25 @@ impl Builder {
             initial_target_connection_window_size: None,
             initial_max_send_streams: usize::MAX,
             settings: Default::default(),
+            initial_settings: None,
+            preface: Vec::new(),
+            preface_window_increment: 0,
+            invalid_preface_window_update: false,
             stream_id: 1.into(),
             local_max_error_reset_streams: Some(proto::DEFAULT_LOCAL_RESET_COUNT_MAX),
+            pseudo_header_order: PseudoHeaderOrder::default(),
+            request_stream_dependency: None,
+            adaptive_window: false,
+            keep_alive_interval: None,
+            keep_alive_timeout: Duration::from_secs(proto::DEFAULT_KEEP_ALIVE_TIMEOUT_SECS),
+            keep_alive_while_idle: false,
+            timer: None,
+            padding: Padding::default(),
+            push_policy: None,
+            max_concurrent_pushes_per_stream: None,
+            push_cache_size: 0,
+            scheduler: None,
+            extension_frame_handlers: ExtensionFrameHandlers::default(),
         }
     }
 
@@ -737,6 +883,176 @@ impl Builder {
         self
     }
 
+    /// Enables adaptive flow control, which grows the receive windows to the
+    /// bandwidth-delay product of the connection.
+    ///
+    /// The bandwidth-delay product is estimated by sending a `PING` frame
+    /// when `DATA` frames are received, and counting the data received until
+    /// it is acknowledged. When that data nears the estimate, the sender was
+    /// likely blocked on flow control, and both the connection window and the
+    /// initial window of streams are set to twice that data, up to 16MB.
+    ///
+    /// The windows start at the sizes set with [`initial_window_size`] and
+    /// [`initial_connection_window_size`], and are only grown.
+    ///
+    /// By default, adaptive flow control is disabled.
+    ///
+    /// [`initial_window_size`]: Builder::initial_window_size
+    /// [`initial_connection_window_size`]: Builder::initial_connection_window_size
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use h2::client::*;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), h2::Error>
+    /// # {
+    /// // `client_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let client_fut = Builder::new()
+    ///     .adaptive_window(true)
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn adaptive_window(&mut self, enabled: bool) -> &mut Self {
+        self.adaptive_window = enabled;
+        self
+    }
+
+    /// Enables keep-alive, sending a `PING` frame when nothing was received
+    /// from the peer for `interval`.
+    ///
+    /// If nothing is received within the [`keep_alive_timeout`] after the
+    /// `PING` is sent, a `GOAWAY` frame is sent if the connection is still
+    /// writable, and the connection fails with an error for which
+    /// [`Error::is_keep_alive_timeout`] returns `true`. This detects peers
+    /// that became unreachable without closing the connection.
+    ///
+    /// `PING` frames are only sent while there are open streams, unless
+    /// [`keep_alive_while_idle`] is enabled.
+    ///
+    /// The keep-alive timer is the one of Tokio, unless a [`timer`] is set.
+    ///
+    /// By default, keep-alive is disabled.
+    ///
+    /// [`keep_alive_timeout`]: Builder::keep_alive_timeout
+    /// [`keep_alive_while_idle`]: Builder::keep_alive_while_idle
+    /// [`Error::is_keep_alive_timeout`]: crate::Error::is_keep_alive_timeout
+    /// [`timer`]: Builder::timer
+    ///
+    /// # Panics
+    ///
+    /// Unless a [`timer`] is set, the handshake and the connection panic when
+    /// they are polled outside of a Tokio runtime with the time driver
+    /// enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use h2::client::*;
+    /// # use std::time::Duration;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), h2::Error>
+    /// # {
+    /// // `client_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let client_fut = Builder::new()
+    ///     .keep_alive_interval(Duration::from_secs(30))
+    ///     .keep_alive_timeout(Duration::from_secs(10))
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn keep_alive_interval(&mut self, interval: Duration) -> &mut Self {
+        self.keep_alive_interval = Some(interval);
+        self
+    }
+
+    /// Sets how long to wait for a frame in response to a keep-alive `PING`
+    /// before failing the connection.
+    ///
+    /// This has no effect unless [`keep_alive_interval`] is set.
+    ///
+    /// The default value is 20 seconds.
+    ///
+    /// [`keep_alive_interval`]: Builder::keep_alive_interval
+    pub fn keep_alive_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.keep_alive_timeout = timeout;
+        self
+    }
+
+    /// Sets whether keep-alive `PING` frames are sent while there are no
+    /// open streams.
+    ///
+    /// This has no effect unless [`keep_alive_interval`] is set.
+    ///
+    /// By default, `PING` frames are only sent while there are open streams.
+    ///
+    /// [`keep_alive_interval`]: Builder::keep_alive_interval
+    pub fn keep_alive_while_idle(&mut self, enabled: bool) -> &mut Self {
+        self.keep_alive_while_idle = enabled;
+        self
+    }
+
+    /// Sets the timer used by keep-alive.
+    ///
+    /// See the [`timer`] module for more details.
+    ///
+    /// By default, the timer of the current Tokio runtime is used.
+    ///
+    /// [`timer`]: crate::timer
+    pub fn timer<M: Timer>(&mut self, timer: M) -> &mut Self {
+        self.timer = Some(SharedTimer::new(timer));
+        self
+    }
+
+    /// Sets the padding policy of the `DATA`, `HEADERS` and `PUSH_PROMISE`
+    /// frames sent on connections.
+    ///
+    /// Streams can override the policy, see the [`padding`] module for
+    /// details.
+    ///
+    /// By default, frames are not padded.
+    ///
+    /// [`padding`]: crate::padding
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use h2::client::*;
+    /// # use h2::padding::Padding;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), h2::Error>
+    /// # {
+    /// // `client_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let client_fut = Builder::new()
+    ///     .padding(Padding::block(64))
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn padding(&mut self, padding: Padding) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+
     /// Indicates the size (in octets) of the largest HTTP/2 frame payload that the
     /// configured client is able to accept.
     ///
@@ -1113,13 +1429,20 @@ impl Builder {
         self
     }
 
-    /// Sets the header table size.
+    /// Sets the policy deciding whether each promised request is accepted.
     ///
-    /// This setting informs the peer of the maximum size of the header compression
-    /// table used to encode header blocks, in octets. The encoder may select any value
-    /// equal to or less than the header table size specified by the sender.
+    /// `policy` is called with the promised request, as soon as its
+    /// `PUSH_PROMISE` frame is received and before the pushed stream is made
+    /// available. A rejected push is reset with `CANCEL`, and is never
+    /// yielded by [`ResponseFuture::push_promises`] nor kept in the
+    /// [push cache].
     ///
-    /// The default value is 4,096.
+    /// `policy` is called while the connection state is locked, and must not
+    /// use the connection.
+    ///
+    /// By default, all pushes are accepted.
+    ///
+    /// [push cache]: #method.push_cache_size
     ///
     /// # Examples
     ///
@@ -1134,127 +1457,591 @@ impl Builder {
     /// // `client_fut` is a future representing the completion of the HTTP/2
     /// // handshake.
     /// let client_fut = Builder::new()
-    ///     .header_table_size(1_000_000)
+    ///     .push_policy(|request| {
+    ///         request.uri().authority().map(|a| a.host()) == Some("example.com")
+    ///             && request.uri().path().starts_with("/static/")
+    ///     })
     ///     .handshake(my_io);
     /// # client_fut.await
     /// # }
     /// #
     /// # pub fn main() {}
     /// ```
-    pub fn header_table_size(&mut self, size: u32) -> &mut Self {
-        self.settings.set_header_table_size(Some(size));
-        self
-    }
-
-    /// Sets the first stream ID to something other than 1.
-    #[cfg(feature = "unstable")]
-    pub fn initial_stream_id(&mut self, stream_id: u32) -> &mut Self {
-        self.stream_id = stream_id.into();
-        assert!(
-            self.stream_id.is_client_initiated(),
-            "stream id must be odd"
-        );
+    pub fn push_policy<F>(&mut self, policy: F) -> &mut Self
+    where
+        F: Fn(&Request<()>) -> bool + Send + Sync + 'static,
+    {
+        self.push_policy = Some(PushPolicy::new(policy));
         self
     }
 
-    /// Creates a new configured HTTP/2 client backed by `io`.
-    ///
-    /// It is expected that `io` already be in an appropriate state to commence
-    /// the [HTTP/2 handshake]. The handshake is completed once both the connection
-    /// preface and the initial settings frame is sent by the client.
-    ///
-    /// The handshake future does not wait for the initial settings frame from the
-    /// server.
-    ///
-    /// Returns a future which resolves to the [`Connection`] / [`SendRequest`]
-    /// tuple once the HTTP/2 handshake has been completed.
+    /// Sets the maximum number of pushed streams of a request that may be
+    /// open at the same time.
     ///
-    /// This function also allows the caller to configure the send payload data
-    /// type. See [Outbound data type] for more details.
+    /// Pushes received beyond this limit are reset with `REFUSED_STREAM`.
     ///
-    /// [HTTP/2 handshake]: http://httpwg.org/specs/rfc7540.html#ConnectionHeader
-    /// [`Connection`]: struct.Connection.html
-    /// [`SendRequest`]: struct.SendRequest.html
-    /// [Outbound data type]: ../index.html#outbound-data-type.
+    /// By default, the number of pushes is not limited.
     ///
     /// # Examples
     ///
-    /// Basic usage:
-    ///
     /// ```
     /// # use tokio::io::{AsyncRead, AsyncWrite};
     /// # use h2::client::*;
     /// # use bytes::Bytes;
     /// #
     /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
-    ///     -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), h2::Error>
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), h2::Error>
     /// # {
     /// // `client_fut` is a future representing the completion of the HTTP/2
     /// // handshake.
     /// let client_fut = Builder::new()
+    ///     .max_concurrent_pushes_per_stream(8)
     ///     .handshake(my_io);
     /// # client_fut.await
     /// # }
     /// #
     /// # pub fn main() {}
     /// ```
+    pub fn max_concurrent_pushes_per_stream(&mut self, max: usize) -> &mut Self {
+        self.max_concurrent_pushes_per_stream = Some(max);
+        self
+    }
+
+    /// Sets the number of pushed responses kept by the connection until a
+    /// request claims them with [`SendRequest::claim_push`].
     ///
-    /// Configures the send-payload data type. In this case, the outbound data
-    /// type will be `&'static [u8]`.
+    /// When the size is not zero, accepted pushes are kept in the cache of
+    /// the connection instead of being yielded by
+    /// [`ResponseFuture::push_promises`], and the response of a pushed stream
+    /// is received even if the request that promised it is dropped. Once the
+    /// cache is full, the oldest push is reset with `CANCEL` to make room for
+    /// a new one.
+    ///
+    /// By default, the size is zero and no push is cached.
+    ///
+    /// # Examples
     ///
     /// ```
     /// # use tokio::io::{AsyncRead, AsyncWrite};
     /// # use h2::client::*;
+    /// # use bytes::Bytes;
     /// #
     /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
-    /// # -> Result<((SendRequest<&'static [u8]>, Connection<T, &'static [u8]>)), h2::Error>
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), h2::Error>
     /// # {
     /// // `client_fut` is a future representing the completion of the HTTP/2
     /// // handshake.
     /// let client_fut = Builder::new()
-    ///     .handshake::<_, &'static [u8]>(my_io);
+    ///     .push_cache_size(32)
+    ///     .handshake(my_io);
     /// # client_fut.await
     /// # }
     /// #
     /// # pub fn main() {}
     /// ```
-    pub fn handshake<T, B>(
-        &self,
-        io: T,
-    ) -> impl Future<Output = Result<(SendRequest<B>, Connection<T, B>), crate::Error>>
+    pub fn push_cache_size(&mut self, size: usize) -> &mut Self {
+        self.push_cache_size = size;
+        self
+    }
+
+    /// Sets the `SETTINGS_NO_RFC7540_PRIORITIES` setting.
+    ///
+    /// Setting it to `true` tells the server that the client does not use
+    /// the [RFC 7540] priority scheme, and signals priorities with the
+    /// `priority` header and `PRIORITY_UPDATE` frames of [RFC 9218] instead.
+    ///
+    /// By default, the setting is not sent.
+    ///
+    /// [RFC 7540]: https://httpwg.org/specs/rfc7540.html#StreamPriority
+    /// [RFC 9218]: https://www.rfc-editor.org/rfc/rfc9218.html
+    pub fn no_rfc7540_priorities(&mut self, enabled: bool) -> &mut Self {
+        self.settings.set_no_rfc7540_priorities(Some(enabled));
+        self
+    }
+
+    /// Sets the strategy deciding which stream sends the next frame.
+    ///
+    /// `new_scheduler` is called to create the scheduler of each connection.
+    /// See the [`scheduler`] module for more details.
+    ///
+    /// By default, streams take turns following their [RFC 9218]
+    /// priorities.
+    ///
+    /// [`scheduler`]: crate::scheduler
+    /// [RFC 9218]: https://www.rfc-editor.org/rfc/rfc9218.html
+    pub fn scheduler<F, S>(&mut self, new_scheduler: F) -> &mut Self
     where
-        T: AsyncRead + AsyncWrite + Unpin,
-        B: Buf,
+        F: Fn() -> S + Send + Sync + 'static,
+        S: SendScheduler,
     {
-        Connection::handshake2(io, self.clone())
+        self.scheduler = Some(NewScheduler::new(new_scheduler));
+        self
     }
-}
 
-impl Default for Builder {
-    fn default() -> Builder {
-        Builder::new()
+    /// Registers the handler of received frames of type `kind`, which is
+    /// not defined by this crate, such as the frames of protocol extensions.
+    ///
+    /// The handler is called with the frames that apply to the connection,
+    /// and with the frames received on streams that are not open. Frames
+    /// received on open streams are read with
+    /// [`RecvStream::poll_extension_frame`] instead. Frames of types without
+    /// a handler are ignored.
+    ///
+    /// The handler is called while the connection is polled, and should not
+    /// block.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `kind` is a frame type defined by this crate.
+    ///
+    /// [`RecvStream::poll_extension_frame`]: crate::RecvStream::poll_extension_frame
+    pub fn extension_frame_handler<F>(&mut self, kind: u8, handler: F) -> &mut Self
+    where
+        F: Fn(ExtensionFrame) + Send + Sync + 'static,
+    {
+        self.extension_frame_handlers.insert(kind, handler);
+        self
     }
-}
 
-/// Creates a new configured HTTP/2 client with default configuration
-/// values backed by `io`.
-///
-/// It is expected that `io` already be in an appropriate state to commence
-/// the [HTTP/2 handshake]. See [Handshake] for more details.
-///
-/// Returns a future which resolves to the [`Connection`] / [`SendRequest`]
-/// tuple once the HTTP/2 handshake has been completed. The returned
-/// [`Connection`] instance will be using default configuration values. Use
-/// [`Builder`] to customize the configuration values used by a [`Connection`]
-/// instance.
-///
-/// [HTTP/2 handshake]: http://httpwg.org/specs/rfc7540.html#ConnectionHeader
-/// [Handshake]: ../index.html#handshake
-/// [`Connection`]: struct.Connection.html
-/// [`SendRequest`]: struct.SendRequest.html
-///
-/// # Examples
-///
+    /// Sets the header table size.
+    ///
+    /// This setting informs the peer of the maximum size of the header compression
+    /// table used to encode header blocks, in octets. The encoder may select any value
+    /// equal to or less than the header table size specified by the sender.
+    ///
+    /// The default value is 4,096.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use h2::client::*;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), h2::Error>
+    /// # {
+    /// // `client_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let client_fut = Builder::new()
+    ///     .header_table_size(1_000_000)
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn header_table_size(&mut self, size: u32) -> &mut Self {
+        self.settings.set_header_table_size(Some(size));
+        self
+    }
+
+    /// Sets the exact list of settings sent in the initial `SETTINGS` frame.
+    ///
+    /// The settings are encoded as `(identifier, value)` pairs in the given
+    /// order. Duplicate and unknown identifiers (for example reserved or
+    /// GREASE values) are sent as is. Settings that are not listed are not
+    /// sent at all, so the peer assumes their default values.
+    ///
+    /// Once set, the `SETTINGS` values configured through other builder
+    /// methods, such as [`initial_window_size`] or [`enable_push`], are
+    /// ignored. Listed settings with a known identifier configure the local
+    /// side of the connection in the same way those methods would.
+    ///
+    /// [`initial_window_size`]: #method.initial_window_size
+    /// [`enable_push`]: #method.enable_push
+    ///
+    /// # Errors
+    ///
+    /// The handshake fails with a user error, before anything is written, if
+    /// a known setting has an invalid value, for example an
+    /// `SETTINGS_ENABLE_PUSH` value other than 0 or 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use h2::client::*;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), h2::Error>
+    /// # {
+    /// // `client_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let client_fut = Builder::new()
+    ///     .initial_settings([
+    ///         (0x1, 65_536),
+    ///         (0x2, 0),
+    ///         (0x4, 6_291_456),
+    ///         (0x6, 262_144),
+    ///         (0x9, 1),
+    ///     ])
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn initial_settings<I>(&mut self, settings: I) -> &mut Self
+    where
+        I: IntoIterator<Item = (u16, u32)>,
+    {
+        self.initial_settings = Some(settings.into_iter().collect());
+        self
+    }
+
+    /// Queues a connection-level `WINDOW_UPDATE` frame to be sent as part of
+    /// the connection preface.
+    ///
+    /// The frame is sent right after the initial `SETTINGS` frame, together
+    /// with any other frames queued through the `preface_*` methods, in the
+    /// order the methods were called. The connection window is increased by
+    /// `increment` as soon as the frame is sent, instead of being grown
+    /// lazily as with [`initial_connection_window_size`].
+    ///
+    /// [`initial_connection_window_size`]: #method.initial_connection_window_size
+    ///
+    /// # Errors
+    ///
+    /// The handshake fails with a user error, before anything is written, if
+    /// `increment` is zero, or if the increments of all the queued frames
+    /// grow the connection window beyond the maximum window size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use h2::client::*;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), h2::Error>
+    /// # {
+    /// // `client_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let client_fut = Builder::new()
+    ///     .preface_window_update(15_663_105)
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn preface_window_update(&mut self, increment: u32) -> &mut Self {
+        // The connection window starts at the default initial window size.
+        let max_increment = proto::MAX_WINDOW_SIZE - frame::DEFAULT_INITIAL_WINDOW_SIZE;
+        let total = self.preface_window_increment.saturating_add(increment);
+
+        if increment == 0 || total > max_increment {
+            self.invalid_preface_window_update = true;
+            return self;
+        }
+
+        self.preface_window_increment = total;
+        self.preface
+            .push(proto::PrefaceFrame::WindowUpdate(frame::WindowUpdate::new(
+                StreamId::zero(),
+                increment,
+            )));
+        self
+    }
+
+    /// Queues a `PRIORITY` frame for `stream_id` to be sent as part of the
+    /// connection preface.
+    ///
+    /// The frame is sent right after the initial `SETTINGS` frame, together
+    /// with any other frames queued through the `preface_*` methods, in the
+    /// order the methods were called. This is typically used to build a
+    /// priority tree out of idle streams before the first request is sent.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `stream_id` is zero, is not a valid stream
+    /// identifier, or if the stream depends on itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use h2::client::*;
+    /// # use h2::ext::StreamDependency;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), h2::Error>
+    /// # {
+    /// // `client_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let client_fut = Builder::new()
+    ///     .preface_priority(3, StreamDependency::new(0, 201, false))
+    ///     .preface_priority(5, StreamDependency::new(0, 101, false))
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn preface_priority(&mut self, stream_id: u32, dependency: StreamDependency) -> &mut Self {
+        let stream_id = StreamId::from(stream_id);
+        assert!(!stream_id.is_zero(), "stream id must not be zero");
+        assert_ne!(
+            stream_id,
+            dependency.dependency_id(),
+            "stream cannot depend on itself"
+        );
+        self.preface
+            .push(proto::PrefaceFrame::Priority(frame::Priority::new(
+                stream_id,
+                dependency.into_frame(),
+            )));
+        self
+    }
+
+    /// Sets the order in which request pseudo headers are encoded.
+    ///
+    /// Pseudo headers are always encoded before regular header fields, but
+    /// their relative order is up to the sender. This sets that order for
+    /// every request sent on the connection. A single request can use a
+    /// different order by attaching a [`PseudoHeadersOverride`] extension.
+    ///
+    /// The default order is `:method`, `:scheme`, `:authority`, `:path`,
+    /// `:protocol`.
+    ///
+    /// [`PseudoHeadersOverride`]: ../ext/struct.PseudoHeadersOverride.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use h2::client::*;
+    /// # use h2::ext::{PseudoHeader, PseudoHeaderOrder};
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), h2::Error>
+    /// # {
+    /// // `client_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let client_fut = Builder::new()
+    ///     .pseudo_header_order(PseudoHeaderOrder::new([
+    ///         PseudoHeader::Method,
+    ///         PseudoHeader::Authority,
+    ///         PseudoHeader::Scheme,
+    ///         PseudoHeader::Path,
+    ///     ]))
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn pseudo_header_order(&mut self, order: PseudoHeaderOrder) -> &mut Self {
+        self.pseudo_header_order = order;
+        self
+    }
+
+    /// Sets the stream dependency sent with the `HEADERS` frame of every
+    /// request.
+    ///
+    /// The `HEADERS` frames are sent with the `PRIORITY` flag set and the
+    /// given dependency. A single request can use a different dependency by
+    /// attaching a [`StreamDependency`] extension.
+    ///
+    /// By default, request `HEADERS` frames carry no priority information.
+    ///
+    /// [`StreamDependency`]: ../ext/struct.StreamDependency.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use h2::client::*;
+    /// # use h2::ext::StreamDependency;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), h2::Error>
+    /// # {
+    /// // `client_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let client_fut = Builder::new()
+    ///     .request_stream_dependency(StreamDependency::new(0, 256, true))
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn request_stream_dependency(&mut self, dependency: StreamDependency) -> &mut Self {
+        self.request_stream_dependency = Some(dependency);
+        self
+    }
+
+    /// Configures the connection to look like the given client [`Profile`].
+    ///
+    /// This replaces the initial `SETTINGS`, the frames sent in the
+    /// connection preface, the pseudo header order and the default request
+    /// stream dependency with the values of the profile.
+    ///
+    /// The profile's `SETTINGS` are set through [`initial_settings`], so the
+    /// `SETTINGS` values configured through other builder methods, such as
+    /// [`initial_window_size`], are ignored even when called afterwards. The
+    /// preface, pseudo header order and request stream dependency can still be
+    /// adjusted with their own builder methods.
+    ///
+    /// [`Profile`]: ../profiles/enum.Profile.html
+    /// [`initial_settings`]: #method.initial_settings
+    /// [`initial_window_size`]: #method.initial_window_size
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use h2::client::*;
+    /// # use h2::profiles::Profile;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), h2::Error>
+    /// # {
+    /// // `client_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let client_fut = Builder::new()
+    ///     .profile(Profile::Chrome131)
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn profile(&mut self, profile: Profile) -> &mut Self {
+        self.initial_settings(profile.settings().iter().copied());
+
+        self.preface.clear();
+        self.preface_window_increment = 0;
+        self.invalid_preface_window_update = false;
+        if let Some(increment) = profile.connection_window_update() {
+            self.preface_window_update(increment);
+        }
+        for (stream_id, dependency) in profile.priority_frames() {
+            self.preface_priority(stream_id, dependency);
+        }
+
+        self.pseudo_header_order = profile.pseudo_header_order();
+        self.request_stream_dependency = profile.request_stream_dependency();
+        self
+    }
+
+    /// Sets the first stream ID to something other than 1.
+    #[cfg(feature = "unstable")]
+    pub fn initial_stream_id(&mut self, stream_id: u32) -> &mut Self {
+        self.stream_id = stream_id.into();
+        assert!(
+            self.stream_id.is_client_initiated(),
+            "stream id must be odd"
+        );
+        self
+    }
+
+    /// Creates a new configured HTTP/2 client backed by `io`.
+    ///
+    /// It is expected that `io` already be in an appropriate state to commence
+    /// the [HTTP/2 handshake]. The handshake is completed once both the connection
+    /// preface and the initial settings frame is sent by the client.
+    ///
+    /// The handshake future does not wait for the initial settings frame from the
+    /// server.
+    ///
+    /// Returns a future which resolves to the [`Connection`] / [`SendRequest`]
+    /// tuple once the HTTP/2 handshake has been completed.
+    ///
+    /// This function also allows the caller to configure the send payload data
+    /// type. See [Outbound data type] for more details.
+    ///
+    /// [HTTP/2 handshake]: http://httpwg.org/specs/rfc7540.html#ConnectionHeader
+    /// [`Connection`]: struct.Connection.html
+    /// [`SendRequest`]: struct.SendRequest.html
+    /// [Outbound data type]: ../index.html#outbound-data-type.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use h2::client::*;
+    /// # use bytes::Bytes;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    ///     -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), h2::Error>
+    /// # {
+    /// // `client_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let client_fut = Builder::new()
+    ///     .handshake(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// Configures the send-payload data type. In this case, the outbound data
+    /// type will be `&'static [u8]`.
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use h2::client::*;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Result<((SendRequest<&'static [u8]>, Connection<T, &'static [u8]>)), h2::Error>
+    /// # {
+    /// // `client_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let client_fut = Builder::new()
+    ///     .handshake::<_, &'static [u8]>(my_io);
+    /// # client_fut.await
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn handshake<T, B>(
+        &self,
+        io: T,
+    ) -> impl Future<Output = Result<(SendRequest<B>, Connection<T, B>), crate::Error>>
+    where
+        T: AsyncRead + AsyncWrite + Unpin,
+        B: Buf,
+    {
+        Connection::handshake2(io, self.clone())
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Builder {
+        Builder::new()
+    }
+}
+
+/// Creates a new configured HTTP/2 client with default configuration
+/// values backed by `io`.
+///
+/// It is expected that `io` already be in an appropriate state to commence
+/// the [HTTP/2 handshake]. See [Handshake] for more details.
+///
+/// Returns a future which resolves to the [`Connection`] / [`SendRequest`]
+/// tuple once the HTTP/2 handshake has been completed. The returned
+/// [`Connection`] instance will be using default configuration values. Use
+/// [`Builder`] to customize the configuration values used by a [`Connection`]
+/// instance.
+///
+/// [HTTP/2 handshake]: http://httpwg.org/specs/rfc7540.html#ConnectionHeader
+/// [Handshake]: ../index.html#handshake
+/// [`Connection`]: struct.Connection.html
+/// [`SendRequest`]: struct.SendRequest.html
+///
+/// # Examples
+///
 /// ```
 /// # use tokio::io::{AsyncRead, AsyncWrite};
 /// # use h2::client;
@@ -1307,23 +2094,33 @@ where
         mut io: T,
         builder: Builder,
     ) -> Result<(SendRequest<B>, Connection<T, B>), crate::Error> {
+        let mut settings = builder.settings.clone();
+        if let Some(initial) = builder.initial_settings {
+            settings
+                .set_custom(initial)
+                .map_err(|_| UserError::InvalidSettingValue)?;
+        }
+
+        if builder.invalid_preface_window_update {
+            return Err(UserError::InvalidWindowUpdateIncrement.into());
+        }
+
         bind_connection(&mut io).await?;
 
         // Create the codec
         let mut codec = Codec::new(io);
 
-        if let Some(max) = builder.settings.max_frame_size() {
+        if let Some(max) = settings.max_frame_size() {
             codec.set_max_recv_frame_size(max as usize);
         }
 
-        if let Some(max) = builder.settings.max_header_list_size() {
+        if let Some(max) = settings.max_header_list_size() {
             codec.set_max_recv_header_list_size(max as usize);
         }
 
-        // Send initial settings frame
-        codec
-            .buffer(builder.settings.clone().into())
-            .expect("invalid SETTINGS frame");
+        // Send initial settings frame, which may be too large with initial
+        // settings listed by the user
+        codec.buffer(settings.clone().into())?;
 
         let inner = proto::Connection::new(
             codec,
@@ -1335,7 +2132,22 @@ where
                 reset_stream_max: builder.reset_stream_max,
                 remote_reset_stream_max: builder.pending_accept_reset_stream_max,
                 local_error_reset_streams_max: builder.local_max_error_reset_streams,
-                settings: builder.settings.clone(),
+                pseudo_header_order: builder.pseudo_header_order,
+                request_stream_dependency: builder.request_stream_dependency,
+                rfc7540_priorities: false,
+                adaptive_window: builder.adaptive_window,
+                keep_alive_interval: builder.keep_alive_interval,
+                keep_alive_timeout: builder.keep_alive_timeout,
+                keep_alive_while_idle: builder.keep_alive_while_idle,
+                timer: builder.timer,
+                padding: builder.padding,
+                push_policy: builder.push_policy,
+                max_concurrent_pushes_per_stream: builder.max_concurrent_pushes_per_stream,
+                push_cache_size: builder.push_cache_size,
+                scheduler: builder.scheduler,
+                extension_frame_handlers: builder.extension_frame_handlers,
+                settings,
+                preface: builder.preface,
             },
         );
         let send_request = SendRequest {
@@ -1401,6 +2213,61 @@ where
         self.inner.take_user_pings().map(PingPong::new)
     }
 
+    /// Queues a frame of a type that is not defined by this crate, such as
+    /// the frames of protocol extensions, to be sent with the next poll of
+    /// the connection.
+    ///
+    /// A `stream_id` of zero sends a frame that applies to the connection.
+    /// The frame is sent as is, regardless of the state of the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `kind` is a frame type defined by this crate, if
+    /// `stream_id` has its reserved bit set, or if the payload is larger
+    /// than the maximum frame size of the peer.
+    pub fn send_extension_frame(
+        &mut self,
+        kind: u8,
+        flags: u8,
+        stream_id: u32,
+        payload: Bytes,
+    ) -> Result<(), crate::Error> {
+        let frame = proto::extension_frame(kind, flags, stream_id, payload)?;
+        self.inner.send_extension_frame(frame)?;
+        Ok(())
+    }
+
+    /// Polls for the next alternative services advertised by the server
+    /// with an [`ALTSVC` frame][1].
+    ///
+    /// The frames are received while the connection is polled; up to 64 of
+    /// them are buffered until they are polled here, and later ones are
+    /// dropped. Once the connection is closed and the buffered frames were
+    /// polled, this returns `Poll::Ready(None)`.
+    ///
+    /// [1]: https://www.rfc-editor.org/rfc/rfc7838.html#section-4
+    pub fn poll_altsvc(&mut self, cx: &mut Context<'_>) -> Poll<Option<AltSvc>> {
+        self.inner
+            .poll_altsvc(cx)
+            .map(|frame| frame.map(AltSvc::new))
+    }
+
+    /// Polls for changes of the origin set advertised by the server with
+    /// [`ORIGIN` frames][1], listing the ASCII serializations of the origins
+    /// the server is authoritative for.
+    ///
+    /// Each `ORIGIN` frame adds its origins to the origin set, and origins are
+    /// never removed. Once a frame adds origins, the whole origin set is
+    /// returned, in the order the origins were first received. Up to 1024
+    /// origins are kept, and later ones are dropped. The frames are received
+    /// while the connection is polled. Once the connection is closed and the
+    /// last change was polled, this returns `Poll::Ready(None)`.
+    ///
+    /// [1]: https://www.rfc-editor.org/rfc/rfc8336.html#section-2
+    pub fn poll_origin_set(&mut self, cx: &mut Context<'_>) -> Poll<Option<Vec<Bytes>>> {
+        self.inner.poll_origin_set(cx)
+    }
+
     /// Returns the maximum number of concurrent streams that may be initiated
     /// by this client.
     ///
@@ -1427,6 +2294,74 @@ where
     pub fn max_concurrent_recv_streams(&self) -> usize {
         self.inner.max_recv_streams()
     }
+
+    /// Returns the [Akamai HTTP/2 fingerprint][1] of this client.
+    ///
+    /// The fingerprint has the format
+    /// `SETTINGS|WINDOW_UPDATE|PRIORITY|PSEUDO_HEADER_ORDER`, for example
+    /// `1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p`. It is computed
+    /// from the frames sent on this connection up to and including the first
+    /// `HEADERS` frame, so this returns `None` until a request was sent.
+    ///
+    /// [1]: https://www.blackhat.com/docs/eu-17/materials/eu-17-Shuster-Passive-Fingerprinting-Of-HTTP2-Clients-wp.pdf
+    pub fn local_fingerprint(&self) -> Option<String> {
+        self.inner.local_fingerprint()
+    }
+
+    /// Returns a snapshot of the statistics of the connection.
+    ///
+    /// See the [`stats`](crate::stats) module for more details.
+    pub fn stats(&self) -> ConnectionStats {
+        self.inner.stats()
+    }
+
+    /// Polls for the next `GOAWAY` frame received from the server.
+    ///
+    /// Returns the last `GOAWAY` received since the previous call, or
+    /// `Poll::Pending` and registers the task to be notified once one is
+    /// received. A server shutting down gracefully may send several `GOAWAY`
+    /// frames, with decreasing last stream IDs. Once the connection is closed
+    /// and the last `GOAWAY` was polled, this returns `Poll::Ready(None)`.
+    ///
+    /// Requests with a stream ID above the last stream ID fail with an error
+    /// for which [`Error::is_unprocessed`] returns true, and can be retried
+    /// on another connection. The connection itself must still be polled to
+    /// complete the requests at or below it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use h2::client::Connection;
+    /// # use bytes::Bytes;
+    /// # use std::future::Future;
+    /// # use std::pin::Pin;
+    /// # use std::task::Poll;
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(mut connection: Connection<T, Bytes>)
+    /// # -> Result<(), h2::Error>
+    /// # {
+    /// futures_util::future::poll_fn(|cx| {
+    ///     while let Poll::Ready(Some(go_away)) = connection.poll_goaway(cx) {
+    ///         // Stop sending requests on this connection.
+    ///         println!("GOAWAY; last stream ID = {:?}", go_away.last_stream_id());
+    ///     }
+    ///     Pin::new(&mut connection).poll(cx)
+    /// })
+    /// .await
+    /// # }
+    /// # pub fn main() {}
+    /// ```
+    ///
+    /// [`Error::is_unprocessed`]: crate::Error::is_unprocessed
+    pub fn poll_goaway(&mut self, cx: &mut Context<'_>) -> Poll<Option<GoAway>> {
+        self.inner.poll_recv_go_away(cx).map(|frame| {
+            frame.map(|frame| GoAway {
+                last_stream_id: crate::StreamId::from_internal(frame.last_stream_id()),
+                reason: frame.reason(),
+                debug_data: frame.debug_data().clone(),
+            })
+        })
+    }
 }
 
 impl<T, B> Future for Connection<T, B>
@@ -1504,6 +2439,12 @@ impl ResponseFuture {
 
     /// Returns a stream of PushPromises
     ///
+    /// Pushes rejected by the [push policy], or kept in the [push cache], are
+    /// not yielded.
+    ///
+    /// [push policy]: struct.Builder.html#method.push_policy
+    /// [push cache]: struct.Builder.html#method.push_cache_size
+    ///
     /// # Panics
     ///
     /// If this method has been called before
@@ -1599,18 +2540,70 @@ impl PushedResponseFuture {
     }
 }
 
+// ===== impl GoAway =====
+
+impl GoAway {
+    /// Returns the ID of the last stream the server may have processed.
+    ///
+    /// Streams with a higher ID were not processed, and their requests can
+    /// be retried on another connection.
+    pub fn last_stream_id(&self) -> crate::StreamId {
+        self.last_stream_id
+    }
+
+    /// Returns the error code of the `GOAWAY`, `NO_ERROR` for a graceful
+    /// shutdown.
+    pub fn reason(&self) -> Reason {
+        self.reason
+    }
+
+    /// Returns the opaque debug data sent by the server.
+    pub fn debug_data(&self) -> &Bytes {
+        &self.debug_data
+    }
+}
+
+// ===== impl PushPolicy =====
+
+impl PushPolicy {
+    fn new<F>(policy: F) -> Self
+    where
+        F: Fn(&Request<()>) -> bool + Send + Sync + 'static,
+    {
+        PushPolicy(Arc::new(policy))
+    }
+
+    pub(crate) fn accepts(&self, request: &Request<()>) -> bool {
+        (self.0)(request)
+    }
+}
+
+impl fmt::Debug for PushPolicy {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("PushPolicy").finish()
+    }
+}
+
 // ===== impl Peer =====
 
 impl Peer {
     pub fn convert_send_message(
         id: StreamId,
         request: Request<()>,
-        protocol: Option<Protocol>,
-        pseudo_overrides: Option<PseudoHeadersOverride>,
+        extensions: SendExtensions,
+        pseudo_order: PseudoHeaderOrder,
         end_of_stream: bool,
     ) -> Result<Headers, SendError> {
         use http::request::Parts;
 
+        let SendExtensions {
+            protocol,
+            pseudo_overrides,
+            stream_dep,
+            ordered_headers,
+            header_indexing,
+        } = extensions;
+
         let (
             Parts {
                 method,
@@ -1622,6 +2615,10 @@ impl Peer {
             _,
         ) = request.into_parts();
 
+        if let Some(ref ordered) = ordered_headers {
+            headers = ordered.to_header_map();
+        }
+
         // should not send host header - only authority psuedo header.
         _ = headers.remove(HOST);
 
@@ -1632,6 +2629,7 @@ impl Peer {
             .and_then(|overrides| overrides.protocol.clone())
             .or(protocol);
         let mut pseudo = Pseudo::request(method, uri, protocol);
+        pseudo.set_order(pseudo_order);
         if let Some(overrides) = pseudo_overrides {
             if let Some(method) = overrides.method {
                 pseudo.method = Some(method);
@@ -1687,6 +2685,22 @@ impl Peer {
         // Create the HEADERS frame
         let mut frame = Headers::new(id, pseudo, headers);
 
+        if let Some(dep) = stream_dep {
+            if StreamId::from(dep.dependency_id()) == id {
+                return Err(UserError::SelfDependentStream.into());
+            }
+
+            frame.set_stream_dependency(dep.into_frame());
+        }
+
+        if let Some(ordered) = ordered_headers {
+            frame.set_ordered_fields(ordered.into_vec());
+        }
+
+        if let Some(indexing) = header_indexing {
+            frame.set_header_indexing(indexing);
+        }
+
         if end_of_stream {
             frame.set_end_stream()
         }
@@ -1760,8 +2774,11 @@ mod tests {
         let headers = Peer::convert_send_message(
             StreamId::from(1),
             request,
-            None,
-            Some(overrides),
+            SendExtensions {
+                pseudo_overrides: Some(overrides),
+                ..Default::default()
+            },
+            PseudoHeaderOrder::default(),
             true,
         )
         .expect("pseudo overrides should succeed");
@@ -1770,11 +2787,38 @@ mod tests {
 
         assert_eq!(pseudo.method, Some(Method::POST));
         assert_eq!(pseudo.scheme.unwrap().as_ref(), b"http");
-        assert_eq!(
-            pseudo.authority.unwrap().as_ref(),
-            b"override.example.com"
-        );
+        assert_eq!(pseudo.authority.unwrap().as_ref(), b"override.example.com");
         assert_eq!(pseudo.path.unwrap().as_ref(), b"/custom-path");
         assert_eq!(pseudo.protocol.unwrap().as_str(), "test-proto");
     }
+
+    #[test]
+    fn pseudo_order_is_applied() {
+        use crate::ext::PseudoHeader;
+
+        let request = Request::builder()
+            .uri("https://example.com/")
+            .body(())
+            .unwrap();
+
+        let order = PseudoHeaderOrder::new([
+            PseudoHeader::Method,
+            PseudoHeader::Authority,
+            PseudoHeader::Scheme,
+            PseudoHeader::Path,
+        ]);
+
+        let headers = Peer::convert_send_message(
+            StreamId::from(1),
+            request,
+            SendExtensions::default(),
+            order,
+            true,
+        )
+        .expect("request should convert");
+
+        let (pseudo, _) = headers.into_parts();
+
+        assert_eq!(pseudo.order, order);
+    }
 }
@@ -4,13 +4,17 @@ use std::{error, fmt, io};
 
 /// Errors caused by sending a message
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum SendError {
+    /// The connection failed.
     Connection(Error),
+    /// The frame cannot be sent.
     User(UserError),
 }
 
 /// Errors caused by users of the library
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum UserError {
     /// The stream ID is no longer accepting frames.
     InactiveStreamId,
@@ -52,6 +56,23 @@ pub enum UserError {
 
     /// Invalid status code for informational response (must be 1xx)
     InvalidInformationalStatusCode,
+
+    /// A request stream was made to depend on itself.
+    SelfDependentStream,
+
+    /// An extension frame has a type defined by this crate, or an invalid
+    /// stream ID.
+    InvalidExtensionFrame,
+
+    /// An origin is empty, or longer than 65535 octets.
+    InvalidOrigin,
+
+    /// An initial setting has a value that is invalid for its identifier.
+    InvalidSettingValue,
+
+    /// A `WINDOW_UPDATE` increment is zero, or grows the window beyond the
+    /// maximum window size.
+    InvalidWindowUpdateIncrement,
 }
 
 // ===== impl SendError =====
@@ -101,6 +122,11 @@ impl fmt::Display for UserError {
             SendSettingsWhilePending => "sending SETTINGS before received previous ACK",
             PeerDisabledServerPush => "sending PUSH_PROMISE to peer who disabled server push",
             InvalidInformationalStatusCode => "invalid informational status code",
+            SelfDependentStream => "stream cannot depend on itself",
+            InvalidExtensionFrame => "invalid extension frame type or stream ID",
+            InvalidOrigin => "invalid origin",
+            InvalidSettingValue => "invalid SETTINGS value",
+            InvalidWindowUpdateIncrement => "invalid WINDOW_UPDATE increment",
         })
     }
 }
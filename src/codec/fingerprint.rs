@@ -0,0 +1,192 @@
+use crate::ext::PseudoHeader;
+use crate::frame::{self, Frame};
+
+use std::fmt::Write;
+
+/// Records the frames that make up the Akamai HTTP/2 fingerprint of one
+/// direction of a connection.
+///
+/// The fingerprint covers the first `SETTINGS` frame, the connection level
+/// `WINDOW_UPDATE` and `PRIORITY` frames preceding the first `HEADERS` frame,
+/// and the pseudo header order of that `HEADERS` frame. Frames seen after the
+/// first `HEADERS` frame are ignored.
+#[derive(Debug, Default)]
+pub(crate) struct Fingerprint {
+    settings: Option<Vec<(u16, u32)>>,
+    window_update: Option<u32>,
+    priorities: Vec<frame::Priority>,
+    pseudo_headers: Option<Vec<PseudoHeader>>,
+}
+
+impl Fingerprint {
+    /// Returns whether the next non-ACK `SETTINGS` frame is part of the
+    /// fingerprint, so that its entries need to be recorded.
+    pub fn wants_settings(&self) -> bool {
+        self.settings.is_none() && !self.is_complete()
+    }
+
+    /// Records the `(identifier, value)` pairs of a non-ACK `SETTINGS` frame.
+    pub fn record_settings<I>(&mut self, entries: I)
+    where
+        I: IntoIterator<Item = (u16, u32)>,
+    {
+        if self.wants_settings() {
+            self.settings = Some(entries.into_iter().collect());
+        }
+    }
+
+    /// Records any other frame that is part of the fingerprint.
+    pub fn record<T>(&mut self, frame: &Frame<T>) {
+        if self.is_complete() {
+            return;
+        }
+
+        match *frame {
+            Frame::WindowUpdate(ref frame) if frame.stream_id().is_zero() => {
+                self.window_update.get_or_insert(frame.size_increment());
+            }
+            Frame::Priority(ref frame) => {
+                self.priorities.push(*frame);
+            }
+            Frame::Headers(ref frame) => {
+                self.pseudo_headers = Some(frame.pseudo().request_headers().collect());
+            }
+            _ => {}
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.pseudo_headers.is_some()
+    }
+
+    /// Returns the fingerprint in the Akamai format
+    /// `SETTINGS|WINDOW_UPDATE|PRIORITY|PSEUDO_HEADER_ORDER`, or `None` if
+    /// no `HEADERS` frame was recorded yet.
+    pub fn to_akamai(&self) -> Option<String> {
+        let pseudo_headers = self.pseudo_headers.as_ref()?;
+        let mut dst = String::new();
+
+        for (i, &(id, val)) in self.settings.iter().flatten().enumerate() {
+            if i > 0 {
+                dst.push(';');
+            }
+            let _ = write!(dst, "{}:{}", id, val);
+        }
+
+        match self.window_update {
+            Some(increment) => {
+                let _ = write!(dst, "|{}|", increment);
+            }
+            None => dst.push_str("|00|"),
+        }
+
+        if self.priorities.is_empty() {
+            dst.push('0');
+        }
+        for (i, frame) in self.priorities.iter().enumerate() {
+            if i > 0 {
+                dst.push(',');
+            }
+            let dependency = frame.dependency();
+            let _ = write!(
+                dst,
+                "{}:{}:{}:{}",
+                u32::from(frame.stream_id()),
+                dependency.is_exclusive() as u8,
+                u32::from(dependency.dependency_id()),
+                u16::from(dependency.weight()) + 1,
+            );
+        }
+
+        dst.push('|');
+
+        let letters = pseudo_headers.iter().filter_map(|header| match header {
+            PseudoHeader::Method => Some('m'),
+            PseudoHeader::Authority => Some('a'),
+            PseudoHeader::Scheme => Some('s'),
+            PseudoHeader::Path => Some('p'),
+            PseudoHeader::Protocol => None,
+        });
+        for (i, letter) in letters.enumerate() {
+            if i > 0 {
+                dst.push(',');
+            }
+            dst.push(letter);
+        }
+
+        Some(dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ext::PseudoHeaderOrder;
+    use crate::frame::{Headers, Pseudo, StreamDependency, StreamId, WindowUpdate};
+
+    use http::{HeaderMap, Method, Uri};
+
+    fn request(order: PseudoHeaderOrder) -> Frame<()> {
+        let mut pseudo = Pseudo::request(Method::GET, Uri::from_static("https://a.test/"), None);
+        pseudo.set_order(order);
+        Headers::new(StreamId::from(1), pseudo, HeaderMap::new()).into()
+    }
+
+    #[test]
+    fn formats_akamai_fingerprint() {
+        let mut fingerprint = Fingerprint::default();
+        fingerprint.record_settings(vec![(1, 65_536), (4, 131_072), (5, 16_384)]);
+        fingerprint.record::<()>(&WindowUpdate::new(StreamId::zero(), 12_517_377).into());
+        fingerprint.record::<()>(
+            &frame::Priority::new(
+                StreamId::from(3),
+                StreamDependency::new(StreamId::zero(), 200, false),
+            )
+            .into(),
+        );
+        fingerprint.record::<()>(
+            &frame::Priority::new(
+                StreamId::from(9),
+                StreamDependency::new(StreamId::from(7), 0, true),
+            )
+            .into(),
+        );
+
+        assert_eq!(fingerprint.to_akamai(), None);
+
+        fingerprint.record(&request(PseudoHeaderOrder::new([
+            PseudoHeader::Method,
+            PseudoHeader::Path,
+            PseudoHeader::Authority,
+            PseudoHeader::Scheme,
+        ])));
+
+        assert_eq!(
+            fingerprint.to_akamai().unwrap(),
+            "1:65536;4:131072;5:16384|12517377|3:0:0:201,9:1:7:1|m,p,a,s"
+        );
+    }
+
+    #[test]
+    fn ignores_frames_after_first_headers() {
+        let mut fingerprint = Fingerprint::default();
+        fingerprint.record_settings(vec![(2, 0)]);
+        fingerprint.record(&request(PseudoHeaderOrder::default()));
+        fingerprint.record_settings(vec![(4, 1)]);
+        fingerprint.record::<()>(&WindowUpdate::new(StreamId::zero(), 1).into());
+
+        assert_eq!(fingerprint.to_akamai().unwrap(), "2:0|00|0|m,s,a,p");
+    }
+
+    #[test]
+    fn wants_only_first_settings() {
+        let mut fingerprint = Fingerprint::default();
+        assert!(fingerprint.wants_settings());
+        fingerprint.record_settings(vec![(2, 0)]);
+        assert!(!fingerprint.wants_settings());
+
+        let mut fingerprint = Fingerprint::default();
+        fingerprint.record(&request(PseudoHeaderOrder::default()));
+        assert!(!fingerprint.wants_settings());
+    }
+}
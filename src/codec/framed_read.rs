@@ -1,3 +1,4 @@
+use crate::codec::fingerprint::Fingerprint;
 use crate::frame::{self, Frame, Kind, Reason};
 use crate::frame::{
     DEFAULT_MAX_FRAME_SIZE, DEFAULT_SETTINGS_HEADER_TABLE_SIZE, MAX_MAX_FRAME_SIZE,
@@ -5,6 +6,7 @@ use crate::frame::{
 use crate::proto::Error;
 
 use crate::hpack;
+use crate::stats::TrafficStats;
 
 use futures_core::Stream;
 
@@ -33,6 +35,12 @@ pub struct FramedRead<T> {
     max_continuation_frames: usize,
 
     partial: Option<Partial>,
+
+    /// Fingerprint of the received frames
+    fingerprint: Fingerprint,
+
+    /// Counters of the received frames
+    stats: TrafficStats,
 }
 
 /// Partially loaded headers frame
@@ -64,6 +72,8 @@ impl<T> FramedRead<T> {
             max_header_list_size,
             max_continuation_frames,
             partial: None,
+            fingerprint: Fingerprint::default(),
+            stats: TrafficStats::default(),
         }
     }
 
@@ -105,6 +115,16 @@ impl<T> FramedRead<T> {
     pub fn set_header_table_size(&mut self, val: usize) {
         self.hpack.queue_size_update(val);
     }
+
+    /// Returns the fingerprint of the received frames.
+    pub fn fingerprint(&self) -> &Fingerprint {
+        &self.fingerprint
+    }
+
+    /// Returns the counters of the received frames.
+    pub fn stats(&self) -> &TrafficStats {
+        &self.stats
+    }
 }
 
 fn calc_max_continuation_frames(header_max: usize, frame_max: usize) -> usize {
@@ -124,6 +144,7 @@ fn decode_frame(
     max_header_list_size: usize,
     max_continuation_frames: usize,
     partial_inout: &mut Option<Partial>,
+    fingerprint: &mut Fingerprint,
     mut bytes: BytesMut,
 ) -> Result<Option<Frame>, Error> {
     let span = tracing::trace_span!("FramedRead::decode_frame", offset = bytes.len());
@@ -199,13 +220,21 @@ fn decode_frame(
 
     let frame = match kind {
         Kind::Settings => {
-            let res = frame::Settings::load(head, &bytes[frame::HEADER_LEN..]);
+            let payload = &bytes[frame::HEADER_LEN..];
+            let res = frame::Settings::load(head, payload);
 
-            res.map_err(|e| {
+            let settings = res.map_err(|e| {
                 proto_err!(conn: "failed to load SETTINGS frame; err={:?}", e);
                 Error::library_go_away(Reason::PROTOCOL_ERROR)
-            })?
-            .into()
+            })?;
+
+            // The frame does not keep the order of the settings, so record
+            // them from the payload.
+            if !settings.is_ack() {
+                fingerprint.record_settings(frame::Settings::load_entries(payload));
+            }
+
+            settings.into()
         }
         Kind::Ping => {
             let res = frame::Ping::load(head, &bytes[frame::HEADER_LEN..]);
@@ -277,6 +306,33 @@ fn decode_frame(
                 }
             }
         }
+        Kind::PriorityUpdate => {
+            let res = frame::PriorityUpdate::load(head, &bytes[frame::HEADER_LEN..]);
+            res.map_err(|e| {
+                proto_err!(conn: "failed to load PRIORITY_UPDATE frame; err={:?}", e);
+                match e {
+                    frame::Error::BadFrameSize => Error::library_go_away(Reason::FRAME_SIZE_ERROR),
+                    _ => Error::library_go_away(Reason::PROTOCOL_ERROR),
+                }
+            })?
+            .into()
+        }
+        Kind::AltSvc => match frame::AltSvc::load(head, &bytes[frame::HEADER_LEN..]) {
+            Ok(frame) => frame.into(),
+            Err(e) => {
+                // Invalid ALTSVC frames are ignored (RFC 7838, section 4).
+                tracing::debug!(?e, "ignoring invalid ALTSVC frame");
+                return Ok(None);
+            }
+        },
+        Kind::Origin => match frame::Origin::load(head, &bytes[frame::HEADER_LEN..]) {
+            Ok(frame) => frame.into(),
+            Err(e) => {
+                // Invalid ORIGIN frames are ignored (RFC 8336, section 2.1).
+                tracing::debug!(?e, "ignoring invalid ORIGIN frame");
+                return Ok(None);
+            }
+        },
         Kind::Continuation => {
             let is_end_headers = (head.flag() & 0x4) == 0x4;
 
@@ -361,8 +417,9 @@ fn decode_frame(
             }
         }
         Kind::Unknown => {
-            // Unknown frames are ignored
-            return Ok(None);
+            // Unknown frames are ignored by the connection, unless a handler
+            // was registered for their type.
+            frame::Extension::load(bytes.freeze()).into()
         }
     };
 
@@ -387,11 +444,15 @@ where
             };
 
             tracing::trace!(read.bytes = bytes.len());
+            self.stats.record_bytes(bytes.len());
+            self.stats.record_frame(frame::Head::parse(&bytes).kind());
+
             let Self {
                 ref mut hpack,
                 max_header_list_size,
                 ref mut partial,
                 max_continuation_frames,
+                ref mut fingerprint,
                 ..
             } = *self;
             if let Some(frame) = decode_frame(
@@ -399,9 +460,14 @@ where
                 max_header_list_size,
                 max_continuation_frames,
                 partial,
+                fingerprint,
                 bytes,
             )? {
                 tracing::debug!(?frame, "received");
+                fingerprint.record(&frame);
+                if let Frame::Reset(ref frame) = frame {
+                    self.stats.record_reset(frame.reason());
+                }
                 return Poll::Ready(Some(Ok(frame)));
             }
         }
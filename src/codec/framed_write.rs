@@ -1,7 +1,10 @@
+use crate::codec::fingerprint::Fingerprint;
 use crate::codec::UserError;
 use crate::codec::UserError::*;
 use crate::frame::{self, Frame, FrameSize};
 use crate::hpack;
+use crate::padding::{Padder, Padding};
+use crate::stats::TrafficStats;
 
 use bytes::{Buf, BufMut, BytesMut};
 use std::pin::Pin;
@@ -26,6 +29,9 @@ pub struct FramedWrite<T, B> {
     final_flush_done: bool,
 
     encoder: Encoder<B>,
+
+    /// Fingerprint of the sent frames
+    fingerprint: Fingerprint,
 }
 
 #[derive(Debug)]
@@ -52,6 +58,12 @@ struct Encoder<B> {
 
     /// Min buffer required to attempt to write a frame
     min_buffer_capacity: usize,
+
+    /// Counters of the sent frames
+    stats: TrafficStats,
+
+    /// Padding of the sent frames
+    padder: Padder,
 }
 
 #[derive(Debug)]
@@ -99,7 +111,10 @@ where
                 max_frame_size: frame::DEFAULT_MAX_FRAME_SIZE,
                 chain_threshold,
                 min_buffer_capacity: chain_threshold + frame::HEADER_LEN,
+                stats: TrafficStats::default(),
+                padder: Padder::new(Padding::default()),
             },
+            fingerprint: Fingerprint::default(),
         }
     }
 
@@ -125,7 +140,31 @@ where
     /// `poll_ready` must be called first to ensure that a frame may be
     /// accepted.
     pub fn buffer(&mut self, item: Frame<B>) -> Result<(), UserError> {
-        self.encoder.buffer(item)
+        match item {
+            // Only the first SETTINGS frame is recorded, so the entries are
+            // not collected for the later ones.
+            Frame::Settings(ref v) if !v.is_ack() => {
+                if self.fingerprint.wants_settings() {
+                    self.fingerprint.record_settings(v.entries());
+                }
+            }
+            ref v => self.fingerprint.record(v),
+        }
+
+        let kind = item.kind();
+        let reset = match item {
+            Frame::Reset(ref v) => Some(v.reason()),
+            _ => None,
+        };
+
+        self.encoder.buffer(item)?;
+
+        self.encoder.stats.record_frame(kind);
+        if let Some(reason) = reset {
+            self.encoder.stats.record_reset(reason);
+        }
+
+        Ok(())
     }
 
     /// Flush buffered data to the wire
@@ -139,15 +178,17 @@ where
                     Some(Next::Data(ref mut frame)) => {
                         tracing::trace!(queued_data_frame = true);
                         let mut buf = (&mut self.encoder.buf).chain(frame.payload_mut());
-                        ready!(poll_write_buf(Pin::new(&mut self.inner), cx, &mut buf))?
+                        let n = ready!(poll_write_buf(Pin::new(&mut self.inner), cx, &mut buf))?;
+                        self.encoder.stats.record_bytes(n);
                     }
                     _ => {
                         tracing::trace!(queued_data_frame = false);
-                        ready!(poll_write_buf(
+                        let n = ready!(poll_write_buf(
                             Pin::new(&mut self.inner),
                             cx,
                             &mut self.encoder.buf
-                        ))?
+                        ))?;
+                        self.encoder.stats.record_bytes(n);
                     }
                 };
             }
@@ -203,6 +244,7 @@ where
                 if let Some(continuation) = frame.encode(&mut buf) {
                     self.next = Some(Next::Continuation(continuation));
                 }
+                self.stats.record_frame(frame::Kind::Continuation);
                 ControlFlow::Continue
             }
             None => ControlFlow::Break,
@@ -221,12 +263,15 @@ where
             Frame::Data(mut v) => {
                 // Ensure that the payload is not greater than the max frame.
                 let len = v.payload().remaining();
+                let padded_len = v.padded_len().map_or(0, |n| n as usize);
 
-                if len > self.max_frame_size() {
+                if len + padded_len > self.max_frame_size() {
                     return Err(PayloadTooBig);
                 }
 
-                if len >= self.chain_threshold {
+                // Padded frames are copied, so that the padding can follow
+                // the payload.
+                if len >= self.chain_threshold && padded_len == 0 {
                     let head = v.head();
 
                     // Encode the frame head to the buffer
@@ -252,13 +297,17 @@ where
             }
             Frame::Headers(v) => {
                 let mut buf = limited_write_buf!(self);
-                if let Some(continuation) = v.encode(&mut self.hpack, &mut buf) {
+                if let Some(continuation) =
+                    v.encode_padded(&mut self.hpack, &mut buf, &mut self.padder)
+                {
                     self.next = Some(Next::Continuation(continuation));
                 }
             }
             Frame::PushPromise(v) => {
                 let mut buf = limited_write_buf!(self);
-                if let Some(continuation) = v.encode(&mut self.hpack, &mut buf) {
+                if let Some(continuation) =
+                    v.encode_padded(&mut self.hpack, &mut buf, &mut self.padder)
+                {
                     self.next = Some(Next::Continuation(continuation));
                 }
             }
@@ -279,17 +328,42 @@ where
                 tracing::trace!(rem = self.buf.remaining(), "encoded window_update");
             }
 
-            Frame::Priority(_) => {
-                /*
+            Frame::Priority(v) => {
+                v.encode(self.buf.get_mut());
+                tracing::trace!(rem = self.buf.remaining(), "encoded priority");
+            }
+            Frame::PriorityUpdate(v) => {
                 v.encode(self.buf.get_mut());
-                tracing::trace!("encoded priority; rem={:?}", self.buf.remaining());
-                */
-                unimplemented!();
+                tracing::trace!(rem = self.buf.remaining(), "encoded priority_update");
             }
             Frame::Reset(v) => {
                 v.encode(self.buf.get_mut());
                 tracing::trace!(rem = self.buf.remaining(), "encoded reset");
             }
+            Frame::AltSvc(v) => {
+                if v.payload_len() > self.max_frame_size() {
+                    return Err(PayloadTooBig);
+                }
+
+                v.encode(self.buf.get_mut());
+                tracing::trace!(rem = self.buf.remaining(), "encoded altsvc");
+            }
+            Frame::Origin(v) => {
+                if v.payload_len() > self.max_frame_size() {
+                    return Err(PayloadTooBig);
+                }
+
+                v.encode(self.buf.get_mut());
+                tracing::trace!(rem = self.buf.remaining(), "encoded origin");
+            }
+            Frame::Extension(v) => {
+                if v.payload().len() > self.max_frame_size() {
+                    return Err(PayloadTooBig);
+                }
+
+                v.encode(self.buf.get_mut());
+                tracing::trace!(rem = self.buf.remaining(), "encoded extension frame");
+            }
         }
 
         Ok(())
@@ -321,12 +395,42 @@ impl<T, B> FramedWrite<T, B> {
         self.encoder.max_frame_size()
     }
 
+    /// Returns the counters of the sent frames.
+    pub fn stats(&self) -> &TrafficStats {
+        &self.encoder.stats
+    }
+
+    /// Returns the HPACK compression ratio of the sent header blocks.
+    pub fn hpack_compression_ratio(&self) -> Option<f64> {
+        self.encoder.hpack.compression_ratio()
+    }
+
+    /// Returns the fingerprint of the sent frames.
+    pub fn fingerprint(&self) -> &Fingerprint {
+        &self.fingerprint
+    }
+
     /// Set the peer's max frame size.
     pub fn set_max_frame_size(&mut self, val: usize) {
         assert!(val <= frame::MAX_MAX_FRAME_SIZE as usize);
         self.encoder.max_frame_size = val as FrameSize;
     }
 
+    /// Sets the padding policy of the sent frames.
+    pub fn set_padding(&mut self, padding: Padding) {
+        self.encoder.padder.set_padding(padding);
+    }
+
+    /// Returns the padding of a DATA frame with `len` octets of data.
+    pub fn data_pad_len(
+        &mut self,
+        padding: Option<Padding>,
+        len: usize,
+        max_len: usize,
+    ) -> Option<u8> {
+        self.encoder.padder.pad_len(padding, len, max_len)
+    }
+
     /// Set the peer's header table size.
     pub fn set_header_table_size(&mut self, val: usize) {
         self.encoder.hpack.update_max_size(val);
@@ -355,7 +459,7 @@ impl<T: AsyncRead + Unpin, B> AsyncRead for FramedWrite<T, B> {
 // We never project the Pin to `B`.
 impl<T: Unpin, B> Unpin for FramedWrite<T, B> {}
 
-#[cfg(feature = "unstable")]
+#[cfg(any(feature = "unstable", feature = "codec"))]
 mod unstable {
     use super::*;
 
@@ -1,14 +1,54 @@
+//! A raw HTTP/2 frame codec.
+//!
+//! [`Codec`] reads and writes [frames] over an I/O object. It implements
+//! `Stream` for received frames and `Sink` for frames to send, and only
+//! enforces the rules needed to delimit and parse frames: the frame size
+//! limits, HPACK, and the merging of `CONTINUATION` frames. The connection
+//! preface, the state of streams and flow control are left to the caller.
+//!
+//! This module is available with the `codec` feature, for tools such as
+//! fuzzers, conformance testers and proxies.
+//!
+//! # Examples
+//!
+//! ```
+//! use futures_util::{SinkExt, StreamExt};
+//! use h2::codec::Codec;
+//! use h2::frame::{Frame, Ping};
+//! # use tokio::io::{AsyncRead, AsyncWrite};
+//!
+//! # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(io: T) -> Result<(), h2::codec::SendError> {
+//! let mut codec = Codec::<_, bytes::Bytes>::new(io);
+//! codec.send(Ping::new([0; 8]).into()).await?;
+//!
+//! while let Some(Ok(frame)) = codec.next().await {
+//!     if let Frame::Ping(ping) = frame {
+//!         assert!(ping.is_ack());
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [frames]: ../frame/index.html
+
 mod error;
+mod fingerprint;
 mod framed_read;
 mod framed_write;
 
 pub use self::error::{SendError, UserError};
+#[cfg(any(feature = "unstable", feature = "codec"))]
+pub use crate::proto::{Error, Initiator};
 
 use self::framed_read::FramedRead;
 use self::framed_write::FramedWrite;
 
 use crate::frame::{self, Data, Frame};
+use crate::padding::Padding;
+#[cfg(not(any(feature = "unstable", feature = "codec")))]
 use crate::proto::Error;
+use crate::stats::TrafficStats;
 
 use bytes::Buf;
 use futures_core::Stream;
@@ -20,6 +60,9 @@ use tokio_util::codec::length_delimited;
 
 use std::io;
 
+/// Reads and writes HTTP/2 frames over an I/O object.
+///
+/// `B` is the type of the payload of sent `DATA` frames.
 #[derive(Debug)]
 pub struct Codec<T, B> {
     inner: FramedRead<FramedWrite<T, B>>,
@@ -74,7 +117,7 @@ impl<T, B> Codec<T, B> {
     ///
     /// This is the largest size this codec will accept from the wire. Larger
     /// frames will be rejected.
-    #[cfg(feature = "unstable")]
+    #[cfg(any(feature = "unstable", feature = "codec"))]
     #[inline]
     pub fn max_recv_frame_size(&self) -> usize {
         self.inner.max_frame_size()
@@ -95,6 +138,25 @@ impl<T, B> Codec<T, B> {
         self.framed_write().set_header_table_size(val)
     }
 
+    /// Sets the padding policy of the sent `HEADERS` and `PUSH_PROMISE`
+    /// frames, and of the `DATA` frames padded by the connection.
+    pub fn set_padding(&mut self, padding: Padding) {
+        self.framed_write().set_padding(padding)
+    }
+
+    /// Returns the padding of a `DATA` frame with `len` octets of data, which
+    /// can be at most `max_len` octets once padded.
+    ///
+    /// `padding` overrides the policy of the connection.
+    pub(crate) fn data_pad_len(
+        &mut self,
+        padding: Option<Padding>,
+        len: usize,
+        max_len: usize,
+    ) -> Option<u8> {
+        self.framed_write().data_pad_len(padding, len, max_len)
+    }
+
     /// Set the decoder header table size size.
     pub fn set_recv_header_table_size(&mut self, val: usize) {
         self.inner.set_header_table_size(val)
@@ -106,7 +168,7 @@ impl<T, B> Codec<T, B> {
     }
 
     /// Get a reference to the inner stream.
-    #[cfg(feature = "unstable")]
+    #[cfg(any(feature = "unstable", feature = "codec"))]
     pub fn get_ref(&self) -> &T {
         self.inner.get_ref().get_ref()
     }
@@ -116,6 +178,31 @@ impl<T, B> Codec<T, B> {
         self.inner.get_mut().get_mut()
     }
 
+    /// Returns the counters of the frames sent so far.
+    pub(crate) fn send_stats(&self) -> &TrafficStats {
+        self.inner.get_ref().stats()
+    }
+
+    /// Returns the counters of the frames received so far.
+    pub(crate) fn recv_stats(&self) -> &TrafficStats {
+        self.inner.stats()
+    }
+
+    /// Returns the HPACK compression ratio of the header blocks sent so far.
+    pub(crate) fn hpack_compression_ratio(&self) -> Option<f64> {
+        self.inner.get_ref().hpack_compression_ratio()
+    }
+
+    /// Returns the Akamai fingerprint of the frames received so far.
+    pub(crate) fn recv_fingerprint(&self) -> Option<String> {
+        self.inner.fingerprint().to_akamai()
+    }
+
+    /// Returns the Akamai fingerprint of the frames sent so far.
+    pub(crate) fn send_fingerprint(&self) -> Option<String> {
+        self.inner.get_ref().fingerprint().to_akamai()
+    }
+
     /// Takes the data payload value that was fully written to the socket
     pub(crate) fn take_last_data_frame(&mut self) -> Option<Data<B>> {
         self.framed_write().take_last_data_frame()
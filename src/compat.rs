@@ -0,0 +1,134 @@
+//! Adapters for transports of runtimes other than Tokio.
+//!
+//! The handshakes of [`client`] and [`server`] only accept transports that
+//! implement the [`AsyncRead`] and [`AsyncWrite`] traits of Tokio. With the
+//! `futures-io-compat` feature, a transport implementing the [`futures_io`]
+//! traits instead, as the sockets of `smol` or `async-std` do, can be wrapped
+//! in a [`FuturesIo`] adapter and given to the handshake.
+//!
+//! The adapter is a thin compatibility layer rather than native support: the
+//! [`futures_io`] traits read into initialized buffers only, so the adapter
+//! zeroes part of the connection's read buffer before handing it over, and
+//! it reports no vectored write support, as those traits cannot tell it.
+//!
+//! # Examples
+//!
+//! ```
+//! use h2::client;
+//! use h2::compat::FuturesIo;
+//!
+//! # async fn doc<T>(io: T) -> Result<(), h2::Error>
+//! # where
+//! #     T: futures_io::AsyncRead + futures_io::AsyncWrite + Unpin,
+//! # {
+//! // `io` implements `futures_io::AsyncRead` and `futures_io::AsyncWrite`.
+//! let (send_request, connection) = client::handshake(FuturesIo::new(io)).await?;
+//! # drop((send_request, connection));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`client`]: crate::client
+//! [`server`]: crate::server
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::{cmp, io};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// The most bytes of the read buffer zeroed to read into at once.
+const MAX_INIT_LEN: usize = 8 * 1024;
+
+/// A transport implementing the [`futures_io`] traits, adapted to the
+/// [`AsyncRead`] and [`AsyncWrite`] traits of Tokio.
+///
+/// See the [module documentation](self) for details.
+#[derive(Debug)]
+pub struct FuturesIo<T> {
+    inner: T,
+}
+
+impl<T> FuturesIo<T> {
+    /// Wraps a transport implementing the [`futures_io`] traits.
+    pub fn new(inner: T) -> FuturesIo<T> {
+        FuturesIo { inner }
+    }
+
+    /// Returns a reference to the wrapped transport.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped transport.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Unwraps the transport.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> AsyncRead for FuturesIo<T>
+where
+    T: futures_io::AsyncRead + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        // Only zero a bounded part of the buffer when none of it is
+        // initialized yet, instead of its whole spare capacity on every read.
+        let len = cmp::max(
+            buf.initialized().len() - buf.filled().len(),
+            cmp::min(buf.remaining(), MAX_INIT_LEN),
+        );
+        let n = ready!(Pin::new(&mut self.inner).poll_read(cx, buf.initialize_unfilled_to(len)))?;
+        buf.advance(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T> AsyncWrite for FuturesIo<T>
+where
+    T: futures_io::AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        // The `futures_io` traits cannot tell whether a transport supports
+        // vectored writes, and their default implementation only writes the
+        // first buffer.
+        false
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+impl<T> From<T> for FuturesIo<T> {
+    fn from(inner: T) -> FuturesIo<T> {
+        FuturesIo::new(inner)
+    }
+}
@@ -31,6 +31,9 @@ enum Kind {
     /// A GO_AWAY frame was received or sent.
     GoAway(Bytes, Reason, Initiator),
 
+    /// A GO_AWAY frame was received before the peer processed the stream.
+    Unprocessed(Bytes, Reason),
+
     /// The user created an error from a bare Reason.
     Reason(Reason),
 
@@ -40,6 +43,9 @@ enum Kind {
 
     /// An `io::Error` occurred while trying to read or write.
     Io(io::Error),
+
+    /// A keep-alive PING was not acknowledged in time.
+    KeepAliveTimedOut,
 }
 
 // ===== impl Error =====
@@ -51,9 +57,10 @@ impl Error {
     /// action taken by the peer (i.e. a protocol error).
     pub fn reason(&self) -> Option<Reason> {
         match self.kind {
-            Kind::Reset(_, reason, _) | Kind::GoAway(_, reason, _) | Kind::Reason(reason) => {
-                Some(reason)
-            }
+            Kind::Reset(_, reason, _)
+            | Kind::GoAway(_, reason, _)
+            | Kind::Unprocessed(_, reason)
+            | Kind::Reason(reason) => Some(reason),
             _ => None,
         }
     }
@@ -87,7 +94,23 @@ impl Error {
 
     /// Returns true if the error is from a `GOAWAY`.
     pub fn is_go_away(&self) -> bool {
-        matches!(self.kind, Kind::GoAway(..))
+        matches!(self.kind, Kind::GoAway(..) | Kind::Unprocessed(..))
+    }
+
+    /// Returns true if the request failed before the peer processed it, so
+    /// that it can safely be retried on another connection, even if it is not
+    /// idempotent.
+    ///
+    /// This is the case of a request whose stream ID is above the last stream
+    /// ID of a `GOAWAY` received from the server, or which was reset by the
+    /// server with `REFUSED_STREAM`, as described in [RFC 9113].
+    ///
+    /// [RFC 9113]: https://httpwg.org/specs/rfc9113.html#Reliability
+    pub fn is_unprocessed(&self) -> bool {
+        matches!(
+            self.kind,
+            Kind::Unprocessed(..) | Kind::Reset(_, Reason::REFUSED_STREAM, Initiator::Remote)
+        )
     }
 
     /// Returns true if the error is from a `RST_STREAM`.
@@ -95,13 +118,21 @@ impl Error {
         matches!(self.kind, Kind::Reset(..))
     }
 
+    /// Returns true if the connection failed because a keep-alive `PING` was
+    /// not acknowledged in time.
+    pub fn is_keep_alive_timeout(&self) -> bool {
+        matches!(self.kind, Kind::KeepAliveTimedOut)
+    }
+
     /// Returns true if the error was received in a frame from the remote.
     ///
     /// Such as from a received `RST_STREAM` or `GOAWAY` frame.
     pub fn is_remote(&self) -> bool {
         matches!(
             self.kind,
-            Kind::GoAway(_, _, Initiator::Remote) | Kind::Reset(_, _, Initiator::Remote)
+            Kind::GoAway(_, _, Initiator::Remote)
+                | Kind::Unprocessed(..)
+                | Kind::Reset(_, _, Initiator::Remote)
         )
     }
 
@@ -126,9 +157,11 @@ impl From<proto::Error> for Error {
                 GoAway(debug_data, reason, initiator) => {
                     Kind::GoAway(debug_data, reason, initiator)
                 }
+                Unprocessed(debug_data, reason) => Kind::Unprocessed(debug_data, reason),
                 Io(kind, inner) => {
                     Kind::Io(inner.map_or_else(|| kind.into(), |inner| io::Error::new(kind, inner)))
                 }
+                KeepAliveTimedOut => Kind::KeepAliveTimedOut,
             },
         }
     }
@@ -179,13 +212,15 @@ impl fmt::Display for Error {
                 write!(fmt, "connection error detected: {}", reason)?;
                 debug_data
             }
-            Kind::GoAway(ref debug_data, reason, Initiator::Remote) => {
+            Kind::GoAway(ref debug_data, reason, Initiator::Remote)
+            | Kind::Unprocessed(ref debug_data, reason) => {
                 write!(fmt, "connection error received: {}", reason)?;
                 debug_data
             }
             Kind::Reason(reason) => return write!(fmt, "protocol error: {}", reason),
             Kind::User(ref e) => return write!(fmt, "user error: {}", e),
             Kind::Io(ref e) => return e.fmt(fmt),
+            Kind::KeepAliveTimedOut => return fmt.write_str("keep-alive timed out"),
         };
 
         if !debug_data.is_empty() {
@@ -1,10 +1,14 @@
 //! Extensions specific to the HTTP/2 protocol.
 
+use crate::frame;
 use crate::hpack::BytesStr;
 
 use bytes::Bytes;
-use http::{uri, Method};
+use http::header::{HeaderName, HeaderValue};
+use http::{uri, HeaderMap, Method};
+use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
 
 /// Represents the `:protocol` pseudo-header used by
 /// the [Extended CONNECT Protocol].
@@ -55,6 +59,153 @@ impl fmt::Debug for Protocol {
     }
 }
 
+/// Stream priority information as defined in [RFC 7540 section 5.3].
+///
+/// This is carried by `PRIORITY` frames and by `HEADERS` frames that have the
+/// `PRIORITY` flag set.
+///
+/// When inserted into the extensions of a client request, the request's
+/// `HEADERS` frame is sent with the `PRIORITY` flag set and this dependency
+/// encoded in front of the header block.
+///
+/// ```
+/// # use h2::ext::StreamDependency;
+/// let mut request = http::Request::builder()
+///     .uri("https://example.com/")
+///     .body(())
+///     .unwrap();
+///
+/// request
+///     .extensions_mut()
+///     .insert(StreamDependency::new(0, 256, true));
+/// ```
+///
+/// [RFC 7540 section 5.3]: https://httpwg.org/specs/rfc7540.html#StreamPriority
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StreamDependency {
+    dependency_id: u32,
+    weight: u16,
+    is_exclusive: bool,
+}
+
+impl StreamDependency {
+    /// Creates a dependency on the stream `dependency_id`.
+    ///
+    /// `weight` is the actual weight of the stream, between 1 and 256.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `weight` is out of range or if
+    /// `dependency_id` is not a valid stream identifier.
+    pub fn new(dependency_id: u32, weight: u16, is_exclusive: bool) -> Self {
+        assert!((1..=256).contains(&weight), "weight must be in 1..=256");
+        assert_eq!(dependency_id >> 31, 0, "invalid stream ID -- MSB is set");
+
+        StreamDependency {
+            dependency_id,
+            weight,
+            is_exclusive,
+        }
+    }
+
+    /// Returns the identifier of the stream this stream depends on.
+    pub fn dependency_id(&self) -> u32 {
+        self.dependency_id
+    }
+
+    /// Returns the weight of the stream, between 1 and 256.
+    pub fn weight(&self) -> u16 {
+        self.weight
+    }
+
+    /// Returns `true` if the dependency is exclusive.
+    pub fn is_exclusive(&self) -> bool {
+        self.is_exclusive
+    }
+
+    pub(crate) fn into_frame(self) -> frame::StreamDependency {
+        frame::StreamDependency::new(
+            self.dependency_id.into(),
+            (self.weight - 1) as u8,
+            self.is_exclusive,
+        )
+    }
+}
+
+/// A request pseudo-header field.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum PseudoHeader {
+    /// The `:method` pseudo header.
+    Method,
+    /// The `:scheme` pseudo header.
+    Scheme,
+    /// The `:authority` pseudo header.
+    Authority,
+    /// The `:path` pseudo header.
+    Path,
+    /// The `:protocol` pseudo header.
+    Protocol,
+}
+
+/// The order in which request pseudo headers are encoded in a HEADERS block.
+///
+/// Pseudo headers that are not listed are appended after the listed ones, in
+/// the default order. Pseudo headers that are absent from a request are
+/// skipped.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PseudoHeaderOrder {
+    order: [PseudoHeader; 5],
+}
+
+impl PseudoHeaderOrder {
+    const DEFAULT: [PseudoHeader; 5] = [
+        PseudoHeader::Method,
+        PseudoHeader::Scheme,
+        PseudoHeader::Authority,
+        PseudoHeader::Path,
+        PseudoHeader::Protocol,
+    ];
+
+    /// Creates an order from a sequence of pseudo headers.
+    ///
+    /// Repeated entries are ignored.
+    pub fn new<I>(order: I) -> Self
+    where
+        I: IntoIterator<Item = PseudoHeader>,
+    {
+        let mut dst = Self::DEFAULT;
+        let mut len = 0;
+
+        for field in order.into_iter().chain(Self::DEFAULT) {
+            if len == dst.len() {
+                break;
+            }
+
+            if !dst[..len].contains(&field) {
+                dst[len] = field;
+                len += 1;
+            }
+        }
+
+        PseudoHeaderOrder { order: dst }
+    }
+
+    /// Returns the pseudo headers in encoding order.
+    pub fn as_slice(&self) -> &[PseudoHeader] {
+        &self.order
+    }
+}
+
+impl Default for PseudoHeaderOrder {
+    /// Returns the order `:method`, `:scheme`, `:authority`, `:path`,
+    /// `:protocol`.
+    fn default() -> Self {
+        PseudoHeaderOrder {
+            order: Self::DEFAULT,
+        }
+    }
+}
+
 /// Allows overriding the request pseudo headers before a `Request` is encoded.
 #[derive(Clone, Debug, Default)]
 pub struct PseudoHeadersOverride {
@@ -63,6 +214,7 @@ pub struct PseudoHeadersOverride {
     pub(crate) authority: Option<BytesStr>,
     pub(crate) path: Option<BytesStr>,
     pub(crate) protocol: Option<Protocol>,
+    pub(crate) order: Option<PseudoHeaderOrder>,
 }
 
 impl PseudoHeadersOverride {
@@ -112,4 +264,504 @@ impl PseudoHeadersOverride {
         self.protocol = Some(protocol);
         self
     }
+
+    /// Overrides the order in which the pseudo headers are encoded.
+    ///
+    /// This takes precedence over the connection-wide order configured with
+    /// [`client::Builder::pseudo_header_order`].
+    ///
+    /// [`client::Builder::pseudo_header_order`]: ../client/struct.Builder.html#method.pseudo_header_order
+    pub fn set_order(mut self, order: PseudoHeaderOrder) -> Self {
+        self.order = Some(order);
+        self
+    }
+}
+
+/// An ordered list of header fields to send with a request.
+///
+/// When inserted into the extensions of a client request, these fields are
+/// sent instead of the request's `HeaderMap`, in exactly the given order.
+/// Unlike a `HeaderMap`, which groups values by name, the list may contain
+/// repeated names interleaved with other names.
+///
+/// HTTP/2 requires header field names to be lowercase, so names are always
+/// sent as stored in `HeaderName`.
+///
+/// ```
+/// # use h2::ext::OrderedHeaders;
+/// # use http::header::{ACCEPT, COOKIE};
+/// # use http::HeaderValue;
+/// let mut headers = OrderedHeaders::new();
+/// headers.push(COOKIE, HeaderValue::from_static("a=1"));
+/// headers.push(ACCEPT, HeaderValue::from_static("*/*"));
+/// headers.push(COOKIE, HeaderValue::from_static("b=2"));
+///
+/// let mut request = http::Request::builder()
+///     .uri("https://example.com/")
+///     .body(())
+///     .unwrap();
+///
+/// request.extensions_mut().insert(headers);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OrderedHeaders {
+    fields: Vec<(HeaderName, HeaderValue)>,
+}
+
+impl OrderedHeaders {
+    /// Creates an empty header list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a header field to the end of the list.
+    pub fn push(&mut self, name: HeaderName, value: HeaderValue) {
+        self.fields.push((name, value));
+    }
+
+    /// Returns an iterator over the header fields, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&HeaderName, &HeaderValue)> {
+        self.fields.iter().map(|(name, value)| (name, value))
+    }
+
+    /// Returns the number of header fields in the list.
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Returns `true` if the list contains no header fields.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub(crate) fn to_header_map(&self) -> HeaderMap {
+        let mut map = HeaderMap::with_capacity(self.fields.len());
+        for (name, value) in &self.fields {
+            map.append(name.clone(), value.clone());
+        }
+        map
+    }
+
+    pub(crate) fn into_vec(self) -> Vec<(HeaderName, HeaderValue)> {
+        self.fields
+    }
+}
+
+impl FromIterator<(HeaderName, HeaderValue)> for OrderedHeaders {
+    fn from_iter<I: IntoIterator<Item = (HeaderName, HeaderValue)>>(iter: I) -> Self {
+        OrderedHeaders {
+            fields: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<(HeaderName, HeaderValue)> for OrderedHeaders {
+    fn extend<I: IntoIterator<Item = (HeaderName, HeaderValue)>>(&mut self, iter: I) {
+        self.fields.extend(iter)
+    }
+}
+
+/// The HPACK representation of a header field.
+///
+/// See [RFC 7541 section 6.2] for the details of each representation.
+///
+/// [RFC 7541 section 6.2]: https://httpwg.org/specs/rfc7541.html#literal.header.representation
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum HpackIndexing {
+    /// Literal header field with incremental indexing.
+    ///
+    /// The field is added to the dynamic table, so later occurrences can be
+    /// sent as an index. A field that is already in the dynamic table is
+    /// sent as an index right away.
+    Incremental,
+    /// Literal header field without indexing.
+    WithoutIndexing,
+    /// Literal header field never indexed.
+    ///
+    /// Intermediaries must not add the field to a dynamic table either when
+    /// forwarding it, so this is meant for sensitive values.
+    NeverIndexed,
+}
+
+/// How a header field is encoded by HPACK.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct HeaderIndexing {
+    indexing: HpackIndexing,
+    static_name: bool,
+}
+
+impl HeaderIndexing {
+    /// Encodes the field with the given representation.
+    ///
+    /// The name is taken from the static table when it is found there.
+    pub fn new(indexing: HpackIndexing) -> Self {
+        HeaderIndexing {
+            indexing,
+            static_name: true,
+        }
+    }
+
+    /// Sets whether the name is taken from the static table when it is found
+    /// there. Otherwise, the name is sent as a literal.
+    pub fn static_name(mut self, enabled: bool) -> Self {
+        self.static_name = enabled;
+        self
+    }
+
+    /// Returns the HPACK representation of the field.
+    pub fn indexing(&self) -> HpackIndexing {
+        self.indexing
+    }
+
+    /// Returns whether the name is taken from the static table.
+    pub fn is_static_name(&self) -> bool {
+        self.static_name
+    }
+}
+
+/// Allows choosing the HPACK representation of request header fields.
+///
+/// When inserted into the extensions of a client request, every value of
+/// the listed header names is encoded with the given [`HeaderIndexing`].
+/// Other fields are encoded as usual.
+///
+/// Values marked as sensitive with `HeaderValue::set_sensitive` are never
+/// added to the dynamic table, even with [`HpackIndexing::Incremental`].
+///
+/// ```
+/// # use h2::ext::{HeaderIndexing, HeaderIndexingOverride, HpackIndexing};
+/// # use http::header::{AUTHORIZATION, USER_AGENT};
+/// let indexing = HeaderIndexingOverride::new()
+///     .set(AUTHORIZATION, HeaderIndexing::new(HpackIndexing::NeverIndexed))
+///     .set(
+///         USER_AGENT,
+///         HeaderIndexing::new(HpackIndexing::Incremental).static_name(false),
+///     );
+///
+/// let mut request = http::Request::builder()
+///     .uri("https://example.com/")
+///     .body(())
+///     .unwrap();
+///
+/// request.extensions_mut().insert(indexing);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HeaderIndexingOverride {
+    fields: Vec<(HeaderName, HeaderIndexing)>,
+}
+
+impl HeaderIndexingOverride {
+    /// Creates an empty override set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the HPACK representation of the header field `name`.
+    pub fn set(mut self, name: HeaderName, indexing: HeaderIndexing) -> Self {
+        match self.fields.iter_mut().find(|(n, _)| *n == name) {
+            Some(field) => field.1 = indexing,
+            None => self.fields.push((name, indexing)),
+        }
+        self
+    }
+
+    /// Returns the HPACK representation set for the header field `name`.
+    pub fn get(&self, name: &HeaderName) -> Option<HeaderIndexing> {
+        self.fields
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|&(_, indexing)| indexing)
+    }
+}
+
+/// The priority parameters of a stream, as defined in [RFC 9218].
+///
+/// A client signals the priority of a request with the `priority` request
+/// header and with `PRIORITY_UPDATE` frames. A server uses it to decide
+/// which responses to send first: streams with a lower urgency are served
+/// first, and streams of the same urgency that are not incremental are
+/// served one at a time rather than interleaved.
+///
+/// ```
+/// # use h2::ext::Priority;
+/// let priority = Priority::parse(b"u=1, i");
+/// assert_eq!(priority.urgency(), 1);
+/// assert!(priority.is_incremental());
+/// assert_eq!(priority.to_string(), "u=1, i");
+///
+/// assert_eq!(Priority::parse(b"not a dictionary"), Priority::default());
+/// ```
+///
+/// [RFC 9218]: https://www.rfc-editor.org/rfc/rfc9218.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Priority {
+    urgency: u8,
+    incremental: bool,
+}
+
+impl Priority {
+    const DEFAULT_URGENCY: u8 = 3;
+
+    /// Creates priority parameters.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `urgency` is greater than 7.
+    pub fn new(urgency: u8, incremental: bool) -> Self {
+        assert!(urgency <= 7, "urgency must be in 0..=7");
+
+        Priority {
+            urgency,
+            incremental,
+        }
+    }
+
+    /// Parses the value of a `priority` header or `PRIORITY_UPDATE` frame.
+    ///
+    /// Parameters that are missing or invalid keep their default value, as
+    /// do unknown parameters.
+    pub fn parse(value: &[u8]) -> Self {
+        let mut priority = Priority::default();
+
+        for member in value.split(|&b| b == b',') {
+            // Parameters of dictionary members are not used.
+            let member = member.split(|&b| b == b';').next().unwrap_or(member);
+            let member = trim_ows(member);
+
+            let (key, value) = match member.iter().position(|&b| b == b'=') {
+                Some(i) => (&member[..i], Some(&member[i + 1..])),
+                None => (member, None),
+            };
+
+            match (key, value) {
+                (b"u", Some(value)) => {
+                    let urgency = std::str::from_utf8(value)
+                        .ok()
+                        .and_then(|value| value.parse::<u8>().ok())
+                        .filter(|&urgency| urgency <= 7);
+
+                    if let Some(urgency) = urgency {
+                        priority.urgency = urgency;
+                    }
+                }
+                (b"i", None) | (b"i", Some(b"?1")) => priority.incremental = true,
+                (b"i", Some(b"?0")) => priority.incremental = false,
+                _ => {}
+            }
+        }
+
+        priority
+    }
+
+    /// Returns the urgency, from 0 (highest) to 7 (lowest).
+    pub fn urgency(&self) -> u8 {
+        self.urgency
+    }
+
+    /// Returns `true` if the response can be processed incrementally.
+    pub fn is_incremental(&self) -> bool {
+        self.incremental
+    }
+}
+
+impl Default for Priority {
+    /// Returns the default priority, an urgency of 3 without incremental
+    /// processing.
+    fn default() -> Self {
+        Priority {
+            urgency: Priority::DEFAULT_URGENCY,
+            incremental: false,
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    /// Formats the parameters as a structured field dictionary, leaving out
+    /// default values.
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.urgency != Priority::DEFAULT_URGENCY {
+            write!(fmt, "u={}", self.urgency)?;
+
+            if self.incremental {
+                fmt.write_str(", ")?;
+            }
+        }
+
+        if self.incremental {
+            fmt.write_str("i")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Priority> for HeaderValue {
+    fn from(src: Priority) -> Self {
+        HeaderValue::try_from(src.to_string()).expect("priority is a valid header value")
+    }
+}
+
+/// A frame of a type that is not defined by this crate, such as the frames
+/// of protocol extensions.
+///
+/// Extension frames are received by the handlers registered with
+/// [`client::Builder::extension_frame_handler`] and
+/// [`server::Builder::extension_frame_handler`], or with
+/// [`RecvStream::poll_extension_frame`] for the frames received on open
+/// streams.
+///
+/// [`client::Builder::extension_frame_handler`]: crate::client::Builder::extension_frame_handler
+/// [`server::Builder::extension_frame_handler`]: crate::server::Builder::extension_frame_handler
+/// [`RecvStream::poll_extension_frame`]: crate::RecvStream::poll_extension_frame
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExtensionFrame {
+    inner: frame::Extension,
+}
+
+impl ExtensionFrame {
+    pub(crate) fn new(inner: frame::Extension) -> Self {
+        ExtensionFrame { inner }
+    }
+
+    /// Returns the frame type.
+    pub fn kind(&self) -> u8 {
+        self.inner.kind()
+    }
+
+    /// Returns the flags octet.
+    pub fn flags(&self) -> u8 {
+        self.inner.flags()
+    }
+
+    /// Returns the identifier of the stream the frame was received on, which
+    /// is zero for frames that apply to the connection.
+    pub fn stream_id(&self) -> crate::StreamId {
+        crate::StreamId::from_internal(self.inner.stream_id())
+    }
+
+    /// Returns the payload.
+    pub fn payload(&self) -> &Bytes {
+        self.inner.payload()
+    }
+
+    /// Consumes the frame, returning the payload.
+    pub fn into_payload(self) -> Bytes {
+        self.inner.into_payload()
+    }
+}
+
+type ExtensionFrameHandler = Arc<dyn Fn(ExtensionFrame) + Send + Sync>;
+
+/// The handlers of received extension frames, by frame type.
+#[derive(Clone, Default)]
+pub(crate) struct ExtensionFrameHandlers {
+    handlers: HashMap<u8, ExtensionFrameHandler>,
+}
+
+impl ExtensionFrameHandlers {
+    /// Registers the handler of a frame type.
+    ///
+    /// # Panics
+    ///
+    /// If `kind` is a frame type defined by this crate.
+    pub(crate) fn insert<F>(&mut self, kind: u8, handler: F)
+    where
+        F: Fn(ExtensionFrame) + Send + Sync + 'static,
+    {
+        assert_eq!(
+            frame::Kind::new(kind),
+            frame::Kind::Unknown,
+            "frame type {:#x} is not an extension frame type",
+            kind
+        );
+        self.handlers.insert(kind, Arc::new(handler));
+    }
+
+    pub(crate) fn contains(&self, kind: u8) -> bool {
+        self.handlers.contains_key(&kind)
+    }
+
+    pub(crate) fn call(&self, frame: frame::Extension) {
+        if let Some(handler) = self.handlers.get(&frame.kind()) {
+            handler(ExtensionFrame::new(frame));
+        }
+    }
+}
+
+impl fmt::Debug for ExtensionFrameHandlers {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_set().entries(self.handlers.keys()).finish()
+    }
+}
+
+/// Alternative services advertised by a server with an `ALTSVC` frame, as
+/// defined in [RFC 7838].
+///
+/// Received with [`client::Connection::poll_altsvc`], and sent with
+/// [`server::Connection::send_altsvc`].
+///
+/// [RFC 7838]: https://www.rfc-editor.org/rfc/rfc7838.html#section-4
+/// [`client::Connection::poll_altsvc`]: crate::client::Connection::poll_altsvc
+/// [`server::Connection::send_altsvc`]: crate::server::Connection::send_altsvc
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AltSvc {
+    inner: frame::AltSvc,
+}
+
+impl AltSvc {
+    pub(crate) fn new(inner: frame::AltSvc) -> Self {
+        AltSvc { inner }
+    }
+
+    /// Returns the identifier of the stream the frame was received on, which
+    /// is zero for frames that apply to the connection.
+    pub fn stream_id(&self) -> crate::StreamId {
+        crate::StreamId::from_internal(self.inner.stream_id())
+    }
+
+    /// Returns the origin the services apply to.
+    ///
+    /// The origin is empty for frames received on a stream other than stream
+    /// 0, whose services apply to the origin of the request of the stream.
+    pub fn origin(&self) -> &Bytes {
+        self.inner.origin()
+    }
+
+    /// Returns the `Alt-Svc` field value, listing the alternative services.
+    pub fn field_value(&self) -> &Bytes {
+        self.inner.field_value()
+    }
+}
+
+fn trim_ows(mut src: &[u8]) -> &[u8] {
+    while let [b' ' | b'\t', rest @ ..] = src {
+        src = rest;
+    }
+    while let [rest @ .., b' ' | b'\t'] = src {
+        src = rest;
+    }
+    src
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Priority;
+
+    #[test]
+    fn parse_priority() {
+        assert_eq!(Priority::parse(b""), Priority::default());
+        assert_eq!(Priority::parse(b"u=0"), Priority::new(0, false));
+        assert_eq!(Priority::parse(b"i"), Priority::new(3, true));
+        assert_eq!(Priority::parse(b"u=5, i=?0"), Priority::new(5, false));
+        assert_eq!(Priority::parse(b"i=?1,\tu=6;x=1"), Priority::new(6, true));
+        assert_eq!(Priority::parse(b"u=2, u=4"), Priority::new(4, false));
+        assert_eq!(Priority::parse(b"u=8, i=1, v"), Priority::default());
+    }
+
+    #[test]
+    fn format_priority() {
+        assert_eq!(Priority::default().to_string(), "");
+        assert_eq!(Priority::new(3, true).to_string(), "i");
+        assert_eq!(Priority::new(0, false).to_string(), "u=0");
+    }
 }
@@ -0,0 +1,152 @@
+use crate::frame::{self, Error, Head, Kind, StreamId};
+
+use bytes::{BufMut, Bytes};
+
+/// An `ALTSVC` frame, advertising alternative services as defined in
+/// [RFC 7838].
+///
+/// On stream 0, the frame names the origin the services apply to. On any
+/// other stream, it applies to the origin of the stream, and has no origin.
+///
+/// [RFC 7838]: https://www.rfc-editor.org/rfc/rfc7838.html#section-4
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AltSvc {
+    stream_id: StreamId,
+    origin: Bytes,
+    field_value: Bytes,
+}
+
+impl AltSvc {
+    /// Creates an `ALTSVC` frame for `origin` on stream 0.
+    pub fn new(origin: Bytes, field_value: Bytes) -> AltSvc {
+        debug_assert!(!origin.is_empty() && origin.len() <= u16::MAX as usize);
+
+        AltSvc {
+            stream_id: StreamId::zero(),
+            origin,
+            field_value,
+        }
+    }
+
+    /// Creates an `ALTSVC` frame for the origin of the stream `stream_id`.
+    #[cfg(any(feature = "unstable", feature = "codec"))]
+    pub fn for_stream(stream_id: StreamId, field_value: Bytes) -> AltSvc {
+        debug_assert!(!stream_id.is_zero());
+
+        AltSvc {
+            stream_id,
+            origin: Bytes::new(),
+            field_value,
+        }
+    }
+
+    /// Returns the stream identifier.
+    pub fn stream_id(&self) -> StreamId {
+        self.stream_id
+    }
+
+    /// Returns the origin, which is empty for frames sent on a stream other
+    /// than stream 0.
+    pub fn origin(&self) -> &Bytes {
+        &self.origin
+    }
+
+    /// Returns the `Alt-Svc` field value.
+    pub fn field_value(&self) -> &Bytes {
+        &self.field_value
+    }
+
+    /// Returns the length of the payload, in octets.
+    pub fn payload_len(&self) -> usize {
+        2 + self.origin.len() + self.field_value.len()
+    }
+
+    /// Builds an `AltSvc` frame from a raw frame.
+    ///
+    /// RFC 7838 requires invalid frames to be ignored, rather than treated as
+    /// errors.
+    pub fn load(head: Head, payload: &[u8]) -> Result<AltSvc, Error> {
+        debug_assert_eq!(head.kind(), Kind::AltSvc);
+
+        if payload.len() < 2 {
+            return Err(Error::BadFrameSize);
+        }
+
+        let origin_len = ((payload[0] as usize) << 8) | payload[1] as usize;
+        let payload = &payload[2..];
+
+        if payload.len() < origin_len {
+            return Err(Error::BadFrameSize);
+        }
+
+        // An origin is required on stream 0, and forbidden on other streams.
+        if head.stream_id().is_zero() == (origin_len == 0) {
+            return Err(Error::InvalidStreamId);
+        }
+
+        Ok(AltSvc {
+            stream_id: head.stream_id(),
+            origin: Bytes::copy_from_slice(&payload[..origin_len]),
+            field_value: Bytes::copy_from_slice(&payload[origin_len..]),
+        })
+    }
+
+    /// Writes the frame, header included.
+    pub fn encode<B: BufMut>(&self, dst: &mut B) {
+        tracing::trace!("encoding ALTSVC; stream_id={:?}", self.stream_id);
+        let head = Head::new(Kind::AltSvc, 0, self.stream_id);
+        head.encode(self.payload_len(), dst);
+        dst.put_u16(self.origin.len() as u16);
+        dst.put_slice(&self.origin);
+        dst.put_slice(&self.field_value);
+    }
+}
+
+impl<B> From<AltSvc> for frame::Frame<B> {
+    fn from(src: AltSvc) -> Self {
+        frame::Frame::AltSvc(src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let frame = AltSvc::new(
+            Bytes::from_static(b"https://example.com"),
+            Bytes::from_static(b"h3=\":443\""),
+        );
+
+        let mut buf = Vec::new();
+        frame.encode(&mut buf);
+        assert_eq!(&buf[..11], &[0, 0, 30, 0xa, 0, 0, 0, 0, 0, 0, 19]);
+
+        let head = Head::parse(&buf);
+        assert_eq!(AltSvc::load(head, &buf[9..]), Ok(frame));
+
+        let head = Head::new(Kind::AltSvc, 0, StreamId::from(1));
+        let frame = AltSvc::load(head, b"\0\0clear").unwrap();
+        assert!(frame.origin().is_empty());
+        assert_eq!(frame.field_value(), &b"clear"[..]);
+
+        let mut buf = Vec::new();
+        frame.encode(&mut buf);
+        assert_eq!(&buf[9..], b"\0\0clear");
+    }
+
+    #[test]
+    fn load_rejects_invalid_frames() {
+        let head = Head::new(Kind::AltSvc, 0, StreamId::zero());
+        assert_eq!(AltSvc::load(head, &[0]), Err(Error::BadFrameSize));
+        assert_eq!(AltSvc::load(head, &[0, 2, b'a']), Err(Error::BadFrameSize));
+        assert_eq!(AltSvc::load(head, &[0, 0]), Err(Error::InvalidStreamId));
+
+        let head = Head::new(Kind::AltSvc, 0, StreamId::from(1));
+        assert_eq!(
+            AltSvc::load(head, &[0, 1, b'a']),
+            Err(Error::InvalidStreamId)
+        );
+    }
+}
@@ -64,17 +64,24 @@ impl<T> Data<T> {
     }
 
     /// Returns whether the `PADDED` flag is set on this frame.
-    #[cfg(feature = "unstable")]
+    #[cfg(any(feature = "unstable", feature = "codec"))]
     pub fn is_padded(&self) -> bool {
         self.flags.is_padded()
     }
 
     /// Sets the value for the `PADDED` flag on this frame.
-    #[cfg(feature = "unstable")]
+    #[cfg(any(feature = "unstable", feature = "codec"))]
     pub fn set_padded(&mut self) {
         self.flags.set_padded();
     }
 
+    /// Sets the `PADDED` flag, and pads the frame with `pad_len` octets when
+    /// it is encoded.
+    pub fn set_pad_len(&mut self, pad_len: u8) {
+        self.flags.set_padded();
+        self.pad_len = Some(pad_len);
+    }
+
     /// Returns a reference to this frame's payload.
     ///
     /// This does **not** include any padding that might have been originally
@@ -99,6 +106,11 @@ impl<T> Data<T> {
         self.data
     }
 
+    /// If this frame is PADDED, it returns the pad len + 1 (length field).
+    pub(crate) fn padded_len(&self) -> Option<u32> {
+        self.pad_len.map(|n| u32::from(n) + 1)
+    }
+
     pub(crate) fn head(&self) -> Head {
         Head::new(Kind::Data, self.flags.into(), self.stream_id)
     }
@@ -148,11 +160,6 @@ impl Data<Bytes> {
             self.data.len()
         }
     }
-
-    /// If this frame is PADDED, it returns the pad len + 1 (length field).
-    pub(crate) fn padded_len(&self) -> Option<u8> {
-        self.pad_len.map(|n| n + 1)
-    }
 }
 
 impl<T: Buf> Data<T> {
@@ -164,10 +171,24 @@ impl<T: Buf> Data<T> {
     pub(crate) fn encode_chunk<U: BufMut>(&mut self, dst: &mut U) {
         let len = self.data.remaining();
 
-        assert!(dst.remaining_mut() >= len);
-
-        self.head().encode(len, dst);
-        dst.put(&mut self.data);
+        match self.pad_len {
+            Some(pad_len) => {
+                // The pad length field is part of the payload.
+                let payload_len = len + usize::from(pad_len) + 1;
+                assert!(dst.remaining_mut() >= payload_len);
+
+                self.head().encode(payload_len, dst);
+                dst.put_u8(pad_len);
+                dst.put(&mut self.data);
+                dst.put_bytes(0, usize::from(pad_len));
+            }
+            None => {
+                assert!(dst.remaining_mut() >= len);
+
+                self.head().encode(len, dst);
+                dst.put(&mut self.data);
+            }
+        }
     }
 }
 
@@ -219,7 +240,6 @@ impl DataFlags {
         self.0 & PADDED == PADDED
     }
 
-    #[cfg(feature = "unstable")]
     fn set_padded(&mut self) {
         self.0 |= PADDED
     }
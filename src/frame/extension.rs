@@ -0,0 +1,111 @@
+use crate::frame::{self, Head, Kind, StreamId};
+
+use bytes::{BufMut, Bytes};
+
+/// A frame of a type that is not defined by this crate, such as the frames
+/// of protocol extensions.
+///
+/// The payload is not interpreted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Extension {
+    kind: u8,
+    flags: u8,
+    stream_id: StreamId,
+    payload: Bytes,
+}
+
+impl Extension {
+    /// Creates a frame of type `kind`.
+    pub fn new(kind: u8, flags: u8, stream_id: StreamId, payload: Bytes) -> Extension {
+        debug_assert_eq!(Kind::new(kind), Kind::Unknown);
+
+        Extension {
+            kind,
+            flags,
+            stream_id,
+            payload,
+        }
+    }
+
+    /// Returns the frame type.
+    pub fn kind(&self) -> u8 {
+        self.kind
+    }
+
+    /// Returns the flags octet.
+    pub fn flags(&self) -> u8 {
+        self.flags
+    }
+
+    /// Returns the stream identifier, which is zero for frames that apply
+    /// to the connection.
+    pub fn stream_id(&self) -> StreamId {
+        self.stream_id
+    }
+
+    /// Returns the payload.
+    pub fn payload(&self) -> &Bytes {
+        &self.payload
+    }
+
+    /// Consumes the frame, returning the payload.
+    pub fn into_payload(self) -> Bytes {
+        self.payload
+    }
+
+    /// Builds an `Extension` frame from a raw frame, header included, as
+    /// `Head` does not keep the type of unknown frames. The payload shares the
+    /// buffer of `frame`.
+    pub fn load(frame: Bytes) -> Extension {
+        let head = Head::parse(&frame);
+        debug_assert_eq!(head.kind(), Kind::Unknown);
+
+        Extension {
+            kind: frame[3],
+            flags: head.flag(),
+            stream_id: head.stream_id(),
+            payload: frame.slice(frame::HEADER_LEN..),
+        }
+    }
+
+    /// Writes the frame, header included.
+    pub fn encode<B: BufMut>(&self, dst: &mut B) {
+        tracing::trace!(
+            "encoding extension frame; kind={:#x} stream_id={:?}",
+            self.kind,
+            self.stream_id
+        );
+        // `Head` only knows about the frame types defined by this crate.
+        dst.put_uint(self.payload.len() as u64, 3);
+        dst.put_u8(self.kind);
+        dst.put_u8(self.flags);
+        dst.put_u32(self.stream_id.into());
+        dst.put_slice(&self.payload);
+    }
+}
+
+impl<B> From<Extension> for frame::Frame<B> {
+    fn from(src: Extension) -> Self {
+        frame::Frame::Extension(src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let frame = Extension::new(0xf0, 0x1, StreamId::from(3), Bytes::from_static(b"alt"));
+
+        let mut buf = Vec::new();
+        frame.encode(&mut buf);
+        assert_eq!(&buf[..9], &[0, 0, 3, 0xf0, 0x1, 0, 0, 0, 3]);
+
+        let buf = Bytes::from(buf);
+        let loaded = Extension::load(buf.clone());
+        assert_eq!(loaded, frame);
+        // The payload is not copied.
+        assert_eq!(loaded.payload().as_ptr(), buf[9..].as_ptr());
+    }
+}
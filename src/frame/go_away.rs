@@ -4,6 +4,7 @@ use bytes::{BufMut, Bytes};
 
 use crate::frame::{self, Error, Head, Kind, Reason, StreamId};
 
+/// A `GOAWAY` frame, initiating the shutdown of a connection.
 #[derive(Clone, Eq, PartialEq)]
 pub struct GoAway {
     last_stream_id: StreamId,
@@ -12,6 +13,7 @@ pub struct GoAway {
 }
 
 impl GoAway {
+    /// Creates a `GOAWAY` frame without debug data.
     pub fn new(last_stream_id: StreamId, reason: Reason) -> Self {
         GoAway {
             last_stream_id,
@@ -20,6 +22,7 @@ impl GoAway {
         }
     }
 
+    /// Creates a `GOAWAY` frame carrying opaque debug data.
     pub fn with_debug_data(last_stream_id: StreamId, reason: Reason, debug_data: Bytes) -> Self {
         Self {
             last_stream_id,
@@ -28,18 +31,22 @@ impl GoAway {
         }
     }
 
+    /// Returns the identifier of the last stream the sender may process.
     pub fn last_stream_id(&self) -> StreamId {
         self.last_stream_id
     }
 
+    /// Returns the error code.
     pub fn reason(&self) -> Reason {
         self.error_code
     }
 
+    /// Returns the debug data.
     pub fn debug_data(&self) -> &Bytes {
         &self.debug_data
     }
 
+    /// Builds a `GoAway` frame from the payload of a raw frame.
     pub fn load(payload: &[u8]) -> Result<GoAway, Error> {
         if payload.len() < 8 {
             return Err(Error::BadFrameSize);
@@ -56,6 +63,7 @@ impl GoAway {
         })
     }
 
+    /// Writes the frame, header included.
     pub fn encode<B: BufMut>(&self, dst: &mut B) {
         tracing::trace!("encoding GO_AWAY; code={:?}", self.error_code);
         let head = Head::new(Kind::GoAway, 0, StreamId::zero());
@@ -2,6 +2,7 @@ use super::StreamId;
 
 use bytes::BufMut;
 
+/// The 9 octet header common to all frames.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Head {
     kind: Kind,
@@ -9,25 +10,44 @@ pub struct Head {
     stream_id: StreamId,
 }
 
+/// The type of a frame.
 #[repr(u8)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Kind {
+    /// `DATA` (0x0)
     Data = 0,
+    /// `HEADERS` (0x1)
     Headers = 1,
+    /// `PRIORITY` (0x2)
     Priority = 2,
+    /// `RST_STREAM` (0x3)
     Reset = 3,
+    /// `SETTINGS` (0x4)
     Settings = 4,
+    /// `PUSH_PROMISE` (0x5)
     PushPromise = 5,
+    /// `PING` (0x6)
     Ping = 6,
+    /// `GOAWAY` (0x7)
     GoAway = 7,
+    /// `WINDOW_UPDATE` (0x8)
     WindowUpdate = 8,
+    /// `CONTINUATION` (0x9)
     Continuation = 9,
+    /// `ALTSVC` (0xa), defined by RFC 7838.
+    AltSvc = 0xa,
+    /// `ORIGIN` (0xc), defined by RFC 8336.
+    Origin = 0xc,
+    /// `PRIORITY_UPDATE` (0x10), defined by RFC 9218.
+    PriorityUpdate = 0x10,
+    /// Any frame type not known to this crate.
     Unknown,
 }
 
 // ===== impl Head =====
 
 impl Head {
+    /// Creates a frame header.
     pub fn new(kind: Kind, flag: u8, stream_id: StreamId) -> Head {
         Head {
             kind,
@@ -47,22 +67,27 @@ impl Head {
         }
     }
 
+    /// Returns the stream identifier.
     pub fn stream_id(&self) -> StreamId {
         self.stream_id
     }
 
+    /// Returns the frame type.
     pub fn kind(&self) -> Kind {
         self.kind
     }
 
+    /// Returns the flags octet.
     pub fn flag(&self) -> u8 {
         self.flag
     }
 
+    /// Returns the encoded length of the header, in octets.
     pub fn encode_len(&self) -> usize {
         super::HEADER_LEN
     }
 
+    /// Writes the header of a frame with a payload of `payload_len` octets.
     pub fn encode<T: BufMut>(&self, payload_len: usize, dst: &mut T) {
         debug_assert!(self.encode_len() <= dst.remaining_mut());
 
@@ -76,6 +101,7 @@ impl Head {
 // ===== impl Kind =====
 
 impl Kind {
+    /// Returns the frame type identified by `byte`.
     pub fn new(byte: u8) -> Kind {
         match byte {
             0 => Kind::Data,
@@ -88,6 +114,9 @@ impl Kind {
             7 => Kind::GoAway,
             8 => Kind::WindowUpdate,
             9 => Kind::Continuation,
+            0xa => Kind::AltSvc,
+            0xc => Kind::Origin,
+            0x10 => Kind::PriorityUpdate,
             _ => Kind::Unknown,
         }
     }
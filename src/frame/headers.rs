@@ -1,7 +1,8 @@
 use super::{util, StreamDependency, StreamId};
-use crate::ext::Protocol;
-use crate::frame::{Error, Frame, Head, Kind};
+use crate::ext::{HeaderIndexingOverride, Protocol, PseudoHeader, PseudoHeaderOrder};
+use crate::frame::{Error, Frame, Head, Kind, HEADER_LEN};
 use crate::hpack::{self, BytesStr};
+use crate::padding::{Padder, Padding};
 
 use http::header::{self, HeaderName, HeaderValue};
 use http::{uri, HeaderMap, Method, Request, StatusCode, Uri};
@@ -29,11 +30,15 @@ pub struct Headers {
 
     /// The associated flags
     flags: HeadersFlag,
+
+    /// The padding policy overriding the one of the connection, if any.
+    padding: Option<Padding>,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub struct HeadersFlag(u8);
 
+/// A `PUSH_PROMISE` frame, reserving a stream for a server push.
 #[derive(Eq, PartialEq)]
 pub struct PushPromise {
     /// The ID of the stream with which this frame is associated.
@@ -47,11 +52,18 @@ pub struct PushPromise {
 
     /// The associated flags
     flags: PushPromiseFlag,
+
+    /// The padding policy overriding the one of the connection, if any.
+    padding: Option<Padding>,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub struct PushPromiseFlag(u8);
 
+/// The remainder of a header block that did not fit in a single frame.
+///
+/// Returned by the `encode` methods, and written with
+/// [`Continuation::encode`].
 #[derive(Debug)]
 pub struct Continuation {
     /// Stream ID of continuation frame
@@ -60,27 +72,54 @@ pub struct Continuation {
     header_block: EncodingHeaderBlock,
 }
 
-// TODO: These fields shouldn't be `pub`
-#[derive(Debug, Default, Eq, PartialEq)]
-pub struct Pseudo {
-    // Request
-    pub method: Option<Method>,
-    pub scheme: Option<BytesStr>,
-    pub authority: Option<BytesStr>,
-    pub path: Option<BytesStr>,
-    pub protocol: Option<Protocol>,
-
-    // Response
-    pub status: Option<StatusCode>,
+// The fields of `Pseudo` are only public with the `unstable` feature, and are
+// read through accessors otherwise.
+macro_rules! pseudo {
+    ($vis:vis) => {
+        /// The pseudo header fields of a header block.
+        #[derive(Debug, Default, Eq)]
+        pub struct Pseudo {
+            // Request
+            /// The `:method` pseudo header.
+            $vis method: Option<Method>,
+            /// The `:scheme` pseudo header.
+            $vis scheme: Option<BytesStr>,
+            /// The `:authority` pseudo header.
+            $vis authority: Option<BytesStr>,
+            /// The `:path` pseudo header.
+            $vis path: Option<BytesStr>,
+            /// The `:protocol` pseudo header.
+            $vis protocol: Option<Protocol>,
+
+            // Response
+            /// The `:status` pseudo header.
+            $vis status: Option<StatusCode>,
+
+            /// The order in which the request pseudo headers are encoded.
+            $vis order: PseudoHeaderOrder,
+        }
+    };
 }
 
+#[cfg(feature = "unstable")]
+pseudo!(pub);
+
+#[cfg(not(feature = "unstable"))]
+pseudo!(pub(crate));
+
 #[derive(Debug)]
 pub struct Iter {
     /// Pseudo headers
     pseudo: Option<Pseudo>,
 
     /// Header fields
-    fields: header::IntoIter<HeaderValue>,
+    fields: Fields,
+}
+
+#[derive(Debug)]
+enum Fields {
+    Map(header::IntoIter<HeaderValue>),
+    Ordered(std::vec::IntoIter<(HeaderName, HeaderValue)>),
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -97,6 +136,13 @@ struct HeaderBlock {
     /// Pseudo headers, these are broken out as they must be sent as part of the
     /// headers frame.
     pseudo: Pseudo,
+
+    /// The header fields in the order they are encoded, if it differs from
+    /// the order of `fields`.
+    ordered_fields: Option<Vec<(HeaderName, HeaderValue)>>,
+
+    /// The HPACK representation of specific header fields.
+    indexing: Option<HeaderIndexingOverride>,
 }
 
 #[derive(Debug)]
@@ -123,11 +169,15 @@ impl Headers {
                 fields,
                 is_over_size: false,
                 pseudo,
+                ordered_fields: None,
+                indexing: None,
             },
             flags: HeadersFlag::default(),
+            padding: None,
         }
     }
 
+    /// Create a new HEADERS frame carrying trailers, which ends the stream
     pub fn trailers(stream_id: StreamId, fields: HeaderMap) -> Self {
         let mut flags = HeadersFlag::default();
         flags.set_end_stream();
@@ -140,8 +190,11 @@ impl Headers {
                 fields,
                 is_over_size: false,
                 pseudo: Pseudo::default(),
+                ordered_fields: None,
+                indexing: None,
             },
             flags,
+            padding: None,
         }
     }
 
@@ -205,13 +258,18 @@ impl Headers {
                 field_size: 0,
                 is_over_size: false,
                 pseudo: Pseudo::default(),
+                ordered_fields: None,
+                indexing: None,
             },
             flags,
+            padding: None,
         };
 
         Ok((headers, src))
     }
 
+    /// Decodes the header block fragment returned by `load`, once it is
+    /// complete.
     pub fn load_hpack(
         &mut self,
         src: &mut BytesMut,
@@ -221,35 +279,67 @@ impl Headers {
         self.header_block.load(src, max_header_list_size, decoder)
     }
 
+    /// Returns the stream identifier.
     pub fn stream_id(&self) -> StreamId {
         self.stream_id
     }
 
+    /// Returns whether the `END_HEADERS` flag is set.
     pub fn is_end_headers(&self) -> bool {
         self.flags.is_end_headers()
     }
 
+    /// Sets the `END_HEADERS` flag.
     pub fn set_end_headers(&mut self) {
         self.flags.set_end_headers();
     }
 
+    /// Returns whether the `END_STREAM` flag is set.
     pub fn is_end_stream(&self) -> bool {
         self.flags.is_end_stream()
     }
 
+    /// Sets the `END_STREAM` flag.
     pub fn set_end_stream(&mut self) {
         self.flags.set_end_stream()
     }
 
+    /// Sets the exact order in which the header fields are encoded.
+    ///
+    /// Only fields that are also present in the `HeaderMap` are encoded.
+    pub fn set_ordered_fields(&mut self, fields: Vec<(HeaderName, HeaderValue)>) {
+        self.header_block.ordered_fields = Some(fields);
+    }
+
+    /// Sets the HPACK representation of specific header fields.
+    pub fn set_header_indexing(&mut self, indexing: HeaderIndexingOverride) {
+        self.header_block.indexing = Some(indexing);
+    }
+
+    /// Returns the stream dependency, if the `PRIORITY` flag is set.
+    pub fn stream_dependency(&self) -> Option<&StreamDependency> {
+        self.stream_dep.as_ref()
+    }
+
+    /// Sets the stream dependency, which also sets the `PRIORITY` flag.
+    pub fn set_stream_dependency(&mut self, dependency: StreamDependency) {
+        self.stream_dep = Some(dependency);
+        self.flags.set_priority();
+    }
+
+    /// Returns whether the decoded header list exceeded the maximum size,
+    /// in which case the fields are incomplete.
     pub fn is_over_size(&self) -> bool {
         self.header_block.is_over_size
     }
 
+    /// Consumes the frame, returning the pseudo headers and header fields.
     pub fn into_parts(self) -> (Pseudo, HeaderMap) {
         (self.header_block.pseudo, self.header_block.fields)
     }
 
-    #[cfg(feature = "unstable")]
+    /// Returns a mutable reference to the pseudo headers.
+    #[cfg(any(feature = "unstable", feature = "codec"))]
     pub fn pseudo_mut(&mut self) -> &mut Pseudo {
         &mut self.header_block.pseudo
     }
@@ -263,28 +353,66 @@ impl Headers {
         self.header_block.pseudo.is_informational()
     }
 
+    /// Returns the header fields.
     pub fn fields(&self) -> &HeaderMap {
         &self.header_block.fields
     }
 
+    /// Consumes the frame, returning the header fields.
     pub fn into_fields(self) -> HeaderMap {
         self.header_block.fields
     }
 
+    /// Sets the padding policy of the frame, overriding the one of the
+    /// connection.
+    pub(crate) fn set_padding(&mut self, padding: Padding) {
+        self.padding = Some(padding);
+    }
+
+    /// Encodes the frame into `dst`, which must have room for at least the
+    /// frame header. A header block that does not fit is returned as a
+    /// `Continuation` to write next.
+    #[cfg(any(test, feature = "unstable", feature = "codec"))]
     pub fn encode(
         self,
         encoder: &mut hpack::Encoder,
         dst: &mut EncodeBuf<'_>,
+    ) -> Option<Continuation> {
+        self.encode_with(encoder, dst, None)
+    }
+
+    /// Encodes the frame like `encode`, padded as decided by `padder`.
+    pub(crate) fn encode_padded(
+        self,
+        encoder: &mut hpack::Encoder,
+        dst: &mut EncodeBuf<'_>,
+        padder: &mut Padder,
+    ) -> Option<Continuation> {
+        self.encode_with(encoder, dst, Some(padder))
+    }
+
+    fn encode_with(
+        self,
+        encoder: &mut hpack::Encoder,
+        dst: &mut EncodeBuf<'_>,
+        padder: Option<&mut Padder>,
     ) -> Option<Continuation> {
         // At this point, the `is_end_headers` flag should always be set
         debug_assert!(self.flags.is_end_headers());
 
         // Get the HEADERS frame head
         let head = self.head();
+        let stream_dep = self.stream_dep;
+
+        let block = self.header_block.into_encoding(encoder);
+        let prefix_len = if stream_dep.is_some() { 5 } else { 0 };
+        let pad_len = block.pad_len(padder, self.padding, prefix_len, dst);
 
-        self.header_block
-            .into_encoding(encoder)
-            .encode(&head, dst, |_| {})
+        block.encode(&head, dst, pad_len, |dst| {
+            if let Some(ref dep) = stream_dep {
+                dep.encode(dst);
+            }
+        })
     }
 
     fn head(&self) -> Head {
@@ -323,6 +451,8 @@ impl fmt::Debug for Headers {
 #[derive(Debug, PartialEq, Eq)]
 pub struct ParseU64Error;
 
+/// Parses an unsigned integer made of at most 19 decimal digits, such as the
+/// value of a `content-length` header.
 pub fn parse_u64(src: &[u8]) -> Result<u64, ParseU64Error> {
     if src.len() > 19 {
         // At danger for overflow...
@@ -346,12 +476,17 @@ pub fn parse_u64(src: &[u8]) -> Result<u64, ParseU64Error> {
 // ===== impl PushPromise =====
 
 #[derive(Debug)]
+/// The reasons a request cannot be promised.
 pub enum PushPromiseHeaderError {
+    /// The request has a body.
     InvalidContentLength(Result<u64, ParseU64Error>),
+    /// The request method is not safe and cacheable.
     NotSafeAndCacheable,
 }
 
 impl PushPromise {
+    /// Creates a `PUSH_PROMISE` frame, promising `promised_id` on the
+    /// stream `stream_id`.
     pub fn new(
         stream_id: StreamId,
         promised_id: StreamId,
@@ -365,12 +500,19 @@ impl PushPromise {
                 fields,
                 is_over_size: false,
                 pseudo,
+                ordered_fields: None,
+                indexing: None,
             },
             promised_id,
             stream_id,
+            padding: None,
         }
     }
 
+    /// Checks that `req` may be promised, as required by [Section 8.2] of
+    /// RFC 7540.
+    ///
+    /// [Section 8.2]: https://httpwg.org/specs/rfc7540.html#PushRequests
     pub fn validate_request(req: &Request<()>) -> Result<(), PushPromiseHeaderError> {
         use PushPromiseHeaderError::*;
         // The spec has some requirements for promised request headers
@@ -399,11 +541,13 @@ impl PushPromise {
         method == Method::GET || method == Method::HEAD
     }
 
+    /// Returns the header fields.
     pub fn fields(&self) -> &HeaderMap {
         &self.header_block.fields
     }
 
-    #[cfg(feature = "unstable")]
+    /// Consumes the frame, returning the header fields.
+    #[cfg(any(feature = "unstable", feature = "codec"))]
     pub fn into_fields(self) -> HeaderMap {
         self.header_block.fields
     }
@@ -456,13 +600,18 @@ impl PushPromise {
                 field_size: 0,
                 is_over_size: false,
                 pseudo: Pseudo::default(),
+                ordered_fields: None,
+                indexing: None,
             },
             promised_id,
             stream_id: head.stream_id(),
+            padding: None,
         };
         Ok((frame, src))
     }
 
+    /// Decodes the header block fragment returned by `load`, once it is
+    /// complete.
     pub fn load_hpack(
         &mut self,
         src: &mut BytesMut,
@@ -472,30 +621,65 @@ impl PushPromise {
         self.header_block.load(src, max_header_list_size, decoder)
     }
 
+    /// Returns the stream identifier.
     pub fn stream_id(&self) -> StreamId {
         self.stream_id
     }
 
+    /// Returns the identifier of the promised stream.
     pub fn promised_id(&self) -> StreamId {
         self.promised_id
     }
 
+    /// Returns whether the `END_HEADERS` flag is set.
     pub fn is_end_headers(&self) -> bool {
         self.flags.is_end_headers()
     }
 
+    /// Sets the `END_HEADERS` flag.
     pub fn set_end_headers(&mut self) {
         self.flags.set_end_headers();
     }
 
+    /// Returns whether the decoded header list exceeded the maximum size,
+    /// in which case the fields are incomplete.
     pub fn is_over_size(&self) -> bool {
         self.header_block.is_over_size
     }
 
+    /// Sets the padding policy of the frame, overriding the one of the
+    /// connection.
+    pub(crate) fn set_padding(&mut self, padding: Padding) {
+        self.padding = Some(padding);
+    }
+
+    /// Encodes the frame into `dst`, which must have room for at least the
+    /// frame header. A header block that does not fit is returned as a
+    /// `Continuation` to write next.
+    #[cfg(any(feature = "unstable", feature = "codec"))]
     pub fn encode(
         self,
         encoder: &mut hpack::Encoder,
         dst: &mut EncodeBuf<'_>,
+    ) -> Option<Continuation> {
+        self.encode_with(encoder, dst, None)
+    }
+
+    /// Encodes the frame like `encode`, padded as decided by `padder`.
+    pub(crate) fn encode_padded(
+        self,
+        encoder: &mut hpack::Encoder,
+        dst: &mut EncodeBuf<'_>,
+        padder: &mut Padder,
+    ) -> Option<Continuation> {
+        self.encode_with(encoder, dst, Some(padder))
+    }
+
+    fn encode_with(
+        self,
+        encoder: &mut hpack::Encoder,
+        dst: &mut EncodeBuf<'_>,
+        padder: Option<&mut Padder>,
     ) -> Option<Continuation> {
         // At this point, the `is_end_headers` flag should always be set
         debug_assert!(self.flags.is_end_headers());
@@ -503,11 +687,12 @@ impl PushPromise {
         let head = self.head();
         let promised_id = self.promised_id;
 
-        self.header_block
-            .into_encoding(encoder)
-            .encode(&head, dst, |dst| {
-                dst.put_u32(promised_id.into());
-            })
+        let block = self.header_block.into_encoding(encoder);
+        let pad_len = block.pad_len(padder, self.padding, 4, dst);
+
+        block.encode(&head, dst, pad_len, |dst| {
+            dst.put_u32(promised_id.into());
+        })
     }
 
     fn head(&self) -> Head {
@@ -544,17 +729,20 @@ impl Continuation {
         Head::new(Kind::Continuation, END_HEADERS, self.stream_id)
     }
 
+    /// Encodes the frame into `dst`, returning the rest of the header block
+    /// if it still does not fit.
     pub fn encode(self, dst: &mut EncodeBuf<'_>) -> Option<Continuation> {
         // Get the CONTINUATION frame head
         let head = self.head();
 
-        self.header_block.encode(&head, dst, |_| {})
+        self.header_block.encode(&head, dst, None, |_| {})
     }
 }
 
 // ===== impl Pseudo =====
 
 impl Pseudo {
+    /// Returns the pseudo headers of a request.
     pub fn request(method: Method, uri: Uri, protocol: Option<Protocol>) -> Self {
         let parts = uri::Parts::from(uri);
 
@@ -584,6 +772,7 @@ impl Pseudo {
             path,
             protocol,
             status: None,
+            order: PseudoHeaderOrder::default(),
         };
 
         // If the URI includes a scheme component, add it to the pseudo headers
@@ -600,6 +789,7 @@ impl Pseudo {
         pseudo
     }
 
+    /// Returns the pseudo headers of a response.
     pub fn response(status: StatusCode) -> Self {
         Pseudo {
             method: None,
@@ -608,14 +798,17 @@ impl Pseudo {
             path: None,
             protocol: None,
             status: Some(status),
+            order: PseudoHeaderOrder::default(),
         }
     }
 
-    #[cfg(feature = "unstable")]
+    /// Sets the `:status` pseudo header.
+    #[cfg(any(feature = "unstable", feature = "codec"))]
     pub fn set_status(&mut self, value: StatusCode) {
         self.status = Some(value);
     }
 
+    /// Sets the `:scheme` pseudo header.
     pub fn set_scheme(&mut self, scheme: uri::Scheme) {
         let bytes_str = match scheme.as_str() {
             "http" => BytesStr::from_static("http"),
@@ -625,15 +818,79 @@ impl Pseudo {
         self.scheme = Some(bytes_str);
     }
 
-    #[cfg(feature = "unstable")]
+    /// Sets the `:protocol` pseudo header.
+    #[cfg(any(feature = "unstable", feature = "codec"))]
     pub fn set_protocol(&mut self, protocol: Protocol) {
         self.protocol = Some(protocol);
     }
 
+    /// Sets the `:authority` pseudo header.
     pub fn set_authority(&mut self, authority: BytesStr) {
         self.authority = Some(authority);
     }
 
+    /// Sets the order in which the request pseudo headers are encoded.
+    pub fn set_order(&mut self, order: PseudoHeaderOrder) {
+        self.order = order;
+    }
+
+    /// Returns the `:method` pseudo header.
+    #[cfg(any(feature = "unstable", feature = "codec"))]
+    pub fn method(&self) -> Option<&Method> {
+        self.method.as_ref()
+    }
+
+    /// Returns the `:scheme` pseudo header.
+    #[cfg(any(feature = "unstable", feature = "codec"))]
+    pub fn scheme(&self) -> Option<&str> {
+        self.scheme.as_ref().map(|s| s.as_str())
+    }
+
+    /// Returns the `:authority` pseudo header.
+    #[cfg(any(feature = "unstable", feature = "codec"))]
+    pub fn authority(&self) -> Option<&str> {
+        self.authority.as_ref().map(|s| s.as_str())
+    }
+
+    /// Returns the `:path` pseudo header.
+    #[cfg(any(feature = "unstable", feature = "codec"))]
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_ref().map(|s| s.as_str())
+    }
+
+    /// Returns the `:protocol` pseudo header.
+    #[cfg(any(feature = "unstable", feature = "codec"))]
+    pub fn protocol(&self) -> Option<&Protocol> {
+        self.protocol.as_ref()
+    }
+
+    /// Returns the `:status` pseudo header.
+    #[cfg(any(feature = "unstable", feature = "codec"))]
+    pub fn status(&self) -> Option<StatusCode> {
+        self.status
+    }
+
+    /// Returns the order in which the request pseudo headers are encoded.
+    #[cfg(any(feature = "unstable", feature = "codec"))]
+    pub fn order(&self) -> PseudoHeaderOrder {
+        self.order
+    }
+
+    /// Returns the request pseudo headers that are set, in encoding order.
+    pub(crate) fn request_headers(&self) -> impl Iterator<Item = PseudoHeader> + '_ {
+        self.order
+            .as_slice()
+            .iter()
+            .copied()
+            .filter(move |header| match header {
+                PseudoHeader::Method => self.method.is_some(),
+                PseudoHeader::Scheme => self.scheme.is_some(),
+                PseudoHeader::Authority => self.authority.is_some(),
+                PseudoHeader::Path => self.path.is_some(),
+                PseudoHeader::Protocol => self.protocol.is_some(),
+            })
+    }
+
     /// Whether it has status 1xx
     pub(crate) fn is_informational(&self) -> bool {
         self.status
@@ -641,10 +898,43 @@ impl Pseudo {
     }
 }
 
+impl PartialEq for Pseudo {
+    fn eq(&self, other: &Pseudo) -> bool {
+        // Only the order of the pseudo headers that are set matters.
+        self.method == other.method
+            && self.scheme == other.scheme
+            && self.authority == other.authority
+            && self.path == other.path
+            && self.protocol == other.protocol
+            && self.status == other.status
+            && self.request_headers().eq(other.request_headers())
+    }
+}
+
 // ===== impl EncodingHeaderBlock =====
 
 impl EncodingHeaderBlock {
-    fn encode<F>(mut self, head: &Head, dst: &mut EncodeBuf<'_>, f: F) -> Option<Continuation>
+    /// Returns the padding of the first frame of the block, which has
+    /// `prefix_len` octets of fields before the block. Blocks that do not fit
+    /// in a single frame are not padded.
+    fn pad_len(
+        &self,
+        padder: Option<&mut Padder>,
+        padding: Option<Padding>,
+        prefix_len: usize,
+        dst: &EncodeBuf<'_>,
+    ) -> Option<u8> {
+        let max_len = dst.remaining_mut().saturating_sub(HEADER_LEN);
+        padder?.pad_len(padding, prefix_len + self.hpack.len(), max_len)
+    }
+
+    fn encode<F>(
+        mut self,
+        head: &Head,
+        dst: &mut EncodeBuf<'_>,
+        pad_len: Option<u8>,
+        f: F,
+    ) -> Option<Continuation>
     where
         F: FnOnce(&mut EncodeBuf<'_>),
     {
@@ -657,11 +947,19 @@ impl EncodingHeaderBlock {
 
         let payload_pos = dst.get_ref().len();
 
+        if let Some(pad_len) = pad_len {
+            dst.put_u8(pad_len);
+        }
+
         f(dst);
 
+        let padded = pad_len.is_some();
+        let pad_len = pad_len.map_or(0, usize::from);
+        let remaining = dst.remaining_mut().saturating_sub(pad_len);
+
         // Now, encode the header payload
-        let continuation = if self.hpack.len() > dst.remaining_mut() {
-            dst.put((&mut self.hpack).take(dst.remaining_mut()));
+        let continuation = if self.hpack.len() > remaining {
+            dst.put((&mut self.hpack).take(remaining));
 
             Some(Continuation {
                 stream_id: head.stream_id(),
@@ -673,6 +971,8 @@ impl EncodingHeaderBlock {
             None
         };
 
+        dst.put_bytes(0, pad_len);
+
         // Compute the header block length
         let payload_len = (dst.get_ref().len() - payload_pos) as u64;
 
@@ -681,6 +981,10 @@ impl EncodingHeaderBlock {
         assert!(payload_len_be[0..5].iter().all(|b| *b == 0));
         (dst.get_mut()[head_pos..head_pos + 3]).copy_from_slice(&payload_len_be[5..]);
 
+        if padded {
+            dst.get_mut()[head_pos + 4] |= PADDED;
+        }
+
         if continuation.is_some() {
             // There will be continuation frames, so the `is_end_headers` flag
             // must be unset
@@ -702,24 +1006,20 @@ impl Iterator for Iter {
         use crate::hpack::Header::*;
 
         if let Some(ref mut pseudo) = self.pseudo {
-            if let Some(method) = pseudo.method.take() {
-                return Some(Method(method));
-            }
-
-            if let Some(scheme) = pseudo.scheme.take() {
-                return Some(Scheme(scheme));
-            }
-
-            if let Some(authority) = pseudo.authority.take() {
-                return Some(Authority(authority));
-            }
-
-            if let Some(path) = pseudo.path.take() {
-                return Some(Path(path));
-            }
-
-            if let Some(protocol) = pseudo.protocol.take() {
-                return Some(Protocol(protocol));
+            let order = pseudo.order;
+
+            for field in order.as_slice() {
+                let header = match field {
+                    PseudoHeader::Method => pseudo.method.take().map(Method),
+                    PseudoHeader::Scheme => pseudo.scheme.take().map(Scheme),
+                    PseudoHeader::Authority => pseudo.authority.take().map(Authority),
+                    PseudoHeader::Path => pseudo.path.take().map(Path),
+                    PseudoHeader::Protocol => pseudo.protocol.take().map(Protocol),
+                };
+
+                if header.is_some() {
+                    return header;
+                }
             }
 
             if let Some(status) = pseudo.status.take() {
@@ -729,9 +1029,13 @@ impl Iterator for Iter {
 
         self.pseudo = None;
 
-        self.fields
-            .next()
-            .map(|(name, value)| Field { name, value })
+        match self.fields {
+            Fields::Map(ref mut fields) => fields.next().map(|(name, value)| Field { name, value }),
+            Fields::Ordered(ref mut fields) => fields.next().map(|(name, value)| Field {
+                name: Some(name),
+                value,
+            }),
+        }
     }
 }
 
@@ -769,6 +1073,10 @@ impl HeadersFlag {
     pub fn is_priority(&self) -> bool {
         self.0 & PRIORITY == PRIORITY
     }
+
+    pub fn set_priority(&mut self) {
+        self.0 |= PRIORITY;
+    }
 }
 
 impl Default for HeadersFlag {
@@ -854,6 +1162,12 @@ impl HeaderBlock {
         let mut malformed = false;
         let mut headers_size = self.calculate_header_list_size();
 
+        // Track the order in which request pseudo headers are received. The
+        // header block may be split across CONTINUATION frames, so start with
+        // the ones already loaded.
+        let mut order: Vec<_> = self.pseudo.request_headers().collect();
+        let loaded = order.len();
+
         macro_rules! set_pseudo {
             ($field:ident, $val:expr) => {{
                 if reg {
@@ -918,11 +1232,26 @@ impl HeaderBlock {
                         }
                     }
                 }
-                Authority(v) => set_pseudo!(authority, v),
-                Method(v) => set_pseudo!(method, v),
-                Scheme(v) => set_pseudo!(scheme, v),
-                Path(v) => set_pseudo!(path, v),
-                Protocol(v) => set_pseudo!(protocol, v),
+                Authority(v) => {
+                    order.push(PseudoHeader::Authority);
+                    set_pseudo!(authority, v)
+                }
+                Method(v) => {
+                    order.push(PseudoHeader::Method);
+                    set_pseudo!(method, v)
+                }
+                Scheme(v) => {
+                    order.push(PseudoHeader::Scheme);
+                    set_pseudo!(scheme, v)
+                }
+                Path(v) => {
+                    order.push(PseudoHeader::Path);
+                    set_pseudo!(path, v)
+                }
+                Protocol(v) => {
+                    order.push(PseudoHeader::Protocol);
+                    set_pseudo!(protocol, v)
+                }
                 Status(v) => set_pseudo!(status, v),
             }
         });
@@ -937,17 +1266,33 @@ impl HeaderBlock {
             return Err(Error::MalformedMessage);
         }
 
+        if order.len() > loaded {
+            self.pseudo.order = PseudoHeaderOrder::new(order);
+        }
+
         Ok(())
     }
 
     fn into_encoding(self, encoder: &mut hpack::Encoder) -> EncodingHeaderBlock {
         let mut hpack = BytesMut::new();
+        let fields = match self.ordered_fields {
+            Some(mut ordered) => {
+                // Fields may have been removed from the map since the order
+                // was set, e.g. the `host` header.
+                ordered.retain(|(name, _)| self.fields.contains_key(name));
+                Fields::Ordered(ordered.into_iter())
+            }
+            None => Fields::Map(self.fields.into_iter()),
+        };
         let headers = Iter {
             pseudo: Some(self.pseudo),
-            fields: self.fields.into_iter(),
+            fields,
         };
 
-        encoder.encode(headers, &mut hpack);
+        match self.indexing {
+            Some(indexing) => encoder.encode_with(headers, |name| indexing.get(name), &mut hpack),
+            None => encoder.encode(headers, &mut hpack),
+        }
 
         EncodingHeaderBlock {
             hpack: hpack.freeze(),
@@ -997,6 +1342,24 @@ mod test {
     use crate::frame;
     use crate::hpack::{huffman, Encoder};
 
+    #[test]
+    #[cfg(any(feature = "unstable", feature = "codec"))]
+    fn test_pseudo_accessors() {
+        let uri = Uri::from_static("https://example.com/index.html");
+        let pseudo = Pseudo::request(Method::GET, uri, None);
+        assert_eq!(pseudo.method(), Some(&Method::GET));
+        assert_eq!(pseudo.scheme(), Some("https"));
+        assert_eq!(pseudo.authority(), Some("example.com"));
+        assert_eq!(pseudo.path(), Some("/index.html"));
+        assert_eq!(pseudo.protocol(), None);
+        assert_eq!(pseudo.status(), None);
+        assert_eq!(pseudo.order(), PseudoHeaderOrder::default());
+
+        let pseudo = Pseudo::response(StatusCode::NO_CONTENT);
+        assert_eq!(pseudo.method(), None);
+        assert_eq!(pseudo.status(), Some(StatusCode::NO_CONTENT));
+    }
+
     #[test]
     fn test_nameless_header_at_resume() {
         let mut encoder = Encoder::default();
@@ -1052,6 +1415,122 @@ mod test {
         assert_eq!("sup", huff_decode(&dst[21..]));
     }
 
+    #[test]
+    fn test_pseudo_headers_follow_configured_order() {
+        let mut pseudo = Pseudo::request(
+            Method::GET,
+            Uri::from_static("https://example.com/index.html"),
+            None,
+        );
+        pseudo.set_order(PseudoHeaderOrder::new([
+            PseudoHeader::Method,
+            PseudoHeader::Path,
+            PseudoHeader::Authority,
+            PseudoHeader::Scheme,
+        ]));
+
+        let iter = Iter {
+            pseudo: Some(pseudo),
+            fields: Fields::Map(HeaderMap::new().into_iter()),
+        };
+
+        let names = iter
+            .map(|header| match header {
+                hpack::Header::Method(_) => ":method",
+                hpack::Header::Scheme(_) => ":scheme",
+                hpack::Header::Authority(_) => ":authority",
+                hpack::Header::Path(_) => ":path",
+                other => panic!("unexpected header; {:?}", other),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, [":method", ":path", ":authority", ":scheme"]);
+    }
+
+    #[test]
+    fn test_ordered_fields_are_encoded_as_given() {
+        let fields = vec![
+            (header::COOKIE, HeaderValue::from_static("a=1")),
+            (header::ACCEPT, HeaderValue::from_static("*/*")),
+            (header::COOKIE, HeaderValue::from_static("b=2")),
+            (header::HOST, HeaderValue::from_static("example.com")),
+        ];
+
+        let mut map = HeaderMap::new();
+        for (name, value) in &fields {
+            map.append(name.clone(), value.clone());
+        }
+        map.remove(header::HOST);
+
+        let mut headers = Headers::new(StreamId::from(1), Pseudo::default(), map);
+        headers.set_ordered_fields(fields);
+
+        let mut dst = BytesMut::new();
+        assert!(headers
+            .encode(&mut Encoder::default(), &mut (&mut dst).limit(1024))
+            .is_none());
+
+        let mut decoded = Vec::new();
+        let mut payload = dst.split_off(frame::HEADER_LEN);
+        hpack::Decoder::new(4096)
+            .decode(&mut Cursor::new(&mut payload), |header| match header {
+                hpack::Header::Field { name, value } => {
+                    decoded.push(format!("{}: {}", name, value.to_str().unwrap()))
+                }
+                other => panic!("unexpected header; {:?}", other),
+            })
+            .unwrap();
+
+        assert_eq!(decoded, ["cookie: a=1", "accept: */*", "cookie: b=2"]);
+    }
+
+    #[test]
+    fn test_padded_headers_round_trip() {
+        let mut map = HeaderMap::new();
+        map.insert(header::ACCEPT, HeaderValue::from_static("*/*"));
+
+        let mut headers = Headers::new(StreamId::from(1), Pseudo::default(), map.clone());
+        headers.set_padding(Padding::fixed(10));
+
+        let mut dst = BytesMut::new();
+        let mut padder = Padder::new(Padding::none());
+        assert!(headers
+            .encode_padded(
+                &mut Encoder::default(),
+                &mut (&mut dst).limit(1024),
+                &mut padder,
+            )
+            .is_none());
+
+        let head = frame::Head::parse(&dst);
+        assert_eq!(head.flag() & PADDED, PADDED);
+        assert_eq!(dst[frame::HEADER_LEN], 10);
+        assert!(dst.ends_with(&[0; 10]));
+
+        let payload = dst.split_off(frame::HEADER_LEN);
+        let (mut headers, mut payload) = Headers::load(head, payload).unwrap();
+        headers
+            .load_hpack(&mut payload, 1024, &mut hpack::Decoder::new(4096))
+            .unwrap();
+        assert_eq!(headers.into_fields(), map);
+    }
+
+    #[test]
+    fn test_partial_pseudo_header_order_appends_remaining() {
+        let order = PseudoHeaderOrder::new([PseudoHeader::Path, PseudoHeader::Path]);
+
+        assert_eq!(
+            order.as_slice(),
+            [
+                PseudoHeader::Path,
+                PseudoHeader::Method,
+                PseudoHeader::Scheme,
+                PseudoHeader::Authority,
+                PseudoHeader::Protocol,
+            ]
+        );
+    }
+
     fn huff_decode(src: &[u8]) -> BytesMut {
         let mut buf = BytesMut::new();
         huffman::decode(src, &mut buf).unwrap()
@@ -1,3 +1,14 @@
+//! HTTP/2 frame types.
+//!
+//! Each frame type of [RFC 7540] has its own struct, which can be built,
+//! inspected, parsed from a raw payload with `load`, and written with
+//! `encode`. The [`Frame`] enum is what a [`Codec`] reads and writes.
+//!
+//! This module is available with the `codec` feature.
+//!
+//! [RFC 7540]: https://httpwg.org/specs/rfc7540.html
+//! [`Codec`]: ../codec/struct.Codec.html
+
 use crate::hpack;
 
 use bytes::Bytes;
@@ -35,12 +46,16 @@ mod tests {
     }
 }
 
+mod altsvc;
 mod data;
+mod extension;
 mod go_away;
 mod head;
 mod headers;
+mod origin;
 mod ping;
 mod priority;
+mod priority_update;
 mod reason;
 mod reset;
 mod settings;
@@ -48,22 +63,26 @@ mod stream_id;
 mod util;
 mod window_update;
 
+pub use self::altsvc::AltSvc;
 pub use self::data::Data;
+pub use self::extension::Extension;
 pub use self::go_away::GoAway;
 pub use self::head::{Head, Kind};
 pub use self::headers::{
     parse_u64, Continuation, Headers, Pseudo, PushPromise, PushPromiseHeaderError,
 };
+pub use self::origin::Origin;
 pub use self::ping::Ping;
 pub use self::priority::{Priority, StreamDependency};
+pub use self::priority_update::PriorityUpdate;
 pub use self::reason::Reason;
 pub use self::reset::Reset;
 pub use self::settings::Settings;
 pub use self::stream_id::{StreamId, StreamIdOverflow};
 pub use self::window_update::WindowUpdate;
 
-#[cfg(feature = "unstable")]
-pub use crate::hpack::BytesStr;
+#[cfg(any(feature = "unstable", feature = "codec"))]
+pub use crate::hpack::header::BytesStr;
 
 // Re-export some constants
 
@@ -72,24 +91,70 @@ pub use self::settings::{
     MAX_MAX_FRAME_SIZE,
 };
 
+/// The size of a frame payload, in octets.
 pub type FrameSize = u32;
 
+/// The length of the frame header, in octets.
 pub const HEADER_LEN: usize = 9;
 
+/// An HTTP/2 frame.
+///
+/// `CONTINUATION` frames are merged into the `HEADERS` or `PUSH_PROMISE`
+/// frame they continue, and are split off again when encoding.
 #[derive(Eq, PartialEq)]
+#[non_exhaustive]
 pub enum Frame<T = Bytes> {
+    /// A `DATA` frame, with a payload of type `T`.
     Data(Data<T>),
+    /// A `HEADERS` frame.
     Headers(Headers),
+    /// A `PRIORITY` frame.
     Priority(Priority),
+    /// A `PRIORITY_UPDATE` frame.
+    PriorityUpdate(PriorityUpdate),
+    /// A `PUSH_PROMISE` frame.
     PushPromise(PushPromise),
+    /// A `SETTINGS` frame.
     Settings(Settings),
+    /// A `PING` frame.
     Ping(Ping),
+    /// A `GOAWAY` frame.
     GoAway(GoAway),
+    /// A `WINDOW_UPDATE` frame.
     WindowUpdate(WindowUpdate),
+    /// A `RST_STREAM` frame.
     Reset(Reset),
+    /// An `ALTSVC` frame.
+    AltSvc(AltSvc),
+    /// An `ORIGIN` frame.
+    Origin(Origin),
+    /// A frame of a type not known to this crate.
+    Extension(Extension),
 }
 
 impl<T> Frame<T> {
+    /// Returns the type of the frame.
+    pub fn kind(&self) -> Kind {
+        use self::Frame::*;
+
+        match *self {
+            Data(_) => Kind::Data,
+            Headers(_) => Kind::Headers,
+            Priority(_) => Kind::Priority,
+            PriorityUpdate(_) => Kind::PriorityUpdate,
+            PushPromise(_) => Kind::PushPromise,
+            Settings(_) => Kind::Settings,
+            Ping(_) => Kind::Ping,
+            GoAway(_) => Kind::GoAway,
+            WindowUpdate(_) => Kind::WindowUpdate,
+            Reset(_) => Kind::Reset,
+            AltSvc(_) => Kind::AltSvc,
+            Origin(_) => Kind::Origin,
+            Extension(_) => Kind::Unknown,
+        }
+    }
+
+    /// Maps the payload of a `DATA` frame, leaving other frames unchanged.
     pub fn map<F, U>(self, f: F) -> Frame<U>
     where
         F: FnOnce(T) -> U,
@@ -100,12 +165,16 @@ impl<T> Frame<T> {
             Data(frame) => frame.map(f).into(),
             Headers(frame) => frame.into(),
             Priority(frame) => frame.into(),
+            PriorityUpdate(frame) => frame.into(),
             PushPromise(frame) => frame.into(),
             Settings(frame) => frame.into(),
             Ping(frame) => frame.into(),
             GoAway(frame) => frame.into(),
             WindowUpdate(frame) => frame.into(),
             Reset(frame) => frame.into(),
+            AltSvc(frame) => frame.into(),
+            Origin(frame) => frame.into(),
+            Extension(frame) => frame.into(),
         }
     }
 }
@@ -118,12 +187,16 @@ impl<T> fmt::Debug for Frame<T> {
             Data(ref frame) => fmt::Debug::fmt(frame, fmt),
             Headers(ref frame) => fmt::Debug::fmt(frame, fmt),
             Priority(ref frame) => fmt::Debug::fmt(frame, fmt),
+            PriorityUpdate(ref frame) => fmt::Debug::fmt(frame, fmt),
             PushPromise(ref frame) => fmt::Debug::fmt(frame, fmt),
             Settings(ref frame) => fmt::Debug::fmt(frame, fmt),
             Ping(ref frame) => fmt::Debug::fmt(frame, fmt),
             GoAway(ref frame) => fmt::Debug::fmt(frame, fmt),
             WindowUpdate(ref frame) => fmt::Debug::fmt(frame, fmt),
             Reset(ref frame) => fmt::Debug::fmt(frame, fmt),
+            AltSvc(ref frame) => fmt::Debug::fmt(frame, fmt),
+            Origin(ref frame) => fmt::Debug::fmt(frame, fmt),
+            Extension(ref frame) => fmt::Debug::fmt(frame, fmt),
         }
     }
 }
@@ -0,0 +1,123 @@
+use crate::frame::{self, Error, Head, Kind, StreamId};
+
+use bytes::{BufMut, Bytes};
+
+/// An `ORIGIN` frame, listing the origins a server is authoritative for, as
+/// defined in [RFC 8336].
+///
+/// [RFC 8336]: https://www.rfc-editor.org/rfc/rfc8336.html#section-2
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Origin {
+    origins: Vec<Bytes>,
+}
+
+impl Origin {
+    /// Creates an `ORIGIN` frame listing `origins`.
+    pub fn new(origins: Vec<Bytes>) -> Origin {
+        debug_assert!(origins.iter().all(|o| o.len() <= u16::MAX as usize));
+
+        Origin { origins }
+    }
+
+    /// Returns the ASCII serializations of the origins.
+    #[cfg(any(feature = "unstable", feature = "codec"))]
+    pub fn origins(&self) -> &[Bytes] {
+        &self.origins
+    }
+
+    /// Consumes the frame, returning the origins.
+    pub fn into_origins(self) -> Vec<Bytes> {
+        self.origins
+    }
+
+    /// Returns the length of the payload, in octets.
+    pub fn payload_len(&self) -> usize {
+        self.origins.iter().map(|o| 2 + o.len()).sum()
+    }
+
+    /// Builds an `Origin` frame from a raw frame.
+    ///
+    /// RFC 8336 requires invalid frames to be ignored, rather than treated as
+    /// errors.
+    pub fn load(head: Head, mut payload: &[u8]) -> Result<Origin, Error> {
+        debug_assert_eq!(head.kind(), Kind::Origin);
+
+        if !head.stream_id().is_zero() {
+            return Err(Error::InvalidStreamId);
+        }
+
+        let mut origins = Vec::new();
+
+        while !payload.is_empty() {
+            if payload.len() < 2 {
+                return Err(Error::BadFrameSize);
+            }
+
+            let len = ((payload[0] as usize) << 8) | payload[1] as usize;
+            payload = &payload[2..];
+
+            if payload.len() < len {
+                return Err(Error::BadFrameSize);
+            }
+
+            origins.push(Bytes::copy_from_slice(&payload[..len]));
+            payload = &payload[len..];
+        }
+
+        Ok(Origin { origins })
+    }
+
+    /// Writes the frame, header included.
+    pub fn encode<B: BufMut>(&self, dst: &mut B) {
+        tracing::trace!("encoding ORIGIN; origins={}", self.origins.len());
+        let head = Head::new(Kind::Origin, 0, StreamId::zero());
+        head.encode(self.payload_len(), dst);
+
+        for origin in &self.origins {
+            dst.put_u16(origin.len() as u16);
+            dst.put_slice(origin);
+        }
+    }
+}
+
+impl<B> From<Origin> for frame::Frame<B> {
+    fn from(src: Origin) -> Self {
+        frame::Frame::Origin(src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let frame = Origin::new(vec![
+            Bytes::from_static(b"https://a.example.com"),
+            Bytes::from_static(b"https://b.example.com"),
+        ]);
+
+        let mut buf = Vec::new();
+        frame.encode(&mut buf);
+        assert_eq!(&buf[..11], &[0, 0, 46, 0xc, 0, 0, 0, 0, 0, 0, 21]);
+
+        let head = Head::parse(&buf);
+        assert_eq!(Origin::load(head, &buf[9..]), Ok(frame));
+    }
+
+    #[test]
+    fn load_rejects_invalid_frames() {
+        let head = Head::new(Kind::Origin, 0, StreamId::zero());
+        assert_eq!(
+            Origin::load(head, &[0, 1, b'a', 0]),
+            Err(Error::BadFrameSize)
+        );
+        assert_eq!(Origin::load(head, &[0, 2, b'a']), Err(Error::BadFrameSize));
+
+        let head = Head::new(Kind::Origin, 0, StreamId::from(1));
+        assert_eq!(
+            Origin::load(head, &[0, 1, b'a']),
+            Err(Error::InvalidStreamId)
+        );
+    }
+}
@@ -3,8 +3,10 @@ use bytes::BufMut;
 
 const ACK_FLAG: u8 = 0x1;
 
+/// The opaque data carried by a `PING` frame.
 pub type Payload = [u8; 8];
 
+/// A `PING` frame, or its acknowledgement.
 #[derive(Debug, Eq, PartialEq)]
 pub struct Ping {
     ack: bool,
@@ -15,6 +17,8 @@ pub struct Ping {
 // zeroes to distinguish this specific PING from any other.
 const SHUTDOWN_PAYLOAD: Payload = [0x0b, 0x7b, 0xa2, 0xf0, 0x8b, 0x9b, 0xfe, 0x54];
 const USER_PAYLOAD: Payload = [0x3b, 0x7c, 0xdb, 0x7a, 0x0b, 0x87, 0x16, 0xb4];
+const BDP_PAYLOAD: Payload = [0x8d, 0x2e, 0x51, 0xc7, 0x36, 0xf4, 0x09, 0xa2];
+const KEEP_ALIVE_PAYLOAD: Payload = [0x5f, 0xc3, 0x17, 0x9a, 0xe0, 0x42, 0x6d, 0x1b];
 
 impl Ping {
     #[cfg(feature = "unstable")]
@@ -29,6 +33,19 @@ impl Ping {
     #[cfg(not(feature = "unstable"))]
     pub(crate) const USER: Payload = USER_PAYLOAD;
 
+    #[cfg(feature = "unstable")]
+    pub const BDP: Payload = BDP_PAYLOAD;
+
+    #[cfg(not(feature = "unstable"))]
+    pub(crate) const BDP: Payload = BDP_PAYLOAD;
+
+    #[cfg(feature = "unstable")]
+    pub const KEEP_ALIVE: Payload = KEEP_ALIVE_PAYLOAD;
+
+    #[cfg(not(feature = "unstable"))]
+    pub(crate) const KEEP_ALIVE: Payload = KEEP_ALIVE_PAYLOAD;
+
+    /// Creates a `PING` frame.
     pub fn new(payload: Payload) -> Ping {
         Ping {
             ack: false,
@@ -36,18 +53,22 @@ impl Ping {
         }
     }
 
+    /// Creates the acknowledgement of a `PING` frame carrying `payload`.
     pub fn pong(payload: Payload) -> Ping {
         Ping { ack: true, payload }
     }
 
+    /// Returns whether the `ACK` flag is set.
     pub fn is_ack(&self) -> bool {
         self.ack
     }
 
+    /// Returns the opaque data.
     pub fn payload(&self) -> &Payload {
         &self.payload
     }
 
+    /// Consumes the frame, returning the opaque data.
     pub fn into_payload(self) -> Payload {
         self.payload
     }
@@ -83,6 +104,7 @@ impl Ping {
         Ok(Ping { ack, payload })
     }
 
+    /// Writes the frame, header included.
     pub fn encode<B: BufMut>(&self, dst: &mut B) {
         let sz = self.payload.len();
         tracing::trace!("encoding PING; ack={} len={}", self.ack, sz);
@@ -1,12 +1,16 @@
 use crate::frame::*;
 
-#[derive(Debug, Eq, PartialEq)]
+use bytes::BufMut;
+
+/// A `PRIORITY` frame, setting the dependency of a stream.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct Priority {
     stream_id: StreamId,
     dependency: StreamDependency,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+/// The stream dependency fields of `PRIORITY` and `HEADERS` frames.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct StreamDependency {
     /// The ID of the stream dependency target
     dependency_id: StreamId,
@@ -21,6 +25,15 @@ pub struct StreamDependency {
 }
 
 impl Priority {
+    /// Creates a `PRIORITY` frame.
+    pub fn new(stream_id: StreamId, dependency: StreamDependency) -> Self {
+        Priority {
+            stream_id,
+            dependency,
+        }
+    }
+
+    /// Builds a `Priority` frame from a raw frame.
     pub fn load(head: Head, payload: &[u8]) -> Result<Self, Error> {
         let dependency = StreamDependency::load(payload)?;
 
@@ -33,6 +46,24 @@ impl Priority {
             dependency,
         })
     }
+
+    /// Returns the identifier of the prioritized stream.
+    pub fn stream_id(&self) -> StreamId {
+        self.stream_id
+    }
+
+    /// Returns the stream dependency.
+    pub fn dependency(&self) -> &StreamDependency {
+        &self.dependency
+    }
+
+    /// Writes the frame, header included.
+    pub fn encode<B: BufMut>(&self, dst: &mut B) {
+        tracing::trace!("encoding PRIORITY; id={:?}", self.stream_id);
+        let head = Head::new(Kind::Priority, 0, self.stream_id);
+        head.encode(5, dst);
+        self.dependency.encode(dst);
+    }
 }
 
 impl<B> From<Priority> for Frame<B> {
@@ -44,6 +75,8 @@ impl<B> From<Priority> for Frame<B> {
 // ===== impl StreamDependency =====
 
 impl StreamDependency {
+    /// Creates a stream dependency. `weight` is the wire value, one less than
+    /// the actual weight.
     pub fn new(dependency_id: StreamId, weight: u8, is_exclusive: bool) -> Self {
         StreamDependency {
             dependency_id,
@@ -52,6 +85,7 @@ impl StreamDependency {
         }
     }
 
+    /// Parses the 5 octets of a stream dependency.
     pub fn load(src: &[u8]) -> Result<Self, Error> {
         if src.len() != 5 {
             return Err(Error::InvalidPayloadLength);
@@ -66,7 +100,29 @@ impl StreamDependency {
         Ok(StreamDependency::new(dependency_id, weight, is_exclusive))
     }
 
+    /// Returns the identifier of the stream depended upon.
     pub fn dependency_id(&self) -> StreamId {
         self.dependency_id
     }
+
+    /// Returns the wire value of the weight, one less than the actual weight.
+    pub fn weight(&self) -> u8 {
+        self.weight
+    }
+
+    /// Returns whether the dependency is exclusive.
+    pub fn is_exclusive(&self) -> bool {
+        self.is_exclusive
+    }
+
+    /// Writes the 5 octets of the stream dependency.
+    pub fn encode<B: BufMut>(&self, dst: &mut B) {
+        let mut id = u32::from(self.dependency_id);
+        if self.is_exclusive {
+            id |= 1 << 31;
+        }
+
+        dst.put_u32(id);
+        dst.put_u8(self.weight);
+    }
 }
@@ -0,0 +1,113 @@
+use crate::frame::{self, Error, Head, Kind, StreamId};
+
+use bytes::{BufMut, Bytes};
+
+/// A `PRIORITY_UPDATE` frame, changing the priority of a request as defined
+/// in [RFC 9218].
+///
+/// [RFC 9218]: https://www.rfc-editor.org/rfc/rfc9218.html#section-7.1
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriorityUpdate {
+    prioritized_id: StreamId,
+    field_value: Bytes,
+}
+
+impl PriorityUpdate {
+    /// Creates a `PRIORITY_UPDATE` frame for the request stream
+    /// `prioritized_id`, carrying a `priority` field value.
+    pub fn new(prioritized_id: StreamId, field_value: Bytes) -> PriorityUpdate {
+        PriorityUpdate {
+            prioritized_id,
+            field_value,
+        }
+    }
+
+    /// Returns the identifier of the stream whose priority is updated.
+    pub fn prioritized_id(&self) -> StreamId {
+        self.prioritized_id
+    }
+
+    /// Returns the priority field value, in the same format as the
+    /// `priority` header.
+    pub fn field_value(&self) -> &Bytes {
+        &self.field_value
+    }
+
+    /// Builds a `PriorityUpdate` frame from a raw frame.
+    pub fn load(head: Head, payload: &[u8]) -> Result<PriorityUpdate, Error> {
+        debug_assert_eq!(head.kind(), Kind::PriorityUpdate);
+
+        if !head.stream_id().is_zero() {
+            return Err(Error::InvalidStreamId);
+        }
+
+        if payload.len() < 4 {
+            return Err(Error::BadFrameSize);
+        }
+
+        let (prioritized_id, _) = StreamId::parse(&payload[..4]);
+
+        if prioritized_id.is_zero() {
+            return Err(Error::InvalidStreamId);
+        }
+
+        Ok(PriorityUpdate {
+            prioritized_id,
+            field_value: Bytes::copy_from_slice(&payload[4..]),
+        })
+    }
+
+    /// Writes the frame, header included.
+    pub fn encode<B: BufMut>(&self, dst: &mut B) {
+        tracing::trace!(
+            "encoding PRIORITY_UPDATE; prioritized_id={:?}",
+            self.prioritized_id
+        );
+        let head = Head::new(Kind::PriorityUpdate, 0, StreamId::zero());
+        head.encode(4 + self.field_value.len(), dst);
+        dst.put_u32(self.prioritized_id.into());
+        dst.put_slice(&self.field_value);
+    }
+}
+
+impl<B> From<PriorityUpdate> for frame::Frame<B> {
+    fn from(src: PriorityUpdate) -> Self {
+        frame::Frame::PriorityUpdate(src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let frame = PriorityUpdate::new(StreamId::from(5), Bytes::from_static(b"u=1, i"));
+
+        let mut buf = Vec::new();
+        frame.encode(&mut buf);
+        assert_eq!(&buf[..9], &[0, 0, 10, 0x10, 0, 0, 0, 0, 0]);
+
+        let head = Head::parse(&buf);
+        assert_eq!(PriorityUpdate::load(head, &buf[9..]), Ok(frame));
+    }
+
+    #[test]
+    fn load_rejects_invalid_frames() {
+        let head = Head::new(Kind::PriorityUpdate, 0, StreamId::zero());
+        assert_eq!(
+            PriorityUpdate::load(head, &[0, 0, 1]),
+            Err(Error::BadFrameSize)
+        );
+        assert_eq!(
+            PriorityUpdate::load(head, &[0, 0, 0, 0]),
+            Err(Error::InvalidStreamId)
+        );
+
+        let head = Head::new(Kind::PriorityUpdate, 0, StreamId::from(1));
+        assert_eq!(
+            PriorityUpdate::load(head, &[0, 0, 0, 1]),
+            Err(Error::InvalidStreamId)
+        );
+    }
+}
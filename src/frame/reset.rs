@@ -2,6 +2,7 @@ use crate::frame::{self, Error, Head, Kind, Reason, StreamId};
 
 use bytes::BufMut;
 
+/// A `RST_STREAM` frame, terminating a stream.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct Reset {
     stream_id: StreamId,
@@ -9,6 +10,7 @@ pub struct Reset {
 }
 
 impl Reset {
+    /// Creates a `RST_STREAM` frame.
     pub fn new(stream_id: StreamId, error: Reason) -> Reset {
         Reset {
             stream_id,
@@ -16,14 +18,17 @@ impl Reset {
         }
     }
 
+    /// Returns the identifier of the reset stream.
     pub fn stream_id(&self) -> StreamId {
         self.stream_id
     }
 
+    /// Returns the error code.
     pub fn reason(&self) -> Reason {
         self.error_code
     }
 
+    /// Builds a `Reset` frame from a raw frame.
     pub fn load(head: Head, payload: &[u8]) -> Result<Reset, Error> {
         if payload.len() != 4 {
             return Err(Error::InvalidPayloadLength);
@@ -37,6 +42,7 @@ impl Reset {
         })
     }
 
+    /// Writes the frame, header included.
     pub fn encode<B: BufMut>(&self, dst: &mut B) {
         tracing::trace!(
             "encoding RESET; id={:?} code={:?}",
@@ -3,6 +3,9 @@ use std::fmt;
 use crate::frame::{util, Error, Frame, FrameSize, Head, Kind, StreamId};
 use bytes::{BufMut, BytesMut};
 
+/// A `SETTINGS` frame, or its acknowledgement.
+///
+/// `Settings::default()` is an empty, non-ACK frame.
 #[derive(Clone, Default, Eq, PartialEq)]
 pub struct Settings {
     flags: SettingsFlags,
@@ -14,6 +17,10 @@ pub struct Settings {
     max_frame_size: Option<u32>,
     max_header_list_size: Option<u32>,
     enable_connect_protocol: Option<u32>,
+    no_rfc7540_priorities: Option<u32>,
+    // Explicit (identifier, value) pairs to encode, in order, in place of the
+    // fields above.
+    custom: Option<Vec<(u16, u32)>>,
 }
 
 /// An enum that lists all valid settings that can be sent in a SETTINGS
@@ -29,6 +36,7 @@ pub enum Setting {
     MaxFrameSize(u32),
     MaxHeaderListSize(u32),
     EnableConnectProtocol(u32),
+    NoRfc7540Priorities(u32),
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Default)]
@@ -55,6 +63,7 @@ pub const MAX_MAX_FRAME_SIZE: FrameSize = (1 << 24) - 1;
 // ===== impl Settings =====
 
 impl Settings {
+    /// Creates a `SETTINGS` acknowledgement.
     pub fn ack() -> Settings {
         Settings {
             flags: SettingsFlags::ack(),
@@ -62,30 +71,41 @@ impl Settings {
         }
     }
 
+    /// Returns whether the `ACK` flag is set.
     pub fn is_ack(&self) -> bool {
         self.flags.is_ack()
     }
 
+    /// Returns `SETTINGS_INITIAL_WINDOW_SIZE`, if present.
     pub fn initial_window_size(&self) -> Option<u32> {
         self.initial_window_size
     }
 
+    /// Sets or removes `SETTINGS_INITIAL_WINDOW_SIZE`.
     pub fn set_initial_window_size(&mut self, size: Option<u32>) {
         self.initial_window_size = size;
     }
 
+    /// Returns `SETTINGS_MAX_CONCURRENT_STREAMS`, if present.
     pub fn max_concurrent_streams(&self) -> Option<u32> {
         self.max_concurrent_streams
     }
 
+    /// Sets or removes `SETTINGS_MAX_CONCURRENT_STREAMS`.
     pub fn set_max_concurrent_streams(&mut self, max: Option<u32>) {
         self.max_concurrent_streams = max;
     }
 
+    /// Returns `SETTINGS_MAX_FRAME_SIZE`, if present.
     pub fn max_frame_size(&self) -> Option<u32> {
         self.max_frame_size
     }
 
+    /// Sets or removes `SETTINGS_MAX_FRAME_SIZE`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is outside of the range allowed by RFC 7540.
     pub fn set_max_frame_size(&mut self, size: Option<u32>) {
         if let Some(val) = size {
             assert!(DEFAULT_MAX_FRAME_SIZE <= val && val <= MAX_MAX_FRAME_SIZE);
@@ -93,41 +113,82 @@ impl Settings {
         self.max_frame_size = size;
     }
 
+    /// Returns `SETTINGS_MAX_HEADER_LIST_SIZE`, if present.
     pub fn max_header_list_size(&self) -> Option<u32> {
         self.max_header_list_size
     }
 
+    /// Sets or removes `SETTINGS_MAX_HEADER_LIST_SIZE`.
     pub fn set_max_header_list_size(&mut self, size: Option<u32>) {
         self.max_header_list_size = size;
     }
 
+    /// Returns `SETTINGS_ENABLE_PUSH`, if present.
     pub fn is_push_enabled(&self) -> Option<bool> {
         self.enable_push.map(|val| val != 0)
     }
 
+    /// Sets `SETTINGS_ENABLE_PUSH`.
     pub fn set_enable_push(&mut self, enable: bool) {
         self.enable_push = Some(enable as u32);
     }
 
+    /// Returns `SETTINGS_ENABLE_CONNECT_PROTOCOL`, if present.
     pub fn is_extended_connect_protocol_enabled(&self) -> Option<bool> {
         self.enable_connect_protocol.map(|val| val != 0)
     }
 
+    /// Sets or removes `SETTINGS_ENABLE_CONNECT_PROTOCOL`.
     pub fn set_enable_connect_protocol(&mut self, val: Option<u32>) {
         self.enable_connect_protocol = val;
     }
 
+    /// Returns `SETTINGS_NO_RFC7540_PRIORITIES`, if present.
+    #[cfg(any(feature = "unstable", feature = "codec"))]
+    pub fn is_no_rfc7540_priorities(&self) -> Option<bool> {
+        self.no_rfc7540_priorities.map(|val| val != 0)
+    }
+
+    /// Sets or removes `SETTINGS_NO_RFC7540_PRIORITIES`.
+    pub fn set_no_rfc7540_priorities(&mut self, val: Option<bool>) {
+        self.no_rfc7540_priorities = val.map(u32::from);
+    }
+
+    /// Returns `SETTINGS_HEADER_TABLE_SIZE`, if present.
     pub fn header_table_size(&self) -> Option<u32> {
         self.header_table_size
     }
 
+    /// Sets or removes `SETTINGS_HEADER_TABLE_SIZE`.
     pub fn set_header_table_size(&mut self, size: Option<u32>) {
         self.header_table_size = size;
     }
 
-    pub fn load(head: Head, payload: &[u8]) -> Result<Settings, Error> {
-        use self::Setting::*;
+    /// Replaces the contents of the frame with an explicit list of settings.
+    ///
+    /// The settings are encoded exactly as given, including duplicates and
+    /// unknown identifiers. Known identifiers also update the corresponding
+    /// fields so that the local connection state matches what is sent.
+    pub fn set_custom(&mut self, settings: Vec<(u16, u32)>) -> Result<(), Error> {
+        let mut typed = Settings::default();
+
+        for &(id, val) in &settings {
+            if let Some(setting) = Setting::from_id(id, val) {
+                typed.apply(setting)?;
+            }
+        }
+
+        *self = Settings {
+            flags: self.flags,
+            custom: Some(settings),
+            ..typed
+        };
 
+        Ok(())
+    }
+
+    /// Builds a `Settings` frame from a raw frame.
+    pub fn load(head: Head, payload: &[u8]) -> Result<Settings, Error> {
         debug_assert_eq!(head.kind(), crate::frame::Kind::Settings);
 
         if !head.stream_id().is_zero() {
@@ -157,59 +218,123 @@ impl Settings {
         debug_assert!(!settings.flags.is_ack());
 
         for raw in payload.chunks(6) {
-            match Setting::load(raw) {
-                Some(HeaderTableSize(val)) => {
-                    settings.header_table_size = Some(val);
-                }
-                Some(EnablePush(val)) => match val {
-                    0 | 1 => {
-                        settings.enable_push = Some(val);
-                    }
-                    _ => {
-                        return Err(Error::InvalidSettingValue);
-                    }
-                },
-                Some(MaxConcurrentStreams(val)) => {
-                    settings.max_concurrent_streams = Some(val);
+            if let Some(setting) = Setting::load(raw) {
+                settings.apply(setting)?;
+            }
+        }
+
+        Ok(settings)
+    }
+
+    /// Builds a `Settings` frame from the value of the `HTTP2-Settings`
+    /// header of an HTTP/1.1 request upgrading to HTTP/2, which is the
+    /// payload of a SETTINGS frame encoded in base64url.
+    pub fn load_upgrade_header(value: &[u8]) -> Result<Settings, Error> {
+        let value = match value.iter().position(|&b| b == b'=') {
+            Some(end) if value[end..].iter().all(|&b| b == b'=') => &value[..end],
+            Some(_) => return Err(Error::MalformedMessage),
+            None => value,
+        };
+
+        if value.len() % 4 == 1 {
+            return Err(Error::MalformedMessage);
+        }
+
+        let mut payload = Vec::with_capacity(value.len() * 3 / 4);
+        let mut bits = 0u32;
+        let mut num_bits = 0;
+
+        for &b in value {
+            let sextet = match b {
+                b'A'..=b'Z' => b - b'A',
+                b'a'..=b'z' => b - b'a' + 26,
+                b'0'..=b'9' => b - b'0' + 52,
+                b'-' => 62,
+                b'_' => 63,
+                _ => return Err(Error::MalformedMessage),
+            };
+
+            bits = (bits << 6) | sextet as u32;
+            num_bits += 6;
+
+            if num_bits >= 8 {
+                num_bits -= 8;
+                payload.push((bits >> num_bits) as u8);
+            }
+        }
+
+        let head = Head::new(Kind::Settings, 0, StreamId::zero());
+        Settings::load(head, &payload)
+    }
+
+    /// Validates `setting` and stores it in the matching field.
+    fn apply(&mut self, setting: Setting) -> Result<(), Error> {
+        use self::Setting::*;
+
+        match setting {
+            HeaderTableSize(val) => {
+                self.header_table_size = Some(val);
+            }
+            EnablePush(val) => match val {
+                0 | 1 => {
+                    self.enable_push = Some(val);
                 }
-                Some(InitialWindowSize(val)) => {
-                    if val as usize > MAX_INITIAL_WINDOW_SIZE {
-                        return Err(Error::InvalidSettingValue);
-                    } else {
-                        settings.initial_window_size = Some(val);
-                    }
+                _ => {
+                    return Err(Error::InvalidSettingValue);
                 }
-                Some(MaxFrameSize(val)) => {
-                    if DEFAULT_MAX_FRAME_SIZE <= val && val <= MAX_MAX_FRAME_SIZE {
-                        settings.max_frame_size = Some(val);
-                    } else {
-                        return Err(Error::InvalidSettingValue);
-                    }
+            },
+            MaxConcurrentStreams(val) => {
+                self.max_concurrent_streams = Some(val);
+            }
+            InitialWindowSize(val) => {
+                if val as usize > MAX_INITIAL_WINDOW_SIZE {
+                    return Err(Error::InvalidSettingValue);
+                } else {
+                    self.initial_window_size = Some(val);
                 }
-                Some(MaxHeaderListSize(val)) => {
-                    settings.max_header_list_size = Some(val);
+            }
+            MaxFrameSize(val) => {
+                if DEFAULT_MAX_FRAME_SIZE <= val && val <= MAX_MAX_FRAME_SIZE {
+                    self.max_frame_size = Some(val);
+                } else {
+                    return Err(Error::InvalidSettingValue);
                 }
-                Some(EnableConnectProtocol(val)) => match val {
-                    0 | 1 => {
-                        settings.enable_connect_protocol = Some(val);
-                    }
-                    _ => {
-                        return Err(Error::InvalidSettingValue);
-                    }
-                },
-                None => {}
             }
+            MaxHeaderListSize(val) => {
+                self.max_header_list_size = Some(val);
+            }
+            EnableConnectProtocol(val) => match val {
+                0 | 1 => {
+                    self.enable_connect_protocol = Some(val);
+                }
+                _ => {
+                    return Err(Error::InvalidSettingValue);
+                }
+            },
+            NoRfc7540Priorities(val) => match val {
+                0 | 1 => {
+                    self.no_rfc7540_priorities = Some(val);
+                }
+                _ => {
+                    return Err(Error::InvalidSettingValue);
+                }
+            },
         }
 
-        Ok(settings)
+        Ok(())
     }
 
     fn payload_len(&self) -> usize {
+        if let Some(ref custom) = self.custom {
+            return custom.len() * 6;
+        }
+
         let mut len = 0;
         self.for_each(|_| len += 6);
         len
     }
 
+    /// Writes the frame, header included.
     pub fn encode(&self, dst: &mut BytesMut) {
         // Create & encode an appropriate frame head
         let head = Head::new(Kind::Settings, self.flags.into(), StreamId::zero());
@@ -219,6 +344,15 @@ impl Settings {
 
         head.encode(payload_len, dst);
 
+        if let Some(ref custom) = self.custom {
+            for &(id, val) in custom {
+                tracing::trace!("encoding setting; id={:#x}, val={}", id, val);
+                dst.put_u16(id);
+                dst.put_u32(val);
+            }
+            return;
+        }
+
         // Encode the settings
         self.for_each(|setting| {
             tracing::trace!("encoding setting; val={:?}", setting);
@@ -226,6 +360,26 @@ impl Settings {
         });
     }
 
+    /// Returns the `(identifier, value)` pairs of the frame, in the order
+    /// they are encoded.
+    pub fn entries(&self) -> Vec<(u16, u32)> {
+        if let Some(ref custom) = self.custom {
+            return custom.clone();
+        }
+
+        let mut entries = Vec::new();
+        self.for_each(|setting| entries.push(setting.entry()));
+        entries
+    }
+
+    /// Returns the `(identifier, value)` pairs of a SETTINGS payload, in the
+    /// order they were received, including unknown identifiers.
+    ///
+    /// The payload must already have been validated by `load`.
+    pub(crate) fn load_entries(payload: &[u8]) -> impl Iterator<Item = (u16, u32)> + '_ {
+        payload.chunks(6).map(load_entry)
+    }
+
     fn for_each<F: FnMut(Setting)>(&self, mut f: F) {
         use self::Setting::*;
 
@@ -256,6 +410,10 @@ impl Settings {
         if let Some(v) = self.enable_connect_protocol {
             f(EnableConnectProtocol(v));
         }
+
+        if let Some(v) = self.no_rfc7540_priorities {
+            f(NoRfc7540Priorities(v));
+        }
     }
 }
 
@@ -270,6 +428,11 @@ impl fmt::Debug for Settings {
         let mut builder = f.debug_struct("Settings");
         builder.field("flags", &self.flags);
 
+        if let Some(ref custom) = self.custom {
+            builder.field("custom", custom);
+            return builder.finish();
+        }
+
         self.for_each(|setting| match setting {
             Setting::EnablePush(v) => {
                 builder.field("enable_push", &v);
@@ -292,6 +455,9 @@ impl fmt::Debug for Settings {
             Setting::EnableConnectProtocol(v) => {
                 builder.field("enable_connect_protocol", &v);
             }
+            Setting::NoRfc7540Priorities(v) => {
+                builder.field("no_rfc7540_priorities", &v);
+            }
         });
 
         builder.finish()
@@ -315,6 +481,7 @@ impl Setting {
             5 => Some(MaxFrameSize(val)),
             6 => Some(MaxHeaderListSize(val)),
             8 => Some(EnableConnectProtocol(val)),
+            9 => Some(NoRfc7540Priorities(val)),
             _ => None,
         }
     }
@@ -330,16 +497,23 @@ impl Setting {
     ///
     /// If given a buffer shorter than 6 bytes, the function will panic.
     fn load(raw: &[u8]) -> Option<Setting> {
-        let id: u16 = (u16::from(raw[0]) << 8) | u16::from(raw[1]);
-        let val: u32 = unpack_octets_4!(raw, 2, u32);
+        let (id, val) = load_entry(raw);
 
         Setting::from_id(id, val)
     }
 
     fn encode(&self, dst: &mut BytesMut) {
+        let (kind, val) = self.entry();
+
+        dst.put_u16(kind);
+        dst.put_u32(val);
+    }
+
+    /// Returns the `(identifier, value)` pair of the setting.
+    fn entry(&self) -> (u16, u32) {
         use self::Setting::*;
 
-        let (kind, val) = match *self {
+        match *self {
             HeaderTableSize(v) => (1, v),
             EnablePush(v) => (2, v),
             MaxConcurrentStreams(v) => (3, v),
@@ -347,13 +521,18 @@ impl Setting {
             MaxFrameSize(v) => (5, v),
             MaxHeaderListSize(v) => (6, v),
             EnableConnectProtocol(v) => (8, v),
-        };
-
-        dst.put_u16(kind);
-        dst.put_u32(val);
+            NoRfc7540Priorities(v) => (9, v),
+        }
     }
 }
 
+fn load_entry(raw: &[u8]) -> (u16, u32) {
+    let id: u16 = (u16::from(raw[0]) << 8) | u16::from(raw[1]);
+    let val: u32 = unpack_octets_4!(raw, 2, u32);
+
+    (id, val)
+}
+
 // ===== impl SettingsFlags =====
 
 impl SettingsFlags {
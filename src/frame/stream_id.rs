@@ -11,6 +11,7 @@
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct StreamId(u32);
 
+/// Returned when a stream identifier would exceed [`StreamId::MAX`].
 #[derive(Debug, Copy, Clone)]
 pub struct StreamIdOverflow;
 
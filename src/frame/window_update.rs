@@ -4,6 +4,7 @@ use bytes::BufMut;
 
 const SIZE_INCREMENT_MASK: u32 = 1 << 31;
 
+/// A `WINDOW_UPDATE` frame, increasing a flow control window.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct WindowUpdate {
     stream_id: StreamId,
@@ -11,6 +12,8 @@ pub struct WindowUpdate {
 }
 
 impl WindowUpdate {
+    /// Creates a `WINDOW_UPDATE` frame. A zero `stream_id` targets the
+    /// connection window.
     pub fn new(stream_id: StreamId, size_increment: u32) -> WindowUpdate {
         WindowUpdate {
             stream_id,
@@ -18,10 +21,12 @@ impl WindowUpdate {
         }
     }
 
+    /// Returns the stream identifier, or zero for the connection window.
     pub fn stream_id(&self) -> StreamId {
         self.stream_id
     }
 
+    /// Returns the window size increment, in octets.
     pub fn size_increment(&self) -> u32 {
         self.size_increment
     }
@@ -47,6 +52,7 @@ impl WindowUpdate {
         })
     }
 
+    /// Writes the frame, header included.
     pub fn encode<B: BufMut>(&self, dst: &mut B) {
         tracing::trace!("encoding WINDOW_UPDATE; id={:?}", self.stream_id);
         let head = Head::new(Kind::WindowUpdate, 0, self.stream_id);
@@ -1,3 +1,5 @@
+#[cfg(feature = "hpack")]
+use super::HeaderField;
 use super::{header::BytesStr, huffman, Header};
 use crate::frame;
 
@@ -25,22 +27,37 @@ pub struct Decoder {
 /// of an HPACK header set.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum DecoderError {
+    /// The first octet of a field does not start a known representation.
     InvalidRepresentation,
+    /// An integer was encoded with an invalid prefix.
     InvalidIntegerPrefix,
+    /// A field refers to an index outside of the static and dynamic tables.
     InvalidTableIndex,
+    /// A Huffman encoded string is invalid.
     InvalidHuffmanCode,
+    /// A header name or value is invalid.
     InvalidUtf8,
+    /// The value of a `:status` field is not a valid status code.
     InvalidStatusCode,
+    /// A pseudo header field is unknown.
     InvalidPseudoheader,
+    /// A dynamic table size update exceeds the allowed maximum, or does not
+    /// appear at the beginning of a header block.
     InvalidMaxDynamicSize,
+    /// An integer does not fit in a `usize`.
     IntegerOverflow,
+    /// The header block ended in the middle of a field.
     NeedMore(NeedMore),
 }
 
+/// Describes where a truncated header block ended.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum NeedMore {
+    /// The header block ended before a representation was complete.
     UnexpectedEndOfStream,
+    /// The header block ended in the middle of an integer.
     IntegerUnderflow,
+    /// The header block ended in the middle of a string.
     StringUnderflow,
 }
 
@@ -162,6 +179,10 @@ impl Decoder {
     }
 
     /// Queues a potential size update
+    ///
+    /// This is the `SETTINGS_HEADER_TABLE_SIZE` advertised to the peer. From
+    /// the next decoded header block on, dynamic table size updates sent by
+    /// the peer must not exceed it.
     #[allow(dead_code)]
     pub fn queue_size_update(&mut self, size: usize) {
         let size = match self.max_size_update {
@@ -173,13 +194,26 @@ impl Decoder {
     }
 
     /// Decodes the headers found in the given buffer.
-    pub fn decode<F>(
+    pub(crate) fn decode<F>(
         &mut self,
         src: &mut Cursor<&mut BytesMut>,
         mut f: F,
     ) -> Result<(), DecoderError>
     where
         F: FnMut(Header),
+    {
+        self.decode_with(src, |header, _| f(header))
+    }
+
+    /// Decodes the headers found in the given buffer, also telling `f`
+    /// whether each header was sent as a never-indexed literal.
+    fn decode_with<F>(
+        &mut self,
+        src: &mut Cursor<&mut BytesMut>,
+        mut f: F,
+    ) -> Result<(), DecoderError>
+    where
+        F: FnMut(Header, bool),
     {
         use self::Representation::*;
 
@@ -204,7 +238,7 @@ impl Decoder {
                     can_resize = false;
                     let entry = self.decode_indexed(src)?;
                     consume(src);
-                    f(entry);
+                    f(entry, false);
                 }
                 LiteralWithIndexing => {
                     tracing::trace!(rem = src.remaining(), kind = %"LiteralWithIndexing");
@@ -215,14 +249,14 @@ impl Decoder {
                     self.table.insert(entry.clone());
                     consume(src);
 
-                    f(entry);
+                    f(entry, false);
                 }
                 LiteralWithoutIndexing => {
                     tracing::trace!(rem = src.remaining(), kind = %"LiteralWithoutIndexing");
                     can_resize = false;
                     let entry = self.decode_literal(src, false)?;
                     consume(src);
-                    f(entry);
+                    f(entry, false);
                 }
                 LiteralNeverIndexed => {
                     tracing::trace!(rem = src.remaining(), kind = %"LiteralNeverIndexed");
@@ -230,9 +264,7 @@ impl Decoder {
                     let entry = self.decode_literal(src, false)?;
                     consume(src);
 
-                    // TODO: Track that this should never be indexed
-
-                    f(entry);
+                    f(entry, true);
                 }
                 SizeUpdate => {
                     tracing::trace!(rem = src.remaining(), kind = %"SizeUpdate");
@@ -353,6 +385,63 @@ impl Decoder {
     }
 }
 
+#[cfg(feature = "hpack")]
+impl Decoder {
+    /// Decodes a complete header block, as found in the `HEADERS` or
+    /// `PUSH_PROMISE` frame and the `CONTINUATION` frames that follow it.
+    ///
+    /// Fields sent as never-indexed literals are returned with
+    /// [`HpackIndexing::NeverIndexed`], which intermediaries must preserve
+    /// when encoding them again.
+    ///
+    /// The dynamic table is left in an unspecified state when an error is
+    /// returned, so the decoder should not be used anymore afterwards.
+    ///
+    /// [`HpackIndexing::NeverIndexed`]: ../ext/enum.HpackIndexing.html#variant.NeverIndexed
+    pub fn decode_fields(&mut self, src: &[u8]) -> Result<Vec<HeaderField>, DecoderError> {
+        use crate::ext::{HeaderIndexing, HpackIndexing};
+
+        let mut buf = BytesMut::from(src);
+        let mut fields = Vec::new();
+
+        self.decode_with(&mut Cursor::new(&mut buf), |header, never_indexed| {
+            let indexing = if never_indexed {
+                Some(HeaderIndexing::new(HpackIndexing::NeverIndexed))
+            } else {
+                None
+            };
+            fields.push(HeaderField::from_header(header, indexing));
+        })?;
+
+        Ok(fields)
+    }
+
+    /// Returns the maximum size of the dynamic table, in octets, as last set
+    /// by the peer.
+    pub fn max_size(&self) -> usize {
+        self.table.max_size
+    }
+
+    /// Returns the current size of the dynamic table, in octets.
+    pub fn table_size(&self) -> usize {
+        self.table.size()
+    }
+
+    /// Returns the number of entries in the dynamic table.
+    pub fn table_len(&self) -> usize {
+        self.table.entries.len()
+    }
+
+    /// Returns the `(name, value)` pairs of the dynamic table, from the most
+    /// recently inserted entry (index 62) to the oldest one.
+    pub fn dynamic_table(&self) -> impl Iterator<Item = (&[u8], &[u8])> + '_ {
+        self.table
+            .entries
+            .iter()
+            .map(|header| (header.name_slice(), header.value_slice()))
+    }
+}
+
 impl Default for Decoder {
     fn default() -> Decoder {
         Decoder::new(4096)
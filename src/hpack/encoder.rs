@@ -1,13 +1,21 @@
-use super::table::{Index, Table};
+use super::table::{self, Index, Table};
+#[cfg(feature = "hpack")]
+use super::HeaderField;
 use super::{huffman, Header};
+use crate::ext::{HeaderIndexing, HpackIndexing};
 
 use bytes::{BufMut, BytesMut};
 use http::header::{HeaderName, HeaderValue};
 
+/// Encodes headers using HPACK
 #[derive(Debug)]
 pub struct Encoder {
     table: Table,
     size_update: Option<SizeUpdate>,
+    /// Octets of the names and values of the encoded header fields.
+    field_octets: u64,
+    /// Octets of the encoded header blocks.
+    block_octets: u64,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -17,10 +25,14 @@ enum SizeUpdate {
 }
 
 impl Encoder {
+    /// Creates a new `Encoder` with a dynamic table of `max_size` octets,
+    /// preallocating room for `capacity` entries.
     pub fn new(max_size: usize, capacity: usize) -> Encoder {
         Encoder {
             table: Table::new(max_size, capacity),
             size_update: None,
+            field_octets: 0,
+            block_octets: 0,
         }
     }
 
@@ -58,22 +70,69 @@ impl Encoder {
     }
 
     /// Encode a set of headers into the provide buffer
-    pub fn encode<I>(&mut self, headers: I, dst: &mut BytesMut)
+    pub(crate) fn encode<I>(&mut self, headers: I, dst: &mut BytesMut)
     where
         I: IntoIterator<Item = Header<Option<HeaderName>>>,
+    {
+        self.encode_with(headers, |_| None, dst)
+    }
+
+    /// Encode a set of headers into the provided buffer, using the HPACK
+    /// representation returned by `indexing` for header fields it returns
+    /// `Some` for.
+    pub(crate) fn encode_with<I, F>(&mut self, headers: I, mut indexing: F, dst: &mut BytesMut)
+    where
+        I: IntoIterator<Item = Header<Option<HeaderName>>>,
+        F: FnMut(&HeaderName) -> Option<HeaderIndexing>,
     {
         let span = tracing::trace_span!("hpack::encode");
         let _e = span.enter();
 
+        let start = dst.len();
         self.encode_size_updates(dst);
 
         let mut last_index = None;
+        let mut last_name: Option<(HeaderName, HeaderIndexing)> = None;
+        let mut last_name_len = 0;
 
         for header in headers {
-            match header.reify() {
+            let header = match header.reify() {
+                // Fields with a chosen representation are encoded one by one,
+                // so give the value its name back.
+                Err(value) if last_name.is_some() => {
+                    let name = last_name.as_ref().unwrap().0.clone();
+                    Ok(Header::Field { name, value })
+                }
+                res => res,
+            };
+
+            self.field_octets += match header {
+                Ok(ref header) => {
+                    if let Header::Field { ref name, .. } = *header {
+                        last_name_len = name.as_str().len();
+                    }
+                    (header.len() - 32) as u64
+                }
+                Err(ref value) => (last_name_len + value.len()) as u64,
+            };
+
+            match header {
                 // The header has an associated name. In which case, try to
                 // index it in the table.
                 Ok(header) => {
+                    last_name = match header {
+                        Header::Field { ref name, .. } => {
+                            indexing(name).map(|indexing| (name.clone(), indexing))
+                        }
+                        _ => None,
+                    };
+
+                    if let Some((_, indexing)) = last_name {
+                        self.encode_field_with(header, indexing, dst);
+                        last_index = None;
+                        continue;
+                    }
+
                     let index = self.table.index(header);
                     self.encode_header(&index, dst);
 
@@ -94,6 +153,57 @@ impl Encoder {
                 }
             }
         }
+
+        self.block_octets += (dst.len() - start) as u64;
+    }
+
+    /// Returns the octets of the encoded header blocks, divided by the octets
+    /// of the names and values of the header fields they encode.
+    pub(crate) fn compression_ratio(&self) -> Option<f64> {
+        if self.field_octets == 0 {
+            return None;
+        }
+
+        Some(self.block_octets as f64 / self.field_octets as f64)
+    }
+
+    fn encode_field_with(&mut self, header: Header, indexing: HeaderIndexing, dst: &mut BytesMut) {
+        let statik = if indexing.is_static_name() {
+            table::index_static(&header)
+        } else {
+            None
+        };
+
+        // Sensitive values never make it into the dynamic table.
+        let indexing = match indexing.indexing() {
+            HpackIndexing::Incremental if header.is_sensitive() => HpackIndexing::NeverIndexed,
+            indexing => indexing,
+        };
+
+        match indexing {
+            HpackIndexing::Incremental => {
+                let index = match statik {
+                    Some((n, true)) => Index::Indexed(n, header),
+                    // The field would not fit in the table anyway.
+                    _ if header.len() > self.table.max_size() => Index::new(statik, header),
+                    _ => self.table.index_dynamic(header, statik),
+                };
+                self.encode_header(&index, dst);
+            }
+            HpackIndexing::WithoutIndexing | HpackIndexing::NeverIndexed => {
+                let never = indexing == HpackIndexing::NeverIndexed;
+
+                match statik {
+                    Some((n, _)) => encode_not_indexed(n, header.value_slice(), never, dst),
+                    None => encode_not_indexed2(
+                        header.name().as_slice(),
+                        header.value_slice(),
+                        never,
+                        dst,
+                    ),
+                }
+            }
+        }
     }
 
     fn encode_size_updates(&mut self, dst: &mut BytesMut) {
@@ -182,6 +292,68 @@ impl Encoder {
     }
 }
 
+#[cfg(feature = "hpack")]
+impl Encoder {
+    /// Encodes `fields` as one header block into `dst`.
+    ///
+    /// Any dynamic table size update queued with [`update_max_size`] is
+    /// emitted first. Fields are encoded with the representation set by
+    /// [`HeaderField::with_indexing`], or otherwise with the same heuristics
+    /// h2 uses for its own header blocks.
+    ///
+    /// [`update_max_size`]: #method.update_max_size
+    /// [`HeaderField::with_indexing`]: struct.HeaderField.html#method.with_indexing
+    pub fn encode_fields<'a, I>(&mut self, fields: I, dst: &mut BytesMut)
+    where
+        I: IntoIterator<Item = &'a HeaderField>,
+    {
+        let span = tracing::trace_span!("hpack::encode");
+        let _e = span.enter();
+
+        self.encode_size_updates(dst);
+
+        for field in fields {
+            let header = field.header().clone();
+
+            match field.indexing() {
+                Some(indexing) => self.encode_field_with(header, indexing, dst),
+                None => {
+                    let index = self.table.index(header);
+                    self.encode_header(&index, dst);
+                }
+            }
+        }
+    }
+
+    /// Returns the maximum size of the dynamic table, in octets.
+    ///
+    /// A size queued with [`update_max_size`] only takes effect with the next
+    /// encoded header block.
+    ///
+    /// [`update_max_size`]: #method.update_max_size
+    pub fn max_size(&self) -> usize {
+        self.table.max_size()
+    }
+
+    /// Returns the current size of the dynamic table, in octets.
+    pub fn table_size(&self) -> usize {
+        self.table.size()
+    }
+
+    /// Returns the number of entries in the dynamic table.
+    pub fn table_len(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Returns the `(name, value)` pairs of the dynamic table, from the most
+    /// recently inserted entry (index 62) to the oldest one.
+    pub fn dynamic_table(&self) -> impl Iterator<Item = (&[u8], &[u8])> + '_ {
+        self.table
+            .iter()
+            .map(|header| (header.name_slice(), header.value_slice()))
+    }
+}
+
 impl Default for Encoder {
     fn default() -> Encoder {
         Encoder::new(4096, 0)
@@ -693,6 +865,85 @@ mod test {
         // Not sure what the best way to do this is.
     }
 
+    #[test]
+    fn test_encode_never_indexed_with_static_name() {
+        let mut encoder = Encoder::default();
+        let indexing = HeaderIndexing::new(HpackIndexing::NeverIndexed);
+        let res = encode_with(
+            &mut encoder,
+            vec![header("authorization", "12345")],
+            indexing,
+        );
+
+        assert_eq!(&[0b11111, 8], &res[..2]);
+        assert_eq!(0x80 | 4, res[2]);
+        assert_eq!("12345", huff_decode(&res[3..]));
+        assert_eq!(0, encoder.table.len());
+    }
+
+    #[test]
+    fn test_encode_without_indexing_with_literal_name() {
+        let mut encoder = Encoder::default();
+        let indexing = HeaderIndexing::new(HpackIndexing::WithoutIndexing).static_name(false);
+        let hdrs = vec![
+            header("user-agent", "h2"),
+            Header::Field {
+                name: None,
+                value: HeaderValue::from_static("h2"),
+            },
+        ];
+        let res = encode_with(&mut encoder, hdrs, indexing);
+
+        // Both values are sent with a literal name
+        assert_eq!(res.len() % 2, 0);
+        let (first, second) = res.split_at(res.len() / 2);
+        assert_eq!(first, second);
+        assert_eq!(0, first[0]);
+        assert_eq!(0x80 | 7, first[1]);
+        assert_eq!("user-agent", huff_decode(&first[2..9]));
+        assert_eq!(0, encoder.table.len());
+    }
+
+    #[test]
+    fn test_encode_incremental_overrides_skip_value_index() {
+        let mut encoder = Encoder::default();
+        let indexing = HeaderIndexing::new(HpackIndexing::Incremental);
+
+        let res = encode_with(&mut encoder, vec![header("cookie", "a=1")], indexing);
+        assert_eq!(0b0100_0000 | 32, res[0]);
+        assert_eq!(1, encoder.table.len());
+
+        let res = encode_with(&mut encoder, vec![header("cookie", "a=1")], indexing);
+        assert_eq!(&[0x80 | 62], &res[..]);
+    }
+
+    #[test]
+    fn test_encode_incremental_keeps_sensitive_values_out_of_table() {
+        let mut encoder = Encoder::default();
+        let indexing = HeaderIndexing::new(HpackIndexing::Incremental);
+        let mut value = HeaderValue::from_static("secret");
+        value.set_sensitive(true);
+        let hdrs = vec![Header::Field {
+            name: Some(http::header::COOKIE),
+            value,
+        }];
+
+        let res = encode_with(&mut encoder, hdrs, indexing);
+        assert_eq!(0b10000 | 15, res[0]);
+        assert_eq!(32 - 15, res[1]);
+        assert_eq!(0, encoder.table.len());
+    }
+
+    fn encode_with(
+        e: &mut Encoder,
+        hdrs: Vec<Header<Option<HeaderName>>>,
+        indexing: HeaderIndexing,
+    ) -> BytesMut {
+        let mut dst = BytesMut::with_capacity(1024);
+        e.encode_with(hdrs, |_| Some(indexing), &mut dst);
+        dst
+    }
+
     fn encode(e: &mut Encoder, hdrs: Vec<Header<Option<HeaderName>>>) -> BytesMut {
         let mut dst = BytesMut::with_capacity(1024);
         e.encode(hdrs, &mut dst);
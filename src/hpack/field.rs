@@ -0,0 +1,128 @@
+use super::{DecoderError, Header};
+use crate::ext::HeaderIndexing;
+
+use bytes::Bytes;
+
+/// A header field of an HPACK header block.
+///
+/// The name is either a regular, lowercase header name or one of the
+/// `:authority`, `:method`, `:path`, `:protocol`, `:scheme` and `:status`
+/// pseudo headers.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HeaderField {
+    header: Header,
+    indexing: Option<HeaderIndexing>,
+}
+
+impl HeaderField {
+    /// Creates a header field from its name and value.
+    ///
+    /// Returns an error if `name` is neither a valid header name nor a known
+    /// pseudo header, or if `value` is not valid for it.
+    pub fn new(name: Bytes, value: Bytes) -> Result<HeaderField, DecoderError> {
+        Ok(HeaderField::from_header(Header::new(name, value)?, None))
+    }
+
+    pub(super) fn from_header(header: Header, indexing: Option<HeaderIndexing>) -> HeaderField {
+        HeaderField { header, indexing }
+    }
+
+    /// Sets the HPACK representation used when encoding this field.
+    pub fn with_indexing(mut self, indexing: HeaderIndexing) -> HeaderField {
+        self.indexing = Some(indexing);
+        self
+    }
+
+    /// Returns the HPACK representation of this field, if one was set or
+    /// decoded.
+    pub fn indexing(&self) -> Option<HeaderIndexing> {
+        self.indexing
+    }
+
+    /// Returns the field name.
+    pub fn name(&self) -> &[u8] {
+        self.header.name_slice()
+    }
+
+    /// Returns the field value.
+    pub fn value(&self) -> &[u8] {
+        self.header.value_slice()
+    }
+
+    /// Returns the size of the field in the dynamic table, in octets.
+    pub fn size(&self) -> usize {
+        self.header.len()
+    }
+
+    pub(super) fn header(&self) -> &Header {
+        &self.header
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ext::HpackIndexing;
+    use crate::hpack::{Decoder, Encoder};
+
+    fn field(name: &'static str, value: &'static str) -> HeaderField {
+        HeaderField::new(
+            Bytes::from_static(name.as_bytes()),
+            Bytes::from_static(value.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn round_trip_through_dynamic_tables() {
+        let mut encoder = Encoder::default();
+        let mut decoder = Decoder::default();
+        let fields = vec![
+            field(":method", "GET"),
+            field(":path", "/index.html"),
+            field("user-agent", "h2"),
+            field("authorization", "secret")
+                .with_indexing(HeaderIndexing::new(HpackIndexing::NeverIndexed)),
+        ];
+
+        for _ in 0..2 {
+            let mut dst = bytes::BytesMut::new();
+            encoder.encode_fields(&fields, &mut dst);
+            assert_eq!(decoder.decode_fields(&dst).unwrap(), fields);
+        }
+
+        let table: Vec<_> = decoder.dynamic_table().collect();
+        assert_eq!(table, [(&b"user-agent"[..], &b"h2"[..])]);
+        assert!(encoder.dynamic_table().eq(decoder.dynamic_table()));
+        assert_eq!(encoder.table_size(), 32 + 10 + 2);
+        assert_eq!(decoder.table_size(), encoder.table_size());
+    }
+
+    #[test]
+    fn size_update_resizes_decoder_table() {
+        let mut encoder = Encoder::default();
+        let mut decoder = Decoder::default();
+        let fields = [field("x-custom", "value")];
+
+        let mut dst = bytes::BytesMut::new();
+        encoder.encode_fields(&fields, &mut dst);
+        decoder.decode_fields(&dst).unwrap();
+        assert_eq!(decoder.table_len(), 1);
+
+        encoder.update_max_size(0);
+        let mut dst = bytes::BytesMut::new();
+        encoder.encode_fields(&fields, &mut dst);
+        assert_eq!(dst[0], 0b0010_0000);
+        decoder.decode_fields(&dst).unwrap();
+
+        assert_eq!(encoder.max_size(), 0);
+        assert_eq!(decoder.max_size(), 0);
+        assert_eq!(decoder.table_len(), 0);
+    }
+
+    #[test]
+    fn invalid_name_is_rejected() {
+        let res = HeaderField::new(Bytes::from_static(b":foo"), Bytes::new());
+        assert_eq!(res, Err(DecoderError::InvalidPseudoheader));
+    }
+}
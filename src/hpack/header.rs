@@ -130,6 +130,19 @@ impl Header {
         }
     }
 
+    #[cfg(feature = "hpack")]
+    pub fn name_slice(&self) -> &[u8] {
+        match *self {
+            Header::Field { ref name, .. } => name.as_ref(),
+            Header::Authority(..) => b":authority",
+            Header::Method(..) => b":method",
+            Header::Scheme(..) => b":scheme",
+            Header::Path(..) => b":path",
+            Header::Protocol(..) => b":protocol",
+            Header::Status(..) => b":status",
+        }
+    }
+
     pub fn value_slice(&self) -> &[u8] {
         match *self {
             Header::Field { ref value, .. } => value.as_ref(),
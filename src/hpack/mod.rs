@@ -1,5 +1,38 @@
+//! HPACK header compression, as specified in [RFC 7541].
+//!
+//! This is the implementation h2 uses for its own connections. It is exposed
+//! with the `hpack` feature for tools that need to encode or decode header
+//! blocks on their own, such as proxies or traffic analyzers.
+//!
+//! An [`Encoder`] and a [`Decoder`] each maintain one dynamic table, and
+//! must see every header block of one direction of a connection in order.
+//!
+//! # Examples
+//!
+//! ```
+//! use bytes::{Bytes, BytesMut};
+//! use h2::hpack::{Decoder, Encoder, HeaderField};
+//!
+//! let fields = vec![
+//!     HeaderField::new(Bytes::from_static(b":status"), Bytes::from_static(b"200")).unwrap(),
+//!     HeaderField::new(Bytes::from_static(b"server"), Bytes::from_static(b"h2")).unwrap(),
+//! ];
+//!
+//! let mut encoder = Encoder::default();
+//! let mut block = BytesMut::new();
+//! encoder.encode_fields(&fields, &mut block);
+//!
+//! let mut decoder = Decoder::default();
+//! assert_eq!(decoder.decode_fields(&block).unwrap(), fields);
+//! assert_eq!(decoder.table_len(), 1);
+//! ```
+//!
+//! [RFC 7541]: https://datatracker.ietf.org/doc/html/rfc7541
+
 mod decoder;
 mod encoder;
+#[cfg(feature = "hpack")]
+mod field;
 pub(crate) mod header;
 pub(crate) mod huffman;
 mod table;
@@ -9,4 +42,6 @@ mod test;
 
 pub use self::decoder::{Decoder, DecoderError, NeedMore};
 pub use self::encoder::Encoder;
-pub use self::header::{BytesStr, Header};
+#[cfg(feature = "hpack")]
+pub use self::field::HeaderField;
+pub(crate) use self::header::{BytesStr, Header};
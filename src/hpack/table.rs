@@ -157,7 +157,7 @@ impl Table {
         self.index_dynamic(header, statik)
     }
 
-    fn index_dynamic(&mut self, header: Header, statik: Option<(usize, bool)>) -> Index {
+    pub fn index_dynamic(&mut self, header: Header, statik: Option<(usize, bool)>) -> Index {
         debug_assert!(self.assert_valid_state("one"));
 
         if header.len() + self.size < self.max_size || !header.is_sensitive() {
@@ -263,6 +263,7 @@ impl Table {
         }
     }
 
+    #[allow(clippy::mem_replace_option_with_some)]
     fn index_vacant(
         &mut self,
         header: Header,
@@ -621,7 +622,7 @@ impl Table {
     }
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "hpack"))]
 impl Table {
     /// Returns the number of headers in the table
     pub fn len(&self) -> usize {
@@ -634,8 +635,16 @@ impl Table {
     }
 }
 
+#[cfg(feature = "hpack")]
+impl Table {
+    /// Returns the headers in the table, most recently inserted first
+    pub fn iter(&self) -> impl Iterator<Item = &Header> {
+        self.slots.iter().map(|slot| &slot.header)
+    }
+}
+
 impl Index {
-    fn new(v: Option<(usize, bool)>, e: Header) -> Index {
+    pub fn new(v: Option<(usize, bool)>, e: Header) -> Index {
         match v {
             None => Index::NotIndexed(e),
             Some((n, true)) => Index::Indexed(n, e),
@@ -674,7 +683,7 @@ fn hash_header(header: &Header) -> HashValue {
 
 /// Checks the static table for the header. If found, returns the index and a
 /// boolean representing if the value matched as well.
-fn index_static(header: &Header) -> Option<(usize, bool)> {
+pub fn index_static(header: &Header) -> Option<(usize, bool)> {
     match *header {
         Header::Field {
             ref name,
@@ -46,6 +46,11 @@
 //!
 //! See the [Starting HTTP/2] in the specification for more details.
 //!
+//! Servers can complete an HTTP/1.1 upgrade, once the `101` response is
+//! written, with [`server::Builder::handshake_upgrade`], and tell prior
+//! knowledge apart from HTTP/1 on a shared port with
+//! [`server::detect_preface`].
+//!
 //! # Flow control
 //!
 //! [Flow control] is a fundamental feature of HTTP/2. The `h2` library
@@ -105,11 +110,20 @@ macro_rules! ready {
     };
 }
 
-#[cfg_attr(feature = "unstable", allow(missing_docs))]
+#[cfg(not(any(feature = "unstable", feature = "codec")))]
 mod codec;
+
+#[cfg(any(feature = "unstable", feature = "codec"))]
+#[cfg_attr(feature = "unstable", allow(missing_docs))]
+pub mod codec;
 mod error;
+
+#[cfg(not(feature = "hpack"))]
 mod hpack;
 
+#[cfg(feature = "hpack")]
+pub mod hpack;
+
 #[cfg(not(feature = "unstable"))]
 mod proto;
 
@@ -117,24 +131,35 @@ mod proto;
 #[allow(missing_docs)]
 pub mod proto;
 
-#[cfg(not(feature = "unstable"))]
+#[cfg(not(any(feature = "unstable", feature = "codec")))]
 mod frame;
 
-#[cfg(feature = "unstable")]
-#[allow(missing_docs)]
+#[cfg(any(feature = "unstable", feature = "codec"))]
+#[cfg_attr(feature = "unstable", allow(missing_docs))]
 pub mod frame;
 
 pub mod client;
+#[cfg(feature = "futures-io-compat")]
+pub mod compat;
 pub mod ext;
+pub mod padding;
+pub mod profiles;
+pub mod scheduler;
 pub mod server;
 mod share;
+pub mod stats;
+pub mod timer;
+pub mod tunnel;
 
 #[cfg(fuzzing)]
 #[cfg_attr(feature = "unstable", allow(missing_docs))]
 pub mod fuzz_bridge;
 
 pub use crate::error::{Error, Reason};
-pub use crate::share::{FlowControl, Ping, PingPong, Pong, RecvStream, SendStream, StreamId};
+pub use crate::share::{
+    FlowControl, Ping, PingPong, Pong, RecvStream, RecvStreamReader, SendStream, SendStreamWriter,
+    StreamId,
+};
 
 #[cfg(feature = "unstable")]
 pub use codec::{Codec, SendError, UserError};
@@ -0,0 +1,130 @@
+//! Padding of sent frames.
+//!
+//! HTTP/2 lets `DATA`, `HEADERS` and `PUSH_PROMISE` frames carry up to 255
+//! octets of padding, which hides the exact size of messages from traffic
+//! analysis. A [`Padding`] policy, set for a connection with
+//! [`client::Builder::padding`] or [`server::Builder::padding`], decides how
+//! much padding these frames carry. A stream can override the policy of its
+//! connection with [`SendStream::set_padding`] or
+//! [`SendResponse::set_padding`].
+//!
+//! Padding never makes a frame larger than the maximum frame size of the
+//! peer. The padding of `DATA` frames counts against flow control, so it is
+//! also reduced to the window left over by the capacity assigned to streams,
+//! and is omitted when the window is exhausted. Header blocks split over
+//! `CONTINUATION` frames are not padded.
+//!
+//! [`client::Builder::padding`]: crate::client::Builder::padding
+//! [`server::Builder::padding`]: crate::server::Builder::padding
+//! [`SendStream::set_padding`]: crate::SendStream::set_padding
+//! [`SendResponse::set_padding`]: crate::server::SendResponse::set_padding
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// How much padding sent frames carry.
+///
+/// The default policy adds no padding.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Padding(Policy);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Policy {
+    #[default]
+    None,
+    Fixed(u8),
+    Random(u8),
+    Block(u8),
+}
+
+/// Applies a padding policy to the frames of a connection.
+#[derive(Debug)]
+pub(crate) struct Padder {
+    padding: Padding,
+    /// State of the xorshift generator of random padding.
+    state: u64,
+}
+
+impl Padding {
+    /// Sends frames without padding.
+    pub fn none() -> Padding {
+        Padding(Policy::None)
+    }
+
+    /// Pads every frame with `len` octets.
+    pub fn fixed(len: u8) -> Padding {
+        Padding(Policy::Fixed(len))
+    }
+
+    /// Pads every frame with a random number of octets, from 0 to `max`.
+    pub fn random(max: u8) -> Padding {
+        Padding(Policy::Random(max))
+    }
+
+    /// Pads every frame so that the length of its payload is a multiple of
+    /// `size` octets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn block(size: u8) -> Padding {
+        assert!(size > 0, "padding block size must not be zero");
+        Padding(Policy::Block(size))
+    }
+
+    /// Returns whether the policy adds no padding.
+    pub fn is_none(&self) -> bool {
+        self.0 == Policy::None
+    }
+}
+
+impl Padder {
+    pub(crate) fn new(padding: Padding) -> Padder {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(0);
+
+        Padder {
+            padding,
+            // The state of xorshift must not be zero.
+            state: hasher.finish() | 1,
+        }
+    }
+
+    pub(crate) fn set_padding(&mut self, padding: Padding) {
+        self.padding = padding;
+    }
+
+    /// Returns the padding of a frame with a payload of `len` octets, which
+    /// can be at most `max_len` octets once padded, or `None` if the frame is
+    /// not padded.
+    ///
+    /// `padding` overrides the policy of the connection.
+    pub(crate) fn pad_len(
+        &mut self,
+        padding: Option<Padding>,
+        len: usize,
+        max_len: usize,
+    ) -> Option<u8> {
+        let pad_len = match padding.unwrap_or(self.padding).0 {
+            Policy::None => return None,
+            Policy::Fixed(pad_len) => pad_len as usize,
+            Policy::Random(max) => (self.next_random() % (max as u64 + 1)) as usize,
+            Policy::Block(size) => {
+                let size = size as usize;
+                // The pad length field is part of the payload.
+                (size - (len + 1) % size) % size
+            }
+        };
+
+        // The pad length field needs one octet.
+        let room = max_len.checked_sub(len + 1)?;
+        Some(pad_len.min(room) as u8)
+    }
+
+    fn next_random(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+}
@@ -0,0 +1,167 @@
+//! Connection profiles matching the HTTP/2 behavior of common clients.
+//!
+//! Besides the values carried by requests, HTTP/2 clients differ in how they
+//! set up a connection: which `SETTINGS` they send and in what order, how much
+//! they grow the connection window right away, whether they build a priority
+//! tree out of `PRIORITY` frames, and how they order request pseudo headers.
+//! Together, these make up the [Akamai HTTP/2 fingerprint] of a client.
+//!
+//! A [`Profile`] bundles those values for a given client release, and
+//! [`client::Builder::profile`] applies all of them at once.
+//!
+//! # Examples
+//!
+//! ```
+//! # use tokio::io::{AsyncRead, AsyncWrite};
+//! # use h2::client::*;
+//! # use h2::profiles::Profile;
+//! # use bytes::Bytes;
+//! #
+//! # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+//! # -> Result<((SendRequest<Bytes>, Connection<T, Bytes>)), h2::Error>
+//! # {
+//! let client_fut = Builder::new()
+//!     .profile(Profile::Firefox133)
+//!     .handshake(my_io);
+//! # client_fut.await
+//! # }
+//! #
+//! # pub fn main() {}
+//! ```
+//!
+//! [Akamai HTTP/2 fingerprint]: https://www.blackhat.com/docs/eu-17/materials/eu-17-Shuster-Passive-Fingerprinting-Of-HTTP2-Clients-wp.pdf
+//! [`client::Builder::profile`]: ../client/struct.Builder.html#method.profile
+
+use crate::ext::{PseudoHeader, PseudoHeaderOrder, StreamDependency};
+use crate::frame::DEFAULT_SETTINGS_HEADER_TABLE_SIZE;
+
+use self::PseudoHeader::{Authority, Method, Path, Scheme};
+
+const HEADER_TABLE_SIZE: u16 = 0x1;
+const ENABLE_PUSH: u16 = 0x2;
+const MAX_CONCURRENT_STREAMS: u16 = 0x3;
+const INITIAL_WINDOW_SIZE: u16 = 0x4;
+const MAX_FRAME_SIZE: u16 = 0x5;
+const MAX_HEADER_LIST_SIZE: u16 = 0x6;
+const NO_RFC7540_PRIORITIES: u16 = 0x9;
+
+/// The HTTP/2 connection setup of a known client.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Profile {
+    /// Google Chrome 131.
+    Chrome131,
+    /// Microsoft Edge 131.
+    Edge131,
+    /// Mozilla Firefox 117, the last releases sending `PRIORITY` frames.
+    Firefox117,
+    /// Mozilla Firefox 133.
+    Firefox133,
+    /// Apple Safari 18.
+    Safari18,
+}
+
+impl Profile {
+    /// Returns the `(identifier, value)` pairs of the initial `SETTINGS`
+    /// frame, in the order they are sent.
+    pub fn settings(&self) -> &'static [(u16, u32)] {
+        match *self {
+            Profile::Chrome131 | Profile::Edge131 => &[
+                (HEADER_TABLE_SIZE, 65_536),
+                (ENABLE_PUSH, 0),
+                (INITIAL_WINDOW_SIZE, 6_291_456),
+                (MAX_HEADER_LIST_SIZE, 262_144),
+            ],
+            Profile::Firefox117 => &[
+                (HEADER_TABLE_SIZE, 65_536),
+                (INITIAL_WINDOW_SIZE, 131_072),
+                (MAX_FRAME_SIZE, 16_384),
+            ],
+            Profile::Firefox133 => &[
+                (HEADER_TABLE_SIZE, 65_536),
+                (ENABLE_PUSH, 0),
+                (INITIAL_WINDOW_SIZE, 131_072),
+                (MAX_FRAME_SIZE, 16_384),
+            ],
+            Profile::Safari18 => &[
+                (ENABLE_PUSH, 0),
+                (MAX_CONCURRENT_STREAMS, 100),
+                (INITIAL_WINDOW_SIZE, 2_097_152),
+                (NO_RFC7540_PRIORITIES, 1),
+            ],
+        }
+    }
+
+    /// Returns the increment of the connection level `WINDOW_UPDATE` frame
+    /// sent right after the initial `SETTINGS` frame, if any.
+    pub fn connection_window_update(&self) -> Option<u32> {
+        match *self {
+            Profile::Chrome131 | Profile::Edge131 => Some(15_663_105),
+            Profile::Firefox117 | Profile::Firefox133 => Some(12_517_377),
+            Profile::Safari18 => Some(10_420_225),
+        }
+    }
+
+    /// Returns the `PRIORITY` frames sent as part of the connection preface,
+    /// as `(stream_id, dependency)` pairs in the order they are sent.
+    pub fn priority_frames(&self) -> Vec<(u32, StreamDependency)> {
+        match *self {
+            Profile::Firefox117 => vec![
+                (3, StreamDependency::new(0, 201, false)),
+                (5, StreamDependency::new(0, 101, false)),
+                (7, StreamDependency::new(0, 1, false)),
+                (9, StreamDependency::new(7, 1, false)),
+                (11, StreamDependency::new(3, 1, false)),
+                (13, StreamDependency::new(0, 241, false)),
+            ],
+            Profile::Chrome131 | Profile::Edge131 | Profile::Firefox133 | Profile::Safari18 => {
+                Vec::new()
+            }
+        }
+    }
+
+    /// Returns the order in which request pseudo headers are encoded.
+    pub fn pseudo_header_order(&self) -> PseudoHeaderOrder {
+        match *self {
+            Profile::Chrome131 | Profile::Edge131 => {
+                PseudoHeaderOrder::new([Method, Authority, Scheme, Path])
+            }
+            Profile::Firefox117 | Profile::Firefox133 => {
+                PseudoHeaderOrder::new([Method, Path, Authority, Scheme])
+            }
+            Profile::Safari18 => PseudoHeaderOrder::new([Method, Scheme, Authority, Path]),
+        }
+    }
+
+    /// Returns the stream dependency sent with request `HEADERS` frames, if
+    /// any.
+    pub fn request_stream_dependency(&self) -> Option<StreamDependency> {
+        match *self {
+            Profile::Chrome131 | Profile::Edge131 => Some(StreamDependency::new(0, 256, true)),
+            Profile::Firefox117 => Some(StreamDependency::new(13, 42, false)),
+            Profile::Firefox133 => Some(StreamDependency::new(0, 42, false)),
+            Profile::Safari18 => None,
+        }
+    }
+
+    /// Returns the HPACK header table size advertised to the peer.
+    pub fn header_table_size(&self) -> u32 {
+        self.settings()
+            .iter()
+            .find(|&&(id, _)| id == HEADER_TABLE_SIZE)
+            .map_or(DEFAULT_SETTINGS_HEADER_TABLE_SIZE as u32, |&(_, value)| {
+                value
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_table_size_defaults_when_not_sent() {
+        assert_eq!(Profile::Chrome131.header_table_size(), 65_536);
+        assert_eq!(Profile::Safari18.header_table_size(), 4_096);
+    }
+}
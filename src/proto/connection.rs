@@ -1,5 +1,10 @@
 use crate::codec::UserError;
+use crate::ext::{ExtensionFrameHandlers, PseudoHeaderOrder, StreamDependency};
 use crate::frame::{Reason, StreamId};
+use crate::padding::Padding;
+use crate::scheduler::NewScheduler;
+use crate::stats::ConnectionStats;
+use crate::timer::SharedTimer;
 use crate::{client, server};
 
 use crate::frame::DEFAULT_INITIAL_WINDOW_SIZE;
@@ -45,9 +50,15 @@ where
     /// Pending GOAWAY frames to write.
     go_away: GoAway,
 
+    /// GOAWAY frames received from the peer.
+    recv_go_away: RecvGoAway,
+
     /// Ping/pong handler
     ping_pong: PingPong,
 
+    /// Extension frames handler
+    extension_frames: ExtensionFrames,
+
     /// Connection settings
     settings: Settings,
 
@@ -66,11 +77,22 @@ struct DynConnection<'a, B: Buf = Bytes> {
 
     go_away: &'a mut GoAway,
 
+    recv_go_away: &'a mut RecvGoAway,
+
     streams: DynStreams<'a, B>,
 
     error: &'a mut Option<frame::GoAway>,
 
     ping_pong: &'a mut PingPong,
+
+    extension_frames: &'a mut ExtensionFrames,
+}
+
+/// A frame sent right after the initial SETTINGS frame.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum PrefaceFrame {
+    WindowUpdate(frame::WindowUpdate),
+    Priority(frame::Priority),
 }
 
 #[derive(Debug, Clone)]
@@ -82,7 +104,22 @@ pub(crate) struct Config {
     pub reset_stream_max: usize,
     pub remote_reset_stream_max: usize,
     pub local_error_reset_streams_max: Option<usize>,
+    pub pseudo_header_order: PseudoHeaderOrder,
+    pub request_stream_dependency: Option<StreamDependency>,
+    pub rfc7540_priorities: bool,
+    pub adaptive_window: bool,
+    pub keep_alive_interval: Option<Duration>,
+    pub keep_alive_timeout: Duration,
+    pub keep_alive_while_idle: bool,
+    pub timer: Option<SharedTimer>,
+    pub padding: Padding,
+    pub push_policy: Option<client::PushPolicy>,
+    pub max_concurrent_pushes_per_stream: Option<usize>,
+    pub push_cache_size: usize,
+    pub scheduler: Option<NewScheduler>,
+    pub extension_frame_handlers: ExtensionFrameHandlers,
     pub settings: frame::Settings,
+    pub preface: Vec<PrefaceFrame>,
 }
 
 #[derive(Debug)]
@@ -103,7 +140,7 @@ where
     P: Peer,
     B: Buf,
 {
-    pub fn new(codec: Codec<T, Prioritized<B>>, config: Config) -> Connection<T, P, B> {
+    pub fn new(mut codec: Codec<T, Prioritized<B>>, config: Config) -> Connection<T, P, B> {
         fn streams_config(config: &Config) -> streams::Config {
             streams::Config {
                 initial_max_send_streams: config.initial_max_send_streams,
@@ -123,9 +160,58 @@ where
                     .max_concurrent_streams()
                     .map(|max| max as usize),
                 local_max_error_reset_streams: config.local_error_reset_streams_max,
+                pseudo_header_order: config.pseudo_header_order,
+                request_stream_dependency: config.request_stream_dependency,
+                rfc7540_priorities: config.rfc7540_priorities,
+                scheduler: config.scheduler.clone(),
+                push_policy: config.push_policy.clone(),
+                max_concurrent_pushes_per_stream: config.max_concurrent_pushes_per_stream,
+                push_cache_size: config.push_cache_size,
+            }
+        }
+        let mut streams = Streams::new(streams_config(&config));
+
+        codec.set_padding(config.padding);
+
+        // The initial SETTINGS frame has already been buffered, queue the
+        // rest of the preface right behind it.
+        for frame in config.preface {
+            match frame {
+                PrefaceFrame::WindowUpdate(frame) => {
+                    // The builder checked that the increments of the preface
+                    // fit in the connection window.
+                    streams
+                        .send_connection_window_update(frame.size_increment())
+                        .expect("invalid preface WINDOW_UPDATE increment");
+                    codec
+                        .buffer(frame.into())
+                        .expect("invalid WINDOW_UPDATE frame");
+                }
+                PrefaceFrame::Priority(frame) => {
+                    codec.buffer(frame.into()).expect("invalid PRIORITY frame");
+                }
             }
         }
-        let streams = Streams::new(streams_config(&config));
+
+        let bdp = if config.adaptive_window {
+            let initial_window_size = config
+                .settings
+                .initial_window_size()
+                .unwrap_or(DEFAULT_INITIAL_WINDOW_SIZE);
+            Some(Bdp::new(initial_window_size))
+        } else {
+            None
+        };
+
+        let keep_alive = config.keep_alive_interval.map(|interval| {
+            KeepAlive::new(
+                interval,
+                config.keep_alive_timeout,
+                config.keep_alive_while_idle,
+                config.timer.unwrap_or_default(),
+            )
+        });
+
         let span = tracing::debug_span!(parent: None, "Connection", peer = %P::NAME);
         span.follows_from(tracing::Span::current());
         Connection {
@@ -134,7 +220,9 @@ where
                 state: State::Open,
                 error: None,
                 go_away: GoAway::new(),
-                ping_pong: PingPong::new(),
+                recv_go_away: RecvGoAway::default(),
+                ping_pong: PingPong::new(bdp, keep_alive),
+                extension_frames: ExtensionFrames::new(P::r#dyn(), config.extension_frame_handlers),
                 settings: Settings::new(config.settings),
                 streams,
                 span,
@@ -157,6 +245,27 @@ where
         self.inner.settings.send_settings(settings)
     }
 
+    /// Grows the flow control windows to the bandwidth-delay product
+    /// estimated by adaptive flow control, if it has grown.
+    fn apply_adaptive_window(&mut self) {
+        let size = match self.inner.ping_pong.pending_window_size() {
+            Some(size) => size,
+            None => return,
+        };
+
+        // The connection window is never shrunk, as it may have been set
+        // larger than the estimate.
+        if size > self.inner.streams.target_connection_window_size() {
+            self.set_target_window_size(size);
+        }
+
+        // Existing streams are grown once the SETTINGS frame is acknowledged.
+        // If an earlier SETTINGS frame is still pending, try again later.
+        if self.set_initial_window_size(size).is_ok() {
+            self.inner.ping_pong.clear_pending_window_size();
+        }
+    }
+
     /// Send a new SETTINGS frame with extended CONNECT protocol enabled.
     pub(crate) fn set_enable_connect_protocol(&mut self) -> Result<(), UserError> {
         let mut settings = frame::Settings::default();
@@ -164,6 +273,32 @@ where
         self.inner.settings.send_settings(settings)
     }
 
+    /// Queues an extension frame to be sent.
+    pub(crate) fn send_extension_frame<F>(&mut self, frame: F) -> Result<(), UserError>
+    where
+        F: Into<PendingFrame>,
+    {
+        let frame = frame.into();
+
+        if frame.payload_len() > self.codec.max_send_frame_size() {
+            return Err(UserError::PayloadTooBig);
+        }
+
+        self.inner.extension_frames.send(frame);
+        Ok(())
+    }
+
+    /// Polls for the next ALTSVC frame received by a client.
+    pub(crate) fn poll_altsvc(&mut self, cx: &mut Context) -> Poll<Option<frame::AltSvc>> {
+        self.inner.extension_frames.poll_altsvc(cx)
+    }
+
+    /// Polls for the origin set of the next ORIGIN frame received by a
+    /// client.
+    pub(crate) fn poll_origin_set(&mut self, cx: &mut Context) -> Poll<Option<Vec<Bytes>>> {
+        self.inner.extension_frames.poll_origin_set(cx)
+    }
+
     /// Returns the maximum number of concurrent streams that may be initiated
     /// by this peer.
     pub(crate) fn max_send_streams(&self) -> usize {
@@ -176,6 +311,29 @@ where
         self.inner.streams.max_recv_streams()
     }
 
+    /// Returns a snapshot of the statistics of the connection.
+    pub(crate) fn stats(&self) -> ConnectionStats {
+        let mut stats = ConnectionStats {
+            sent: self.codec.send_stats().clone(),
+            received: self.codec.recv_stats().clone(),
+            hpack_compression_ratio: self.codec.hpack_compression_ratio(),
+            ping_rtt: self.inner.ping_pong.rtt(),
+            ..Default::default()
+        };
+        self.inner.streams.record_stats(&mut stats);
+        stats
+    }
+
+    /// Returns the Akamai fingerprint of the frames received from the peer.
+    pub(crate) fn peer_fingerprint(&self) -> Option<String> {
+        self.codec.recv_fingerprint()
+    }
+
+    /// Returns the Akamai fingerprint of the frames sent to the peer.
+    pub(crate) fn local_fingerprint(&self) -> Option<String> {
+        self.codec.send_fingerprint()
+    }
+
     #[cfg(feature = "unstable")]
     pub fn num_wired_streams(&self) -> usize {
         self.inner.streams.num_wired_streams()
@@ -197,6 +355,10 @@ where
             .settings
             .poll_send(cx, &mut self.codec, &mut self.inner.streams))?;
         ready!(self.inner.streams.send_pending_refusal(cx, &mut self.codec))?;
+        ready!(self
+            .inner
+            .extension_frames
+            .send_pending(cx, &mut self.codec))?;
 
         Poll::Ready(Ok(()))
     }
@@ -260,8 +422,23 @@ where
         self.inner.ping_pong.take_user_pings()
     }
 
+    /// Polls for the next GOAWAY frame received from the peer.
+    pub(crate) fn poll_recv_go_away(&mut self, cx: &mut Context) -> Poll<Option<frame::GoAway>> {
+        self.inner.recv_go_away.poll(cx)
+    }
+
     /// Advances the internal state of the connection.
     pub fn poll(&mut self, cx: &mut Context) -> Poll<Result<(), Error>> {
+        let result = ready!(self.poll_state(cx));
+
+        // No more frames are received for the user to poll.
+        self.inner.recv_go_away.close();
+        self.inner.extension_frames.close();
+
+        Poll::Ready(result)
+    }
+
+    fn poll_state(&mut self, cx: &mut Context) -> Poll<Result<(), Error>> {
         // XXX(eliza): cloning the span is unfortunately necessary here in
         // order to placate the borrow checker — `self` is mutably borrowed by
         // `poll2`, which means that we can't borrow `self.span` to enter it.
@@ -344,6 +521,16 @@ where
                     "graceful GOAWAY should be NO_ERROR"
                 );
             }
+            if self
+                .inner
+                .ping_pong
+                .poll_keep_alive(cx, self.inner.streams.has_streams())
+                .is_ready()
+            {
+                self.go_away_keep_alive_timeout(cx)?;
+                return Poll::Ready(Err(Error::KeepAliveTimedOut));
+            }
+
             ready!(self.poll_ready(cx))?;
 
             match self
@@ -363,9 +550,26 @@ where
                     return Poll::Ready(Ok(()));
                 }
             }
+
+            self.apply_adaptive_window();
         }
     }
 
+    /// Tells the peer that the connection is closed after a keep-alive
+    /// timeout, in case it is still reachable. This does not wait for the
+    /// connection to be writable, as it likely never will be.
+    fn go_away_keep_alive_timeout(&mut self, cx: &mut Context) -> Result<(), Error> {
+        if self.codec.poll_ready(cx)?.is_ready() {
+            let last_processed_id = self.inner.streams.as_dyn().last_processed_id();
+            let frame = frame::GoAway::new(last_processed_id, Reason::NO_ERROR);
+            self.codec
+                .buffer(frame.into())
+                .expect("invalid GOAWAY frame");
+            let _ = self.codec.flush(cx)?;
+        }
+        Ok(())
+    }
+
     fn clear_expired_reset_streams(&mut self) {
         self.inner.streams.clear_expired_reset_streams();
     }
@@ -380,18 +584,22 @@ where
         let ConnectionInner {
             state,
             go_away,
+            recv_go_away,
             streams,
             error,
             ping_pong,
+            extension_frames,
             ..
         } = self;
         let streams = streams.as_dyn();
         DynConnection {
             state,
             go_away,
+            recv_go_away,
             streams,
             error,
             ping_pong,
+            extension_frames,
         }
     }
 }
@@ -441,6 +649,10 @@ where
                 self.handle_go_away(reason, debug_data, initiator);
                 Ok(())
             }
+            Err(Error::Unprocessed(debug_data, reason)) => {
+                self.handle_go_away(reason, debug_data, Initiator::Remote);
+                Ok(())
+            }
             // Attempting to read a frame resulted in a stream level error.
             // This is handled by resetting the frame then trying to read
             // another frame.
@@ -485,6 +697,13 @@ where
                 // Return the error
                 Err(e)
             }
+            // No frame was received in response to a keep-alive PING. All
+            // active streams must be reset.
+            Err(Error::KeepAliveTimedOut) => {
+                tracing::debug!("Connection::poll; keep-alive timed out");
+                self.streams.handle_error(Error::KeepAliveTimedOut);
+                Err(Error::KeepAliveTimedOut)
+            }
         }
     }
 
@@ -511,6 +730,11 @@ where
 
     fn recv_frame(&mut self, frame: Option<Frame>) -> Result<ReceivedFrame, Error> {
         use crate::frame::Frame::*;
+
+        if frame.is_some() {
+            self.ping_pong.recv_frame();
+        }
+
         match frame {
             Some(Headers(frame)) => {
                 tracing::trace!(?frame, "recv HEADERS");
@@ -518,6 +742,7 @@ where
             }
             Some(Data(frame)) => {
                 tracing::trace!(?frame, "recv DATA");
+                self.ping_pong.recv_data(frame.flow_controlled_len());
                 self.streams.recv_data(frame)?;
             }
             Some(Reset(frame)) => {
@@ -539,6 +764,7 @@ where
                 // until they are all EOS. Once they are, State should
                 // transition to GoAway.
                 self.streams.recv_go_away(&frame)?;
+                self.recv_go_away.recv(frame.clone());
                 *self.error = Some(frame);
             }
             Some(Ping(frame)) => {
@@ -560,7 +786,30 @@ where
             }
             Some(Priority(frame)) => {
                 tracing::trace!(?frame, "recv PRIORITY");
-                // TODO: handle
+                self.streams.recv_priority(frame);
+            }
+            Some(PriorityUpdate(frame)) => {
+                tracing::trace!(?frame, "recv PRIORITY_UPDATE");
+                self.streams.recv_priority_update(frame)?;
+            }
+            Some(AltSvc(frame)) => {
+                tracing::trace!(?frame, "recv ALTSVC");
+                self.extension_frames.recv_altsvc(frame);
+            }
+            Some(Origin(frame)) => {
+                tracing::trace!(?frame, "recv ORIGIN");
+                self.extension_frames.recv_origin(frame);
+            }
+            Some(Extension(frame)) => {
+                tracing::trace!(?frame, "recv extension frame");
+                if !self.extension_frames.is_handled(frame.kind()) {
+                    // Unknown frames are ignored.
+                } else if frame.stream_id().is_zero() {
+                    self.extension_frames.recv(frame);
+                } else if let Some(frame) = self.streams.recv_extension(frame) {
+                    // The stream is not open.
+                    self.extension_frames.recv(frame);
+                }
             }
             None => {
                 tracing::trace!("codec closed");
@@ -597,6 +846,20 @@ where
         self.inner.streams.next_incoming()
     }
 
+    /// Applies the settings of an HTTP/1.1 request upgraded to HTTP/2, and
+    /// opens stream 1 to send its response.
+    pub(crate) fn recv_upgrade(
+        &mut self,
+        settings: &frame::Settings,
+    ) -> Result<StreamRef<B>, Error> {
+        self.inner.settings.recv_upgrade_settings(
+            settings,
+            &mut self.codec,
+            &mut self.inner.streams,
+        )?;
+        self.inner.streams.recv_upgrade()
+    }
+
     // Graceful shutdown only makes sense for server peers.
     pub fn go_away_gracefully(&mut self) {
         if self.inner.go_away.is_going_away() {
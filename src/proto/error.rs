@@ -7,10 +7,18 @@ use std::io;
 
 /// Either an H2 reason  or an I/O error
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum Error {
+    /// A stream error, resetting the given stream.
     Reset(StreamId, Reason, Initiator),
+    /// A connection error, closing the connection with the given debug data.
     GoAway(Bytes, Reason, Initiator),
+    /// A GOAWAY received from the peer, failing a stream it did not process.
+    Unprocessed(Bytes, Reason),
+    /// An I/O error, with its description.
     Io(io::ErrorKind, Option<String>),
+    /// A keep-alive PING was not acknowledged in time.
+    KeepAliveTimedOut,
 }
 
 pub struct GoAway {
@@ -18,10 +26,14 @@ pub struct GoAway {
     pub reason: Reason,
 }
 
+/// The side that caused an [`Error`].
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Initiator {
+    /// The local user of the library.
     User,
+    /// The library itself, for instance after receiving an invalid frame.
     Library,
+    /// The remote peer.
     Remote,
 }
 
@@ -29,7 +41,8 @@ impl Error {
     pub(crate) fn is_local(&self) -> bool {
         match *self {
             Self::Reset(_, _, initiator) | Self::GoAway(_, _, initiator) => initiator.is_local(),
-            Self::Io(..) => true,
+            Self::Unprocessed(..) => false,
+            Self::Io(..) | Self::KeepAliveTimedOut => true,
         }
     }
 
@@ -56,6 +69,10 @@ impl Error {
     pub(crate) fn remote_go_away(debug_data: Bytes, reason: Reason) -> Self {
         Self::GoAway(debug_data, reason, Initiator::Remote)
     }
+
+    pub(crate) fn unprocessed(debug_data: Bytes, reason: Reason) -> Self {
+        Self::Unprocessed(debug_data, reason)
+    }
 }
 
 impl Initiator {
@@ -74,9 +91,12 @@ impl Initiator {
 impl fmt::Display for Error {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            Self::Reset(_, reason, _) | Self::GoAway(_, reason, _) => reason.fmt(fmt),
+            Self::Reset(_, reason, _)
+            | Self::GoAway(_, reason, _)
+            | Self::Unprocessed(_, reason) => reason.fmt(fmt),
             Self::Io(_, Some(ref inner)) => inner.fmt(fmt),
             Self::Io(kind, None) => io::Error::from(kind).fmt(fmt),
+            Self::KeepAliveTimedOut => fmt.write_str("keep-alive timed out"),
         }
     }
 }
@@ -0,0 +1,246 @@
+use crate::codec::{Codec, UserError};
+use crate::ext::ExtensionFrameHandlers;
+use crate::frame::{self, Frame};
+use crate::proto::peer;
+
+use bytes::{Buf, Bytes};
+use std::collections::VecDeque;
+use std::io;
+use std::task::{Context, Poll, Waker};
+use tokio::io::AsyncWrite;
+
+/// The maximum number of received ALTSVC frames buffered until they are
+/// polled. Later frames are dropped.
+const MAX_PENDING_ALTSVC: usize = 64;
+
+/// The maximum number of origins in the origin set of a connection. Origins
+/// of later ORIGIN frames beyond it are dropped.
+const MAX_ORIGIN_SET: usize = 1024;
+
+/// Builds an extension frame sent by the user, checking that it is not of a
+/// type defined by this crate.
+pub(crate) fn extension_frame(
+    kind: u8,
+    flags: u8,
+    stream_id: u32,
+    payload: Bytes,
+) -> Result<frame::Extension, UserError> {
+    if frame::Kind::new(kind) != frame::Kind::Unknown || stream_id > frame::StreamId::MAX.into() {
+        return Err(UserError::InvalidExtensionFrame);
+    }
+
+    Ok(frame::Extension::new(
+        kind,
+        flags,
+        stream_id.into(),
+        payload,
+    ))
+}
+
+/// Manages the frames of protocol extensions: frames of unknown types, and
+/// the ALTSVC and ORIGIN frames.
+#[derive(Debug)]
+pub(super) struct ExtensionFrames {
+    peer: peer::Dyn,
+    /// Handlers of received frames of unknown types, by frame type.
+    handlers: ExtensionFrameHandlers,
+    /// Frames sent by the user that must be buffered in the Codec.
+    pending: VecDeque<PendingFrame>,
+    /// ALTSVC frames received by a client, until they are polled.
+    altsvc: VecDeque<frame::AltSvc>,
+    altsvc_task: Option<Waker>,
+    /// The origins of the ORIGIN frames received by a client, in the order
+    /// they were first received.
+    origin_set: Vec<Bytes>,
+    /// Whether origins were added to the origin set since it was polled.
+    origin_set_changed: bool,
+    origin_set_task: Option<Waker>,
+    /// Whether the connection is closed, so no more frames are received.
+    is_closed: bool,
+}
+
+/// An extension frame sent by the user.
+#[derive(Debug)]
+pub(crate) enum PendingFrame {
+    Extension(frame::Extension),
+    AltSvc(frame::AltSvc),
+    Origin(frame::Origin),
+}
+
+impl ExtensionFrames {
+    pub(super) fn new(peer: peer::Dyn, handlers: ExtensionFrameHandlers) -> Self {
+        ExtensionFrames {
+            peer,
+            handlers,
+            pending: VecDeque::new(),
+            altsvc: VecDeque::new(),
+            altsvc_task: None,
+            origin_set: Vec::new(),
+            origin_set_changed: false,
+            origin_set_task: None,
+            is_closed: false,
+        }
+    }
+
+    /// Returns whether a handler was registered for the frame type.
+    pub(super) fn is_handled(&self, kind: u8) -> bool {
+        self.handlers.contains(kind)
+    }
+
+    pub(super) fn recv(&self, frame: frame::Extension) {
+        self.handlers.call(frame);
+    }
+
+    pub(super) fn recv_altsvc(&mut self, frame: frame::AltSvc) {
+        // Alternative services are advertised by servers only.
+        if self.peer.is_server() {
+            tracing::debug!("ignoring ALTSVC frame received by a server");
+            return;
+        }
+
+        if self.altsvc.len() >= MAX_PENDING_ALTSVC {
+            tracing::debug!("dropping ALTSVC frame; too many pending");
+            return;
+        }
+
+        self.altsvc.push_back(frame);
+
+        if let Some(task) = self.altsvc_task.take() {
+            task.wake();
+        }
+    }
+
+    pub(super) fn recv_origin(&mut self, frame: frame::Origin) {
+        // Origin sets are advertised by servers only.
+        if self.peer.is_server() {
+            tracing::debug!("ignoring ORIGIN frame received by a server");
+            return;
+        }
+
+        // Each ORIGIN frame adds its origins to the origin set, and none are
+        // ever removed.
+        for origin in frame.into_origins() {
+            if self.origin_set.contains(&origin) {
+                continue;
+            }
+
+            if self.origin_set.len() >= MAX_ORIGIN_SET {
+                tracing::debug!("dropping origin; origin set is full");
+                break;
+            }
+
+            self.origin_set.push(origin);
+            self.origin_set_changed = true;
+        }
+
+        if self.origin_set_changed {
+            if let Some(task) = self.origin_set_task.take() {
+                task.wake();
+            }
+        }
+    }
+
+    pub(super) fn poll_altsvc(&mut self, cx: &mut Context) -> Poll<Option<frame::AltSvc>> {
+        match self.altsvc.pop_front() {
+            Some(frame) => Poll::Ready(Some(frame)),
+            None if self.is_closed => Poll::Ready(None),
+            None => {
+                self.altsvc_task = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    pub(super) fn poll_origin_set(&mut self, cx: &mut Context) -> Poll<Option<Vec<Bytes>>> {
+        if self.origin_set_changed {
+            self.origin_set_changed = false;
+            Poll::Ready(Some(self.origin_set.clone()))
+        } else if self.is_closed {
+            Poll::Ready(None)
+        } else {
+            self.origin_set_task = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+
+    pub(super) fn close(&mut self) {
+        self.is_closed = true;
+
+        if let Some(task) = self.altsvc_task.take() {
+            task.wake();
+        }
+        if let Some(task) = self.origin_set_task.take() {
+            task.wake();
+        }
+    }
+
+    pub(super) fn send(&mut self, frame: PendingFrame) {
+        self.pending.push_back(frame);
+    }
+
+    pub(super) fn send_pending<T, B>(
+        &mut self,
+        cx: &mut Context,
+        dst: &mut Codec<T, B>,
+    ) -> Poll<io::Result<()>>
+    where
+        T: AsyncWrite + Unpin,
+        B: Buf,
+    {
+        while !self.pending.is_empty() {
+            if !dst.poll_ready(cx)?.is_ready() {
+                return Poll::Pending;
+            }
+
+            let frame = self.pending.pop_front().unwrap();
+
+            // The peer may have lowered its maximum frame size since the
+            // frame was accepted.
+            if let Err(e) = dst.buffer(frame.into()) {
+                tracing::debug!(?e, "dropping extension frame");
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+// ===== impl PendingFrame =====
+
+impl PendingFrame {
+    pub(super) fn payload_len(&self) -> usize {
+        match *self {
+            PendingFrame::Extension(ref frame) => frame.payload().len(),
+            PendingFrame::AltSvc(ref frame) => frame.payload_len(),
+            PendingFrame::Origin(ref frame) => frame.payload_len(),
+        }
+    }
+}
+
+impl From<frame::Extension> for PendingFrame {
+    fn from(src: frame::Extension) -> Self {
+        PendingFrame::Extension(src)
+    }
+}
+
+impl From<frame::AltSvc> for PendingFrame {
+    fn from(src: frame::AltSvc) -> Self {
+        PendingFrame::AltSvc(src)
+    }
+}
+
+impl From<frame::Origin> for PendingFrame {
+    fn from(src: frame::Origin) -> Self {
+        PendingFrame::Origin(src)
+    }
+}
+
+impl<B> From<PendingFrame> for Frame<B> {
+    fn from(src: PendingFrame) -> Self {
+        match src {
+            PendingFrame::Extension(frame) => frame.into(),
+            PendingFrame::AltSvc(frame) => frame.into(),
+            PendingFrame::Origin(frame) => frame.into(),
+        }
+    }
+}
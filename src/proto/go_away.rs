@@ -3,7 +3,7 @@ use crate::frame::{self, Reason, StreamId};
 
 use bytes::Buf;
 use std::io;
-use std::task::{Context, Poll};
+use std::task::{Context, Poll, Waker};
 use tokio::io::AsyncWrite;
 
 /// Manages our sending of GOAWAY frames.
@@ -19,6 +19,17 @@ pub(super) struct GoAway {
     pending: Option<frame::GoAway>,
 }
 
+/// Reports the GOAWAY frames received from the peer to the user.
+#[derive(Debug, Default)]
+pub(super) struct RecvGoAway {
+    /// The last received GOAWAY frame, not polled by the user yet.
+    pending: Option<frame::GoAway>,
+    /// Task waiting for a GOAWAY frame.
+    task: Option<Waker>,
+    /// Whether the connection is closed, so no more frames are received.
+    is_closed: bool,
+}
+
 /// Keeps a memory of any GOAWAY frames we've sent before.
 ///
 /// This looks very similar to a `frame::GoAway`, but is a separate type. Why?
@@ -147,6 +158,37 @@ impl GoAway {
     }
 }
 
+impl RecvGoAway {
+    pub fn recv(&mut self, frame: frame::GoAway) {
+        self.pending = Some(frame);
+
+        if let Some(task) = self.task.take() {
+            task.wake();
+        }
+    }
+
+    /// Returns the last GOAWAY frame received since the previous call, or
+    /// `None` once the connection is closed.
+    pub fn poll(&mut self, cx: &mut Context) -> Poll<Option<frame::GoAway>> {
+        match self.pending.take() {
+            Some(frame) => Poll::Ready(Some(frame)),
+            None if self.is_closed => Poll::Ready(None),
+            None => {
+                self.task = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.is_closed = true;
+
+        if let Some(task) = self.task.take() {
+            task.wake();
+        }
+    }
+}
+
 impl GoingAway {
     pub(crate) fn reason(&self) -> Reason {
         self.reason
@@ -1,13 +1,15 @@
 mod connection;
 mod error;
+mod extension;
 mod go_away;
 mod peer;
 mod ping_pong;
 mod settings;
 mod streams;
 
-pub(crate) use self::connection::{Config, Connection};
+pub(crate) use self::connection::{Config, Connection, PrefaceFrame};
 pub use self::error::{Error, Initiator};
+pub(crate) use self::extension::{extension_frame, PendingFrame};
 pub(crate) use self::peer::{Dyn as DynPeer, Peer};
 pub(crate) use self::ping_pong::UserPings;
 pub(crate) use self::streams::{DynStreams, OpaqueStreamRef, StreamRef, Streams};
@@ -15,8 +17,9 @@ pub(crate) use self::streams::{Open, PollReset, Prioritized};
 
 use crate::codec::Codec;
 
-use self::go_away::GoAway;
-use self::ping_pong::PingPong;
+use self::extension::ExtensionFrames;
+use self::go_away::{GoAway, RecvGoAway};
+use self::ping_pong::{Bdp, KeepAlive, PingPong};
 use self::settings::Settings;
 
 use crate::frame::{self, Frame};
@@ -40,3 +43,4 @@ pub const DEFAULT_RESET_STREAM_MAX: usize = 50;
 // reasonable guess of the average here.
 pub const DEFAULT_RESET_STREAM_SECS: u64 = 1;
 pub const DEFAULT_MAX_SEND_BUFFER_SIZE: usize = 1024 * 400;
+pub const DEFAULT_KEEP_ALIVE_TIMEOUT_SECS: u64 = 20;
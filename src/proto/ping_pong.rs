@@ -1,13 +1,17 @@
 use crate::codec::Codec;
 use crate::frame::Ping;
-use crate::proto::{self, PingPayload};
+use crate::proto::{self, PingPayload, WindowSize};
+use crate::timer::{SharedTimer, Sleep};
 
 use atomic_waker::AtomicWaker;
 use bytes::Buf;
 use std::io;
+use std::pin::Pin;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use std::{cmp, fmt};
 use tokio::io::AsyncWrite;
 
 /// Acknowledges ping requests from the remote.
@@ -16,6 +20,15 @@ pub(crate) struct PingPong {
     pending_ping: Option<PendingPing>,
     pending_pong: Option<PingPayload>,
     user_pings: Option<UserPingsRx>,
+    /// Estimates the bandwidth-delay product when adaptive flow control is
+    /// enabled.
+    bdp: Option<Bdp>,
+    /// Sends keep-alive PINGs when enabled.
+    keep_alive: Option<KeepAlive>,
+    /// When the user PING awaiting its acknowledgement was sent.
+    user_ping_sent_at: Option<Instant>,
+    /// Round-trip time of the last acknowledged PING.
+    rtt: Option<Duration>,
 }
 
 #[derive(Debug)]
@@ -33,6 +46,58 @@ struct UserPingsInner {
     pong_task: AtomicWaker,
 }
 
+/// Estimates the bandwidth-delay product of the connection, by counting the
+/// data received during the round trip of a PING.
+///
+/// The estimate starts at the initial window size, and is doubled whenever
+/// the data received during a round trip fills most of it.
+#[derive(Debug)]
+pub(crate) struct Bdp {
+    /// The current estimate, in octets.
+    bdp: WindowSize,
+    /// Data received since the last PING was sent.
+    bytes: usize,
+    /// Highest bandwidth measured, in octets per second.
+    max_bandwidth: f64,
+    /// Smoothed round-trip time, in seconds.
+    rtt: f64,
+    /// Delay between PINGs once the estimate has stabilized.
+    ping_delay: Duration,
+    /// When the next PING may be sent, after the estimate stabilized.
+    next_ping_at: Option<Instant>,
+    /// Whether a PING must be sent.
+    ping_pending: bool,
+    /// When the PING awaiting its acknowledgement was sent.
+    ping_sent_at: Option<Instant>,
+    /// A grown estimate to be applied to the flow control windows.
+    window_size: Option<WindowSize>,
+}
+
+/// Sends a PING when nothing was received from the peer for an interval, and
+/// times out when nothing is received in response.
+pub(crate) struct KeepAlive {
+    interval: Duration,
+    timeout: Duration,
+    while_idle: bool,
+    state: KeepAliveState,
+    /// When the last frame was received.
+    last_read_at: Instant,
+    timer: SharedTimer,
+    /// Fires at the next PING, or at the timeout of the sent PING.
+    sleep: Pin<Box<dyn Sleep>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeepAliveState {
+    /// Waiting for the interval to elapse.
+    Idle,
+    /// A PING must be sent.
+    PingPending,
+    /// A PING was sent at the given instant, and the timer fires at its
+    /// timeout.
+    PingSent(Instant),
+}
+
 #[derive(Debug)]
 struct PendingPing {
     payload: PingPayload,
@@ -58,17 +123,33 @@ const USER_STATE_RECEIVED_PONG: usize = 3;
 /// The connection is closed.
 const USER_STATE_CLOSED: usize = 4;
 
+/// The estimate of the bandwidth-delay product is never grown past 16MB.
+const BDP_LIMIT: WindowSize = 16 * 1024 * 1024;
+/// Delay between PINGs when the estimate has just stabilized.
+const BDP_MIN_PING_DELAY: Duration = Duration::from_millis(100);
+/// Maximum delay between PINGs once the estimate has stabilized.
+const BDP_MAX_PING_DELAY: Duration = Duration::from_secs(10);
+
 // ===== impl PingPong =====
 
 impl PingPong {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(bdp: Option<Bdp>, keep_alive: Option<KeepAlive>) -> Self {
         PingPong {
             pending_ping: None,
             pending_pong: None,
             user_pings: None,
+            bdp,
+            keep_alive,
+            user_ping_sent_at: None,
+            rtt: None,
         }
     }
 
+    /// Returns the round-trip time of the last acknowledged PING.
+    pub(crate) fn rtt(&self) -> Option<Duration> {
+        self.rtt
+    }
+
     /// Can only be called once. If called a second time, returns `None`.
     pub(crate) fn take_user_pings(&mut self) -> Option<UserPings> {
         if self.user_pings.is_some() {
@@ -115,9 +196,32 @@ impl PingPong {
                 self.pending_ping = Some(pending);
             }
 
+            if let Some(ref mut bdp) = self.bdp {
+                if ping.payload() == &Ping::BDP {
+                    if let Some(rtt) = bdp.recv_pong() {
+                        tracing::trace!("recv PING BDP ack");
+                        self.rtt = Some(rtt);
+                        return ReceivedPing::Unknown;
+                    }
+                }
+            }
+
+            if let Some(ref mut keep_alive) = self.keep_alive {
+                if ping.payload() == &Ping::KEEP_ALIVE {
+                    tracing::trace!("recv PING KEEP_ALIVE ack");
+                    if let Some(rtt) = keep_alive.recv_pong() {
+                        self.rtt = Some(rtt);
+                    }
+                    return ReceivedPing::Unknown;
+                }
+            }
+
             if let Some(ref users) = self.user_pings {
                 if ping.payload() == &Ping::USER && users.receive_pong() {
                     tracing::trace!("recv PING USER ack");
+                    if let Some(sent_at) = self.user_ping_sent_at.take() {
+                        self.rtt = Some(sent_at.elapsed());
+                    }
                     return ReceivedPing::Unknown;
                 }
             }
@@ -157,6 +261,45 @@ impl PingPong {
         Poll::Ready(Ok(()))
     }
 
+    /// Accounts for a received frame, which shows the connection is alive.
+    pub(crate) fn recv_frame(&mut self) {
+        if let Some(ref mut keep_alive) = self.keep_alive {
+            keep_alive.last_read_at = Instant::now();
+        }
+    }
+
+    /// Schedules keep-alive PINGs, returning `Ready` if a PING was not
+    /// acknowledged in time.
+    ///
+    /// PINGs are only sent while `is_active` is true, unless keep-alive is
+    /// enabled while idle.
+    pub(crate) fn poll_keep_alive(&mut self, cx: &mut Context, is_active: bool) -> Poll<()> {
+        match self.keep_alive {
+            Some(ref mut keep_alive) => keep_alive.poll(cx, is_active),
+            None => Poll::Pending,
+        }
+    }
+
+    /// Accounts for a received DATA frame, of `len` flow-controlled octets.
+    pub(crate) fn recv_data(&mut self, len: usize) {
+        if let Some(ref mut bdp) = self.bdp {
+            bdp.recv_data(len);
+        }
+    }
+
+    /// Returns the grown estimate of the bandwidth-delay product, if it has
+    /// not been applied to the flow control windows yet.
+    pub(crate) fn pending_window_size(&self) -> Option<WindowSize> {
+        self.bdp.as_ref().and_then(|bdp| bdp.window_size)
+    }
+
+    /// Marks the estimate returned by `pending_window_size` as applied.
+    pub(crate) fn clear_pending_window_size(&mut self) {
+        if let Some(ref mut bdp) = self.bdp {
+            bdp.window_size = None;
+        }
+    }
+
     /// Send any pending pings.
     pub(crate) fn send_pending_ping<T, B>(
         &mut self,
@@ -185,6 +328,7 @@ impl PingPong {
 
                 dst.buffer(Ping::new(Ping::USER).into())
                     .expect("invalid ping frame");
+                self.user_ping_sent_at = Some(Instant::now());
                 users
                     .0
                     .state
@@ -194,10 +338,221 @@ impl PingPong {
             }
         }
 
+        if let Some(ref mut keep_alive) = self.keep_alive {
+            if keep_alive.state == KeepAliveState::PingPending {
+                if !dst.poll_ready(cx)?.is_ready() {
+                    return Poll::Pending;
+                }
+
+                dst.buffer(Ping::new(Ping::KEEP_ALIVE).into())
+                    .expect("invalid ping frame");
+                keep_alive.ping_sent();
+            }
+        }
+
+        if let Some(ref mut bdp) = self.bdp {
+            if bdp.ping_pending {
+                if !dst.poll_ready(cx)?.is_ready() {
+                    return Poll::Pending;
+                }
+
+                dst.buffer(Ping::new(Ping::BDP).into())
+                    .expect("invalid ping frame");
+                bdp.ping_sent();
+            }
+        }
+
         Poll::Ready(Ok(()))
     }
 }
 
+// ===== impl Bdp =====
+
+impl Bdp {
+    pub(crate) fn new(initial_window_size: WindowSize) -> Self {
+        Bdp {
+            bdp: initial_window_size,
+            bytes: 0,
+            max_bandwidth: 0.0,
+            rtt: 0.0,
+            ping_delay: BDP_MIN_PING_DELAY,
+            next_ping_at: None,
+            ping_pending: false,
+            ping_sent_at: None,
+            window_size: None,
+        }
+    }
+
+    fn recv_data(&mut self, len: usize) {
+        if self.bdp >= BDP_LIMIT {
+            return;
+        }
+
+        // Start a new measurement, unless one is in progress or the estimate
+        // has stabilized recently.
+        if !self.ping_pending && self.ping_sent_at.is_none() {
+            if let Some(next_ping_at) = self.next_ping_at {
+                if Instant::now() < next_ping_at {
+                    return;
+                }
+                self.next_ping_at = None;
+            }
+
+            self.bytes = 0;
+            self.ping_pending = true;
+        }
+
+        self.bytes += len;
+    }
+
+    fn ping_sent(&mut self) {
+        self.ping_pending = false;
+        self.ping_sent_at = Some(Instant::now());
+    }
+
+    /// Returns the round-trip time of the PING, or `None` if no BDP PING was
+    /// awaiting its acknowledgement.
+    fn recv_pong(&mut self) -> Option<Duration> {
+        let elapsed = self.ping_sent_at.take()?.elapsed();
+
+        let rtt = elapsed.as_secs_f64();
+        if self.rtt == 0.0 {
+            self.rtt = rtt;
+        } else {
+            self.rtt += (rtt - self.rtt) * 0.125;
+        }
+
+        let bytes = self.bytes;
+        self.bytes = 0;
+
+        // The acknowledgement is usually received a little after the data
+        // sent before it, so the round trip is padded when measuring the
+        // bandwidth.
+        let bandwidth = bytes as f64 / (self.rtt * 1.5);
+        if bandwidth < self.max_bandwidth {
+            self.stabilize();
+            return Some(elapsed);
+        }
+        self.max_bandwidth = bandwidth;
+
+        // The window is grown when the data received during a round trip
+        // nears it, which means the sender was likely blocked on it.
+        if bytes >= self.bdp as usize * 2 / 3 {
+            self.bdp = cmp::min(bytes.saturating_mul(2), BDP_LIMIT as usize) as WindowSize;
+            self.ping_delay = cmp::max(self.ping_delay / 2, BDP_MIN_PING_DELAY);
+            self.window_size = Some(self.bdp);
+            tracing::trace!(bdp = self.bdp, rtt = self.rtt, "grew BDP estimate");
+        } else {
+            self.stabilize();
+        }
+
+        Some(elapsed)
+    }
+
+    /// Waits longer before the next measurement, as the estimate is not
+    /// changing.
+    fn stabilize(&mut self) {
+        self.next_ping_at = Some(Instant::now() + self.ping_delay);
+        self.ping_delay = cmp::min(self.ping_delay * 4, BDP_MAX_PING_DELAY);
+    }
+}
+
+// ===== impl KeepAlive =====
+
+impl KeepAlive {
+    pub(crate) fn new(
+        interval: Duration,
+        timeout: Duration,
+        while_idle: bool,
+        timer: SharedTimer,
+    ) -> Self {
+        let now = Instant::now();
+        let sleep = timer.sleep_until(now + interval);
+        KeepAlive {
+            interval,
+            timeout,
+            while_idle,
+            state: KeepAliveState::Idle,
+            last_read_at: now,
+            timer,
+            sleep,
+        }
+    }
+
+    fn poll(&mut self, cx: &mut Context, is_active: bool) -> Poll<()> {
+        loop {
+            if self.sleep.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+
+            let now = Instant::now();
+            match self.state {
+                KeepAliveState::Idle => {
+                    let next_ping_at = self.last_read_at + self.interval;
+                    if next_ping_at > now {
+                        // A frame was received since the timer was set.
+                        self.reset_timer(next_ping_at);
+                    } else if is_active || self.while_idle {
+                        tracing::trace!("keep-alive interval elapsed; sending PING");
+                        self.state = KeepAliveState::PingPending;
+                        self.reset_timer(now + self.timeout);
+                    } else {
+                        self.reset_timer(now + self.interval);
+                    }
+                }
+                KeepAliveState::PingPending => {
+                    // The PING could not be written in time.
+                    return Poll::Ready(());
+                }
+                KeepAliveState::PingSent(sent_at) => {
+                    if self.last_read_at < sent_at {
+                        tracing::debug!("keep-alive timed out");
+                        return Poll::Ready(());
+                    }
+
+                    // The peer responded, whether it is with the
+                    // acknowledgement or another frame.
+                    self.state = KeepAliveState::Idle;
+                    self.reset_timer(self.last_read_at + self.interval);
+                }
+            }
+        }
+    }
+
+    fn ping_sent(&mut self) {
+        self.state = KeepAliveState::PingSent(Instant::now());
+    }
+
+    /// Returns the round-trip time of the PING, if one was sent.
+    fn recv_pong(&mut self) -> Option<Duration> {
+        match self.state {
+            KeepAliveState::PingSent(sent_at) => {
+                self.state = KeepAliveState::Idle;
+                self.reset_timer(Instant::now() + self.interval);
+                Some(sent_at.elapsed())
+            }
+            _ => None,
+        }
+    }
+
+    fn reset_timer(&mut self, deadline: Instant) {
+        self.sleep = self.timer.sleep_until(deadline);
+    }
+}
+
+impl fmt::Debug for KeepAlive {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("KeepAlive")
+            .field("interval", &self.interval)
+            .field("timeout", &self.timeout)
+            .field("while_idle", &self.while_idle)
+            .field("state", &self.state)
+            .field("last_read_at", &self.last_read_at)
+            .field("timer", &self.timer)
+            .finish()
+    }
+}
+
 impl ReceivedPing {
     pub(crate) fn is_shutdown(&self) -> bool {
         matches!(*self, Self::Shutdown)
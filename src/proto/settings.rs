@@ -87,6 +87,50 @@ impl Settings {
         }
     }
 
+    /// Applies the SETTINGS of the `HTTP2-Settings` header of an HTTP/1.1
+    /// request upgraded to HTTP/2, which the `101` response acknowledged.
+    pub(crate) fn recv_upgrade_settings<T, B, C, P>(
+        &mut self,
+        settings: &frame::Settings,
+        dst: &mut Codec<T, B>,
+        streams: &mut Streams<C, P>,
+    ) -> Result<(), Error>
+    where
+        B: Buf,
+        C: Buf,
+        P: Peer,
+    {
+        tracing::debug!("applying upgrade settings {:?}", settings);
+
+        // The SETTINGS of the client connection preface is still to come, and
+        // remains the initial one.
+        Self::apply_remote_settings(settings, true, dst, streams)
+    }
+
+    fn apply_remote_settings<T, B, C, P>(
+        settings: &frame::Settings,
+        is_initial: bool,
+        dst: &mut Codec<T, B>,
+        streams: &mut Streams<C, P>,
+    ) -> Result<(), Error>
+    where
+        B: Buf,
+        C: Buf,
+        P: Peer,
+    {
+        streams.apply_remote_settings(settings, is_initial)?;
+
+        if let Some(val) = settings.header_table_size() {
+            dst.set_send_header_table_size(val as usize);
+        }
+
+        if let Some(val) = settings.max_frame_size() {
+            dst.set_max_send_frame_size(val as usize);
+        }
+
+        Ok(())
+    }
+
     pub(crate) fn send_settings(&mut self, frame: frame::Settings) -> Result<(), UserError> {
         assert!(!frame.is_ack());
         match &self.local {
@@ -134,15 +178,7 @@ impl Settings {
             tracing::trace!("ACK sent; applying settings");
 
             let is_initial = self.mark_remote_initial_settings_as_received();
-            streams.apply_remote_settings(&settings, is_initial)?;
-
-            if let Some(val) = settings.header_table_size() {
-                dst.set_send_header_table_size(val as usize);
-            }
-
-            if let Some(val) = settings.max_frame_size() {
-                dst.set_max_send_frame_size(val as usize);
-            }
+            Self::apply_remote_settings(&settings, is_initial, dst, streams)?;
         }
 
         self.remote = None;
@@ -1,5 +1,7 @@
 use super::*;
 
+use std::cmp;
+
 #[derive(Debug)]
 pub(super) struct Counts {
     /// Acting as a client or server. This allows us to track which values to
@@ -18,6 +20,9 @@ pub(super) struct Counts {
     /// Current number of locally initiated streams
     num_recv_streams: usize,
 
+    /// Highest number of streams open at the same time
+    peak_streams: usize,
+
     /// Maximum number of pending locally reset streams
     max_local_reset_streams: usize,
 
@@ -50,6 +55,7 @@ impl Counts {
             num_send_streams: 0,
             max_recv_streams: config.remote_max_initiated.unwrap_or(usize::MAX),
             num_recv_streams: 0,
+            peak_streams: 0,
             max_local_reset_streams: config.local_reset_max,
             num_local_reset_streams: 0,
             max_remote_reset_streams: config.remote_reset_max,
@@ -113,6 +119,7 @@ impl Counts {
         // Increment the number of remote initiated streams
         self.num_recv_streams += 1;
         stream.is_counted = true;
+        self.update_peak_streams();
     }
 
     /// Returns true if the send stream concurrency can be incremented
@@ -132,6 +139,19 @@ impl Counts {
         // Increment the number of remote initiated streams
         self.num_send_streams += 1;
         stream.is_counted = true;
+        self.update_peak_streams();
+    }
+
+    fn update_peak_streams(&mut self) {
+        self.peak_streams = cmp::max(
+            self.peak_streams,
+            self.num_send_streams + self.num_recv_streams,
+        );
+    }
+
+    /// Returns the highest number of streams open at the same time.
+    pub fn peak_streams(&self) -> usize {
+        self.peak_streams
     }
 
     /// Returns true if the number of pending reset streams can be incremented.
@@ -0,0 +1,462 @@
+use super::StreamId;
+use crate::frame::StreamDependency;
+
+use std::collections::HashMap;
+
+/// The weight of streams without priority information (RFC 7540, section
+/// 5.3.5).
+const DEFAULT_WEIGHT: u16 = 16;
+
+/// The largest weight of a stream.
+const MAX_WEIGHT: u16 = 256;
+
+/// Number of nodes above which priority information about idle streams is
+/// ignored. Opened streams are always added to the tree.
+const MAX_NODES: usize = 1_024;
+
+/// Number of nodes at which the tree first drops closed streams.
+const MIN_PRUNE_LEN: usize = 64;
+
+/// The RFC 7540 dependency tree of the streams of a connection.
+///
+/// A stream with frames to send is served before the streams depending on
+/// it. Otherwise, the connection is shared between sibling subtrees by
+/// weight: each node has a virtual time, or cycle, that advances by the
+/// amount of data sent in its subtree divided by its weight, and the child
+/// with the lowest cycle is served first.
+///
+/// Nodes are also created for idle streams that others depend on, as some
+/// clients use them to group requests. Nodes of streams that were opened are
+/// dropped once the stream is gone, and their children move to their parent
+/// (RFC 7540, section 5.3.4).
+#[derive(Debug)]
+pub(super) struct DependencyTree {
+    nodes: HashMap<StreamId, Node>,
+
+    /// Number of nodes at which to drop closed streams again.
+    prune_len: usize,
+}
+
+#[derive(Debug)]
+struct Node {
+    parent: StreamId,
+    weight: u16,
+    children: Vec<StreamId>,
+
+    /// The stream has a frame to send.
+    is_ready: bool,
+
+    /// The stream was opened, and its node can be dropped once it is gone.
+    is_opened: bool,
+
+    /// Number of streams with a frame to send in the subtree, this one
+    /// included.
+    num_ready: usize,
+
+    /// Virtual time of the subtree, compared with its siblings.
+    cycle: u64,
+
+    /// Cycle of the last child served.
+    last_cycle: u64,
+}
+
+impl DependencyTree {
+    pub fn new() -> Self {
+        let mut nodes = HashMap::new();
+        nodes.insert(StreamId::ZERO, Node::new(StreamId::ZERO, DEFAULT_WEIGHT));
+
+        DependencyTree {
+            nodes,
+            prune_len: MIN_PRUNE_LEN,
+        }
+    }
+
+    /// Applies the stream dependency of a `PRIORITY` or `HEADERS` frame.
+    pub fn reprioritize(&mut self, id: StreamId, dependency: &StreamDependency, is_opened: bool) {
+        debug_assert_ne!(id, dependency.dependency_id());
+
+        if is_opened {
+            self.open(id);
+        } else if !self.nodes.contains_key(&id) && !self.try_insert(id) {
+            tracing::trace!(?id, "dependency tree is full; ignoring priority");
+            return;
+        }
+
+        let mut parent = dependency.dependency_id();
+        let mut weight = u16::from(dependency.weight()) + 1;
+        let mut is_exclusive = dependency.is_exclusive();
+
+        if !self.nodes.contains_key(&parent) && !self.try_insert(parent) {
+            // A dependency on a stream that is not in the tree results in
+            // the default priority.
+            parent = StreamId::ZERO;
+            weight = DEFAULT_WEIGHT;
+            is_exclusive = false;
+        }
+
+        // A stream made dependent on one of its own dependencies first
+        // swaps places with it.
+        if self.is_ancestor(id, parent) {
+            let grandparent = self.nodes[&id].parent;
+            self.detach(parent);
+            self.attach(parent, grandparent);
+        }
+
+        self.detach(id);
+        self.node_mut(id).weight = weight;
+        self.attach(id, parent);
+
+        if is_exclusive {
+            let siblings: Vec<StreamId> = self.nodes[&parent]
+                .children
+                .iter()
+                .copied()
+                .filter(|&child| child != id)
+                .collect();
+
+            for sibling in siblings {
+                self.detach(sibling);
+                self.attach(sibling, id);
+            }
+        }
+    }
+
+    /// Adds an opened stream with the default priority, unless it is already
+    /// in the tree.
+    pub fn open(&mut self, id: StreamId) {
+        if !self.nodes.contains_key(&id) {
+            self.insert(id);
+        }
+
+        self.node_mut(id).is_opened = true;
+    }
+
+    /// Marks the stream as having a frame to send.
+    pub fn set_ready(&mut self, id: StreamId) {
+        self.open(id);
+
+        let node = self.node_mut(id);
+
+        if !node.is_ready {
+            node.is_ready = true;
+            self.add_ready(id, 1);
+        }
+    }
+
+    /// Returns the next stream to send a frame, and marks it as no longer
+    /// having a frame to send.
+    pub fn pop(&mut self) -> Option<StreamId> {
+        let mut id = StreamId::ZERO;
+
+        loop {
+            let node = &self.nodes[&id];
+
+            if node.is_ready {
+                break;
+            }
+
+            id = node
+                .children
+                .iter()
+                .map(|child| (child, &self.nodes[child]))
+                .filter(|(_, child)| child.num_ready > 0)
+                .min_by_key(|(&child_id, child)| (child.cycle, child_id))
+                .map(|(&child_id, _)| child_id)?;
+        }
+
+        self.node_mut(id).is_ready = false;
+        self.sub_ready(id, 1);
+
+        Some(id)
+    }
+
+    /// Accounts for `len` bytes of data sent on the stream.
+    pub fn sent(&mut self, id: StreamId, len: usize) {
+        let mut id = id;
+
+        while let Some(node) = self.nodes.get_mut(&id) {
+            if id.is_zero() {
+                break;
+            }
+
+            let parent = node.parent;
+            let cycle = node.cycle;
+            node.cycle += len as u64 * u64::from(MAX_WEIGHT) / u64::from(node.weight);
+
+            self.node_mut(parent).last_cycle = cycle;
+            id = parent;
+        }
+    }
+
+    /// Drops the nodes of streams that were opened and are now gone, once
+    /// the tree has grown enough since the last time.
+    pub fn prune<F: FnMut(StreamId) -> bool>(&mut self, mut is_alive: F) {
+        if self.nodes.len() < self.prune_len {
+            return;
+        }
+
+        let closed: Vec<StreamId> = self
+            .nodes
+            .iter()
+            .filter(|(&id, node)| node.is_opened && !node.is_ready && !is_alive(id))
+            .map(|(&id, _)| id)
+            .collect();
+
+        for id in closed {
+            self.remove(id);
+        }
+
+        self.prune_len = std::cmp::max(MIN_PRUNE_LEN, self.nodes.len() * 2);
+    }
+
+    /// Inserts a node for a stream that was not opened, unless the tree is
+    /// full. Opened streams are always inserted, as they need to be
+    /// scheduled.
+    fn try_insert(&mut self, id: StreamId) -> bool {
+        if self.nodes.len() >= MAX_NODES {
+            return false;
+        }
+
+        self.insert(id);
+        true
+    }
+
+    fn insert(&mut self, id: StreamId) {
+        self.nodes
+            .insert(id, Node::new(StreamId::ZERO, DEFAULT_WEIGHT));
+        self.node_mut(StreamId::ZERO).children.push(id);
+    }
+
+    /// Removes a node, giving its weight to its children (RFC 7540, section
+    /// 5.3.4).
+    fn remove(&mut self, id: StreamId) {
+        debug_assert!(!id.is_zero());
+
+        let node = self.nodes.remove(&id).expect("node not in tree");
+        let total: u32 = node
+            .children
+            .iter()
+            .map(|child| u32::from(self.nodes[child].weight))
+            .sum();
+
+        let parent = self.node_mut(node.parent);
+        parent.children.retain(|&child| child != id);
+        parent.children.extend_from_slice(&node.children);
+
+        for child in &node.children {
+            let child = self.node_mut(*child);
+            let weight = u32::from(node.weight) * u32::from(child.weight) / total;
+
+            child.parent = node.parent;
+            child.weight = std::cmp::max(1, weight) as u16;
+        }
+    }
+
+    fn is_ancestor(&self, ancestor: StreamId, id: StreamId) -> bool {
+        let mut id = id;
+
+        while !id.is_zero() {
+            id = self.nodes[&id].parent;
+
+            if id == ancestor {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn detach(&mut self, id: StreamId) {
+        let node = &self.nodes[&id];
+        let (parent, num_ready) = (node.parent, node.num_ready);
+
+        self.node_mut(parent).children.retain(|&child| child != id);
+        self.sub_ready(parent, num_ready);
+    }
+
+    fn attach(&mut self, id: StreamId, parent: StreamId) {
+        let last_cycle = self.nodes[&parent].last_cycle;
+
+        let node = self.node_mut(id);
+        node.parent = parent;
+        node.cycle = std::cmp::max(node.cycle, last_cycle);
+        let num_ready = node.num_ready;
+
+        self.node_mut(parent).children.push(id);
+        self.add_ready(parent, num_ready);
+    }
+
+    /// Adds ready streams to the node and its ancestors.
+    fn add_ready(&mut self, id: StreamId, num_ready: usize) {
+        if num_ready == 0 {
+            return;
+        }
+
+        let mut id = id;
+
+        loop {
+            let node = &self.nodes[&id];
+            let parent = node.parent;
+
+            // A subtree that becomes active does not get to use the time it
+            // was idle.
+            if node.num_ready == 0 && !id.is_zero() {
+                let last_cycle = self.nodes[&parent].last_cycle;
+                let node = self.node_mut(id);
+                node.cycle = std::cmp::max(node.cycle, last_cycle);
+            }
+
+            self.node_mut(id).num_ready += num_ready;
+
+            if id.is_zero() {
+                break;
+            }
+
+            id = parent;
+        }
+    }
+
+    /// Removes ready streams from the node and its ancestors.
+    fn sub_ready(&mut self, id: StreamId, num_ready: usize) {
+        let mut id = id;
+
+        loop {
+            let node = self.node_mut(id);
+            node.num_ready -= num_ready;
+
+            if id.is_zero() {
+                break;
+            }
+
+            id = node.parent;
+        }
+    }
+
+    fn node_mut(&mut self, id: StreamId) -> &mut Node {
+        self.nodes.get_mut(&id).expect("node not in tree")
+    }
+}
+
+impl Node {
+    fn new(parent: StreamId, weight: u16) -> Self {
+        Node {
+            parent,
+            weight,
+            children: Vec::new(),
+            is_ready: false,
+            is_opened: false,
+            num_ready: 0,
+            cycle: 0,
+            last_cycle: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dep(id: u32, weight: u16, is_exclusive: bool) -> StreamDependency {
+        StreamDependency::new(id.into(), (weight - 1) as u8, is_exclusive)
+    }
+
+    fn parent(tree: &DependencyTree, id: u32) -> u32 {
+        tree.nodes[&id.into()].parent.into()
+    }
+
+    fn drain(tree: &mut DependencyTree, ids: &[u32], len: usize, n: usize) -> Vec<u32> {
+        for &id in ids {
+            tree.set_ready(id.into());
+        }
+
+        (0..n)
+            .map(|_| {
+                let id = tree.pop().unwrap();
+                tree.sent(id, len);
+                tree.set_ready(id);
+                id.into()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parent_is_served_before_children() {
+        let mut tree = DependencyTree::new();
+        tree.reprioritize(3.into(), &dep(1, 16, false), true);
+
+        tree.set_ready(3.into());
+        tree.set_ready(1.into());
+
+        assert_eq!(tree.pop(), Some(1.into()));
+        assert_eq!(tree.pop(), Some(3.into()));
+        assert_eq!(tree.pop(), None);
+    }
+
+    #[test]
+    fn siblings_share_by_weight() {
+        let mut tree = DependencyTree::new();
+        tree.reprioritize(1.into(), &dep(0, 64, false), true);
+        tree.reprioritize(3.into(), &dep(0, 192, false), true);
+
+        let served = drain(&mut tree, &[1, 3], 1_024, 400);
+        let ones = served.iter().filter(|&&id| id == 1).count();
+
+        assert_eq!(ones, 100);
+    }
+
+    #[test]
+    fn exclusive_dependency_adopts_siblings() {
+        let mut tree = DependencyTree::new();
+        tree.reprioritize(1.into(), &dep(0, 16, false), true);
+        tree.reprioritize(3.into(), &dep(0, 16, false), true);
+        tree.reprioritize(5.into(), &dep(0, 16, true), true);
+
+        assert_eq!(parent(&tree, 1), 5);
+        assert_eq!(parent(&tree, 3), 5);
+        assert_eq!(parent(&tree, 5), 0);
+    }
+
+    #[test]
+    fn dependency_on_descendant_moves_it_up() {
+        let mut tree = DependencyTree::new();
+        tree.reprioritize(3.into(), &dep(1, 16, false), true);
+        tree.reprioritize(5.into(), &dep(3, 16, false), true);
+        tree.set_ready(5.into());
+
+        tree.reprioritize(1.into(), &dep(5, 16, false), true);
+
+        assert_eq!(parent(&tree, 5), 0);
+        assert_eq!(parent(&tree, 1), 5);
+        assert_eq!(parent(&tree, 3), 1);
+        assert_eq!(tree.nodes[&StreamId::ZERO].num_ready, 1);
+        assert_eq!(tree.pop(), Some(5.into()));
+    }
+
+    #[test]
+    fn removed_stream_gives_weight_to_children() {
+        let mut tree = DependencyTree::new();
+        tree.reprioritize(1.into(), &dep(0, 32, false), true);
+        tree.reprioritize(3.into(), &dep(1, 16, false), true);
+        tree.reprioritize(5.into(), &dep(1, 48, false), true);
+
+        tree.prune_len = 0;
+        tree.prune(|id| id != 1);
+
+        assert_eq!(parent(&tree, 3), 0);
+        assert_eq!(tree.nodes[&3.into()].weight, 8);
+        assert_eq!(tree.nodes[&5.into()].weight, 24);
+    }
+
+    #[test]
+    fn idle_streams_are_kept() {
+        let mut tree = DependencyTree::new();
+        tree.reprioritize(3.into(), &dep(0, 16, false), false);
+        tree.reprioritize(5.into(), &dep(3, 16, false), true);
+
+        tree.prune_len = 0;
+        tree.prune(|_| false);
+
+        assert!(tree.nodes.contains_key(&3.into()));
+        assert!(!tree.nodes.contains_key(&5.into()));
+    }
+}
@@ -1,5 +1,6 @@
 mod buffer;
 mod counts;
+mod dependency_tree;
 mod flow_control;
 mod prioritize;
 mod recv;
@@ -17,6 +18,7 @@ pub(crate) use self::streams::{DynStreams, OpaqueStreamRef, StreamRef, Streams};
 
 use self::buffer::Buffer;
 use self::counts::Counts;
+use self::dependency_tree::DependencyTree;
 use self::flow_control::FlowControl;
 use self::prioritize::Prioritize;
 use self::recv::Recv;
@@ -25,8 +27,11 @@ use self::state::State;
 use self::store::Store;
 use self::stream::Stream;
 
+use crate::client::PushPolicy;
+use crate::ext::{Priority, PseudoHeaderOrder, StreamDependency};
 use crate::frame::{StreamId, StreamIdOverflow};
 use crate::proto::*;
+use crate::scheduler::NewScheduler;
 
 use bytes::Bytes;
 use std::time::Duration;
@@ -72,6 +77,28 @@ pub struct Config {
     ///
     /// When this gets exceeded, we issue GOAWAYs.
     pub local_max_error_reset_streams: Option<usize>,
+
+    /// Order in which request pseudo headers are encoded
+    pub pseudo_header_order: PseudoHeaderOrder,
+
+    /// Stream dependency sent with request HEADERS that do not carry their
+    /// own
+    pub request_stream_dependency: Option<StreamDependency>,
+
+    /// If sends are scheduled by the RFC 7540 dependency tree
+    pub rfc7540_priorities: bool,
+
+    /// Creates the scheduler of sent frames, if not the default one
+    pub scheduler: Option<NewScheduler>,
+
+    /// Decides whether promised requests are accepted
+    pub push_policy: Option<PushPolicy>,
+
+    /// Maximum number of open pushed streams per stream
+    pub max_concurrent_pushes_per_stream: Option<usize>,
+
+    /// Maximum number of pushed streams kept until claimed
+    pub push_cache_size: usize,
 }
 
 trait DebugStructExt<'a, 'b> {
@@ -5,10 +5,12 @@ use crate::frame::Reason;
 
 use crate::codec::UserError;
 use crate::codec::UserError::*;
+use crate::scheduler::SendScheduler;
 
 use bytes::buf::Take;
 use std::{
     cmp::{self, Ordering},
+    collections::HashSet,
     fmt, io, mem,
     task::{Context, Poll, Waker},
 };
@@ -25,7 +27,7 @@ use std::{
 #[derive(Debug)]
 pub(super) struct Prioritize {
     /// Queue of streams waiting for socket capacity to send a frame.
-    pending_send: store::Queue<stream::NextSend>,
+    pending_send: PendingSend,
 
     /// Queue of streams waiting for window capacity to produce data.
     pending_capacity: store::Queue<stream::NextSendCapacity>,
@@ -58,6 +60,41 @@ pub(super) struct Prioritize {
     max_buffer_size: usize,
 }
 
+/// Streams waiting for socket capacity.
+///
+/// Streams that have not sent a frame yet are kept in a separate queue that
+/// is always served first, so that streams still open in stream ID order.
+/// Other streams are served in the `Order` of the connection.
+#[derive(Debug)]
+struct PendingSend {
+    opening: store::Queue<stream::NextSend>,
+    order: Order,
+}
+
+#[derive(Debug)]
+enum Order {
+    /// Streams are served by their RFC 9218 urgency. Within an urgency, a
+    /// stream that signaled a non-incremental priority stays at the front of
+    /// the queue until it has nothing left to send, while other streams go
+    /// to the back of the queue after each frame.
+    Urgencies([store::Queue<stream::NextSend>; 8]),
+
+    /// Streams are served by the RFC 7540 dependency tree.
+    Dependencies(DependencyTree),
+
+    /// Streams are served by a scheduler of the user.
+    Custom(Custom),
+}
+
+/// A `SendScheduler` of the user, and the streams it was told are open.
+///
+/// Frames of streams that are not open for the scheduler, such as a reset
+/// sent after the stream was closed, go in the `opening` queue instead.
+struct Custom {
+    scheduler: Box<dyn SendScheduler>,
+    opened: HashSet<StreamId>,
+}
+
 #[derive(Debug, Eq, PartialEq)]
 enum InFlightData {
     /// There is no `DATA` frame in flight.
@@ -81,6 +118,11 @@ pub(crate) struct Prioritized<B> {
 // ===== impl Prioritize =====
 
 impl Prioritize {
+    /// Returns the connection window available to send data.
+    pub fn connection_window(&self) -> WindowSize {
+        self.flow.window_size()
+    }
+
     pub fn new(config: &Config) -> Prioritize {
         let mut flow = FlowControl::new();
 
@@ -94,7 +136,7 @@ impl Prioritize {
         tracing::trace!("Prioritize::new; flow={:?}", flow);
 
         Prioritize {
-            pending_send: store::Queue::new(),
+            pending_send: PendingSend::new(config),
             pending_capacity: store::Queue::new(),
             pending_open: store::Queue::new(),
             flow,
@@ -123,6 +165,17 @@ impl Prioritize {
         self.schedule_send(stream, task);
     }
 
+    /// Applies a stream dependency received from the peer. This only has an
+    /// effect when sends are scheduled by the dependency tree.
+    pub fn reprioritize(
+        &mut self,
+        id: StreamId,
+        dependency: Option<&frame::StreamDependency>,
+        is_opened: bool,
+    ) {
+        self.pending_send.reprioritize(id, dependency, is_opened);
+    }
+
     pub fn schedule_send(&mut self, stream: &mut store::Ptr, task: &mut Option<Waker>) {
         // If the stream is waiting to be opened, nothing more to do.
         if stream.is_send_ready() {
@@ -460,6 +513,7 @@ impl Prioritize {
 
             // Assign the capacity to the stream
             stream.assign_capacity(assign, self.max_buffer_size);
+            self.pending_send.capacity_assigned(stream.id, assign);
 
             // Claim the capacity from the connection
             // TODO: proper error handling
@@ -534,7 +588,7 @@ impl Prioritize {
                 self.try_assign_capacity(&mut stream);
             }
 
-            match self.pop_frame(buffer, store, max_frame_len, counts) {
+            match self.pop_frame(buffer, store, max_frame_len, counts, dst) {
                 Some(frame) => {
                     tracing::trace!(?frame, "writing");
 
@@ -661,6 +715,11 @@ impl Prioritize {
         }
     }
 
+    /// Stops scheduling a stream that has nothing more to send.
+    pub fn unschedule(&mut self, stream: &mut store::Ptr) {
+        self.pending_send.close(stream);
+    }
+
     pub fn clear_queue<B>(&mut self, buffer: &mut Buffer<Frame<B>>, stream: &mut store::Ptr) {
         let span = tracing::trace_span!("clear_queue", ?stream.id);
         let _e = span.enter();
@@ -681,7 +740,7 @@ impl Prioritize {
     }
 
     pub fn clear_pending_send(&mut self, store: &mut Store, counts: &mut Counts) {
-        while let Some(mut stream) = self.pending_send.pop(store) {
+        while let Some(mut stream) = self.pending_send.pop_any(store) {
             let is_pending_reset = stream.is_pending_reset_expiration();
             if let Some(reason) = stream.state.get_scheduled_reset() {
                 stream.set_reset(reason, Initiator::Library);
@@ -697,12 +756,13 @@ impl Prioritize {
         }
     }
 
-    fn pop_frame<B>(
+    fn pop_frame<T, B>(
         &mut self,
         buffer: &mut Buffer<Frame<B>>,
         store: &mut Store,
         max_len: usize,
         counts: &mut Counts,
+        dst: &mut Codec<T, Prioritized<B>>,
     ) -> Option<Frame<Prioritized<B>>>
     where
         B: Buf,
@@ -781,7 +841,23 @@ impl Prioritize {
                                 continue;
                             }
 
-                            tracing::trace!(len, "sending data frame");
+                            // Padding counts against flow control, so it may
+                            // only use the window left over by the capacity
+                            // assigned to streams.
+                            let pad_window = cmp::min(
+                                stream
+                                    .send_flow
+                                    .window_size()
+                                    .saturating_sub(stream.send_flow.available().as_size()),
+                                self.flow.available().as_size(),
+                            );
+                            let pad_len = dst.data_pad_len(
+                                stream.padding,
+                                len as usize,
+                                cmp::min(max_len, len as usize + pad_window as usize),
+                            );
+
+                            tracing::trace!(len, ?pad_len, "sending data frame");
 
                             // Update the flow control
                             tracing::trace_span!("updating stream flow").in_scope(|| {
@@ -813,13 +889,46 @@ impl Prioritize {
                                     (eos, len)
                                 });
 
+                            if let Some(pad_len) = pad_len {
+                                let padded_len = WindowSize::from(pad_len) + 1;
+
+                                // `pad_window` leaves room for the padding in
+                                // both windows unless one of them shrank below
+                                // the assigned capacity; send the frame
+                                // unpadded then rather than touch either one.
+                                let fits = stream.send_flow.window_size() >= padded_len
+                                    && self.flow.window_size() >= padded_len
+                                    && self.flow.available() >= padded_len as usize;
+
+                                if fits {
+                                    let _res = stream.send_flow.dec_send_window(padded_len);
+                                    debug_assert!(_res.is_ok());
+                                    let _res = self.flow.send_data(padded_len);
+                                    debug_assert!(_res.is_ok());
+
+                                    frame.set_pad_len(pad_len);
+                                }
+                            }
+
+                            self.pending_send.sent(stream.id, len);
+
                             Frame::Data(frame.map(|buf| Prioritized {
                                 inner: buf.take(len),
                                 end_of_stream: eos,
                                 stream: stream.key(),
                             }))
                         }
-                        Some(Frame::PushPromise(pp)) => {
+                        Some(Frame::Headers(mut frame)) => {
+                            if let Some(padding) = stream.padding {
+                                frame.set_padding(padding);
+                            }
+                            Frame::Headers(frame)
+                        }
+                        Some(Frame::PushPromise(mut pp)) => {
+                            if let Some(padding) = stream.padding {
+                                pp.set_padding(padding);
+                            }
+
                             let mut pushed =
                                 stream.store_mut().find_mut(&pp.promised_id()).unwrap();
                             pushed.is_pending_push = false;
@@ -864,6 +973,13 @@ impl Prioritize {
 
                     tracing::trace!("pop_frame; frame={:?}", frame);
 
+                    let is_first_frame = !stream.is_send_started;
+                    stream.is_send_started = true;
+
+                    if is_first_frame {
+                        self.pending_send.open(stream.id);
+                    }
+
                     if cfg!(debug_assertions) && stream.state.is_idle() {
                         debug_assert!(stream.id > self.last_opened_id);
                         self.last_opened_id = stream.id;
@@ -874,7 +990,13 @@ impl Prioritize {
                         // the next frame. i.e. don't requeue it if the next
                         // frame is a data frame and the stream does not have
                         // any more capacity.
-                        self.pending_send.push(&mut stream);
+                        if is_first_frame {
+                            self.pending_send.push(&mut stream);
+                        } else {
+                            self.pending_send.requeue(&mut stream);
+                        }
+                    } else if stream.state.is_send_closed() {
+                        self.pending_send.close(&mut stream);
                     }
 
                     counts.transition_after(stream, is_pending_reset);
@@ -907,6 +1029,249 @@ impl Prioritize {
     }
 }
 
+// ===== impl PendingSend =====
+
+impl PendingSend {
+    fn new(config: &Config) -> Self {
+        let order = if let Some(ref new_scheduler) = config.scheduler {
+            Order::Custom(Custom {
+                scheduler: new_scheduler.build(),
+                opened: HashSet::new(),
+            })
+        } else if config.rfc7540_priorities {
+            Order::Dependencies(DependencyTree::new())
+        } else {
+            Order::Urgencies(std::array::from_fn(|_| store::Queue::new()))
+        };
+
+        PendingSend {
+            opening: store::Queue::new(),
+            order,
+        }
+    }
+
+    /// Queue the stream behind the other streams of the same priority.
+    fn push(&mut self, stream: &mut store::Ptr) -> bool {
+        if !stream.is_send_started {
+            return self.opening.push(stream);
+        }
+
+        match self.order {
+            Order::Urgencies(ref mut queues) => queues[urgency(stream)].push(stream),
+            Order::Dependencies(ref mut tree) => set_ready(tree, stream),
+            Order::Custom(ref mut custom) if custom.opened.contains(&stream.id) => {
+                custom.frame_ready(stream)
+            }
+            Order::Custom(_) => self.opening.push(stream),
+        }
+    }
+
+    /// Queue the stream ahead of the other streams of the same priority.
+    fn push_front(&mut self, stream: &mut store::Ptr) -> bool {
+        if !stream.is_send_started {
+            return self.opening.push_front(stream);
+        }
+
+        match self.order {
+            Order::Urgencies(ref mut queues) => queues[urgency(stream)].push_front(stream),
+            Order::Dependencies(ref mut tree) => set_ready(tree, stream),
+            Order::Custom(ref mut custom) if custom.opened.contains(&stream.id) => {
+                custom.frame_ready(stream)
+            }
+            Order::Custom(_) => self.opening.push_front(stream),
+        }
+    }
+
+    /// Queue a stream that just sent a frame.
+    fn requeue(&mut self, stream: &mut store::Ptr) -> bool {
+        match stream.send_priority {
+            Some(priority) if !priority.is_incremental() => self.push_front(stream),
+            _ => self.push(stream),
+        }
+    }
+
+    /// Called when the first frame of the stream was popped.
+    fn open(&mut self, id: StreamId) {
+        if let Order::Custom(ref mut custom) = self.order {
+            custom.opened.insert(id);
+            custom
+                .scheduler
+                .stream_opened(crate::StreamId::from_internal(id));
+        }
+    }
+
+    /// Called when the stream has nothing more to send.
+    fn close(&mut self, stream: &mut store::Ptr) {
+        if let Order::Custom(ref mut custom) = self.order {
+            if custom.opened.remove(&stream.id) {
+                // The scheduler forgets about the stream, which is no longer
+                // pending send.
+                stream.is_pending_send = false;
+                custom
+                    .scheduler
+                    .stream_closed(crate::StreamId::from_internal(stream.id));
+            }
+        }
+    }
+
+    fn capacity_assigned(&mut self, id: StreamId, capacity: WindowSize) {
+        if let Order::Custom(ref mut custom) = self.order {
+            custom
+                .scheduler
+                .capacity_assigned(crate::StreamId::from_internal(id), capacity);
+        }
+    }
+
+    /// Accounts for `len` bytes of data sent on the stream.
+    fn sent(&mut self, id: StreamId, len: usize) {
+        if let Order::Dependencies(ref mut tree) = self.order {
+            tree.sent(id, len);
+        }
+    }
+
+    /// Applies a stream dependency received from the peer. Streams opened
+    /// without one get the default priority.
+    fn reprioritize(
+        &mut self,
+        id: StreamId,
+        dependency: Option<&frame::StreamDependency>,
+        is_opened: bool,
+    ) {
+        if let Order::Dependencies(ref mut tree) = self.order {
+            match dependency {
+                Some(dependency) => tree.reprioritize(id, dependency, is_opened),
+                None => tree.open(id),
+            }
+        }
+    }
+
+    fn pop<'a>(&mut self, store: &'a mut Store) -> Option<store::Ptr<'a>> {
+        if !self.opening.is_empty() {
+            return self.opening.pop(store);
+        }
+
+        match self.order {
+            Order::Urgencies(ref mut queues) => loop {
+                let index = queues.iter().position(|queue| !queue.is_empty())?;
+                let mut stream = queues[index].pop(&mut *store).unwrap();
+
+                // The priority of a queued stream may have changed since, in
+                // which case it moves to its new queue.
+                if urgency(&stream) == index {
+                    let key = stream.key();
+                    return Some(store.resolve(key));
+                }
+
+                queues[urgency(&stream)].push(&mut stream);
+            },
+            Order::Dependencies(ref mut tree) => {
+                tree.prune(|id| store.find_mut(&id).is_some());
+
+                loop {
+                    let id = tree.pop()?;
+
+                    // Streams stay in the store while they are pending send,
+                    // so this only skips streams that were never opened.
+                    if let Some(mut stream) = store.find_mut(&id) {
+                        stream.is_pending_send = false;
+                        let key = stream.key();
+                        return Some(store.resolve(key));
+                    }
+                }
+            }
+            Order::Custom(ref mut custom) => {
+                // A scheduler that keeps returning streams that are not ready
+                // must not stall the connection, so it is given one attempt
+                // per opened stream.
+                for _ in 0..=custom.opened.len() {
+                    let id = custom.scheduler.next_stream()?;
+                    let id = StreamId::from(id.as_u32());
+
+                    if custom.opened.contains(&id) {
+                        if let Some(mut stream) = store.find_mut(&id) {
+                            if stream.is_pending_send {
+                                stream.is_pending_send = false;
+                                let key = stream.key();
+                                return Some(store.resolve(key));
+                            }
+                        }
+                    }
+
+                    tracing::trace!(?id, "scheduler returned a stream that is not ready");
+                }
+
+                None
+            }
+        }
+    }
+
+    /// Pops any stream, regardless of the order, to clear the streams.
+    fn pop_any<'a>(&mut self, store: &'a mut Store) -> Option<store::Ptr<'a>> {
+        let custom = match self.order {
+            Order::Custom(ref mut custom) if self.opening.is_empty() => custom,
+            _ => return self.pop(store),
+        };
+
+        // The scheduler may not return all of its streams, so they are
+        // closed instead.
+        while let Some(&id) = custom.opened.iter().next() {
+            custom.opened.remove(&id);
+            custom
+                .scheduler
+                .stream_closed(crate::StreamId::from_internal(id));
+
+            if let Some(mut stream) = store.find_mut(&id) {
+                if stream.is_pending_send {
+                    stream.is_pending_send = false;
+                    let key = stream.key();
+                    return Some(store.resolve(key));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+// ===== impl Custom =====
+
+impl Custom {
+    fn frame_ready(&mut self, stream: &mut store::Ptr) -> bool {
+        if stream.is_pending_send {
+            return false;
+        }
+
+        stream.is_pending_send = true;
+        self.scheduler
+            .frame_ready(crate::StreamId::from_internal(stream.id));
+        true
+    }
+}
+
+impl fmt::Debug for Custom {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Custom")
+            .field("opened", &self.opened)
+            .finish()
+    }
+}
+
+/// Marks a stream as pending send in the dependency tree.
+fn set_ready(tree: &mut DependencyTree, stream: &mut store::Ptr) -> bool {
+    if stream.is_pending_send {
+        return false;
+    }
+
+    stream.is_pending_send = true;
+    tree.set_ready(stream.id);
+    true
+}
+
+/// Returns the index of the urgency queue of the stream.
+fn urgency(stream: &Stream) -> usize {
+    stream.send_priority.unwrap_or_default().urgency() as usize
+}
+
 // ===== impl Prioritized =====
 
 impl<B> Buf for Prioritized<B>
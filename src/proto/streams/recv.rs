@@ -3,13 +3,18 @@ use crate::codec::UserError;
 use crate::frame::{PushPromiseHeaderError, Reason, DEFAULT_INITIAL_WINDOW_SIZE};
 use crate::proto;
 
-use http::{HeaderMap, Request, Response};
+use http::{HeaderMap, Method, Request, Response, Uri};
 
 use std::cmp::Ordering;
+use std::collections::VecDeque;
 use std::io;
 use std::task::{Context, Poll, Waker};
 use std::time::Instant;
 
+/// Maximum number of extension frames received on a stream and not read yet.
+/// Further frames are dropped, as unknown frames may be ignored.
+const MAX_PENDING_EXTENSION_FRAMES: usize = 64;
+
 #[derive(Debug)]
 pub(super) struct Recv {
     /// Initial window size of remote initiated streams
@@ -59,6 +64,26 @@ pub(super) struct Recv {
 
     /// If extended connect protocol is enabled.
     is_extended_connect_protocol_enabled: bool,
+
+    /// Decides whether promised requests are accepted
+    push_policy: Option<PushPolicy>,
+
+    /// Maximum number of open pushed streams per stream
+    max_concurrent_pushes_per_stream: Option<usize>,
+
+    /// Pushed streams kept until claimed, oldest first
+    push_cache: VecDeque<CachedPush>,
+
+    /// Maximum number of streams in the push cache
+    push_cache_size: usize,
+}
+
+/// A pushed stream kept in the push cache.
+#[derive(Debug)]
+struct CachedPush {
+    method: Method,
+    uri: Uri,
+    key: store::Key,
 }
 
 #[derive(Debug)]
@@ -108,6 +133,10 @@ impl Recv {
             refused: None,
             is_push_enabled: config.local_push_enabled,
             is_extended_connect_protocol_enabled: config.extended_connect_protocol_enabled,
+            push_policy: config.push_policy.clone(),
+            max_concurrent_pushes_per_stream: config.max_concurrent_pushes_per_stream,
+            push_cache: VecDeque::new(),
+            push_cache_size: config.push_cache_size,
         }
     }
 
@@ -150,9 +179,29 @@ impl Recv {
         Ok(Some(id))
     }
 
+    /// Opens the stream of an HTTP/1.1 request upgraded to HTTP/2.
+    ///
+    /// The request was received before the upgrade, so the stream is
+    /// half-closed (remote) and never queued for accept.
+    pub fn recv_upgrade(
+        &mut self,
+        stream: &mut store::Ptr,
+        counts: &mut Counts,
+    ) -> Result<(), Error> {
+        stream.state.recv_upgrade()?;
+
+        if stream.id > self.last_processed_id {
+            self.last_processed_id = stream.id;
+        }
+
+        counts.inc_num_recv_streams(stream);
+        Ok(())
+    }
+
     /// Transition the stream state based on receiving headers
     ///
     /// The caller ensures that the frame represents headers and not trailers.
+    #[allow(clippy::result_large_err)]
     pub fn recv_headers(
         &mut self,
         frame: frame::Headers,
@@ -247,6 +296,12 @@ impl Recv {
             return Err(Error::library_reset(stream.id, Reason::PROTOCOL_ERROR).into());
         }
 
+        if counts.peer().is_server() {
+            if let Some(value) = fields.get("priority") {
+                stream.send_priority = Some(Priority::parse(value.as_bytes()));
+            }
+        }
+
         if !pseudo.is_informational() {
             let message = counts
                 .peer()
@@ -509,6 +564,20 @@ impl Recv {
         self.clear_recv_buffer(stream);
     }
 
+    /// Returns the target of the connection window: the window available to
+    /// the peer plus the data received but not released yet.
+    pub fn target_connection_window(&self) -> WindowSize {
+        self.flow
+            .available()
+            .add(self.in_flight_data)
+            .map_or(MAX_WINDOW_SIZE, |size| size.checked_size())
+    }
+
+    /// Returns the connection window granted to the peer.
+    pub fn connection_window(&self) -> WindowSize {
+        self.flow.window_size()
+    }
+
     /// Set the "target" connection window size.
     ///
     /// By default, all new connections start with 64kb of window size. As
@@ -560,6 +629,13 @@ impl Recv {
         Ok(())
     }
 
+    /// Increases the connection window, both as advertised to the peer and as
+    /// available for receiving data, after a WINDOW_UPDATE was sent for it.
+    pub fn inc_connection_window(&mut self, size: WindowSize) -> Result<(), Reason> {
+        self.flow.inc_window(size)?;
+        self.flow.assign_capacity(size)
+    }
+
     pub(crate) fn apply_local_settings(
         &mut self,
         settings: &frame::Settings,
@@ -735,6 +811,7 @@ impl Recv {
 
         // Track the data as in-flight
         stream.in_flight_recv_data += sz;
+        stream.data_received += u64::from(sz);
 
         // We auto-release the padded length, since the user cannot.
         if let Some(padded_len) = frame.padded_len() {
@@ -743,7 +820,7 @@ impl Recv {
                 padded_len,
                 stream.id,
             );
-            let _res = self.release_capacity(padded_len.into(), stream, &mut None);
+            let _res = self.release_capacity(padded_len, stream, &mut None);
             // cannot fail, we JUST added more in_flight data above.
             debug_assert!(_res.is_ok());
         }
@@ -791,11 +868,16 @@ impl Recv {
         Ok(())
     }
 
+    /// Receives the promised request of a pushed stream.
+    ///
+    /// Returns the reason to reset the stream with if the push is declined,
+    /// because `is_over_push_limit` is set or the push policy rejects it.
     pub fn recv_push_promise(
         &mut self,
         frame: frame::PushPromise,
         stream: &mut store::Ptr,
-    ) -> Result<(), Error> {
+        is_over_push_limit: bool,
+    ) -> Result<Option<Reason>, Error> {
         stream.state.reserve_remote()?;
         if frame.is_over_size() {
             // A frame is over size if the decoded header block was bigger than
@@ -843,13 +925,90 @@ impl Recv {
             return Err(Error::library_reset(promised_id, Reason::PROTOCOL_ERROR));
         }
 
+        if is_over_push_limit {
+            tracing::debug!(
+                "recv_push_promise: too many concurrent pushes; promised_id={:?}",
+                promised_id,
+            );
+            return Ok(Some(Reason::REFUSED_STREAM));
+        }
+
+        if let Some(ref policy) = self.push_policy {
+            if !policy.accepts(&req) {
+                tracing::debug!(
+                    "recv_push_promise: push rejected by policy; promised_id={:?}",
+                    promised_id,
+                );
+                return Ok(Some(Reason::CANCEL));
+            }
+        }
+
+        if self.is_push_cache_enabled() {
+            // The cache holds a reference, so that the stream is not canceled
+            // once the stream that promised it is dropped.
+            stream.ref_inc();
+            self.push_cache.push_back(CachedPush {
+                method: req.method().clone(),
+                uri: req.uri().clone(),
+                key: stream.key(),
+            });
+        }
+
         use super::peer::PollMessage::*;
         stream
             .pending_recv
             .push_back(&mut self.buffer, Event::Headers(Server(req)));
         stream.notify_recv();
         stream.notify_push();
-        Ok(())
+        Ok(None)
+    }
+
+    /// Returns the maximum number of open pushed streams per stream.
+    pub fn max_concurrent_pushes_per_stream(&self) -> Option<usize> {
+        self.max_concurrent_pushes_per_stream
+    }
+
+    /// Returns true if accepted pushes are kept in the push cache, instead of
+    /// being queued on the stream that promised them.
+    pub fn is_push_cache_enabled(&self) -> bool {
+        self.push_cache_size > 0
+    }
+
+    /// Removes the oldest pushed stream from the push cache if it is over
+    /// its size. The caller releases the reference held by the cache.
+    pub fn pop_push_cache_overflow(&mut self) -> Option<store::Key> {
+        if self.push_cache.len() > self.push_cache_size {
+            self.push_cache.pop_front().map(|push| push.key)
+        } else {
+            None
+        }
+    }
+
+    /// Removes the pushed stream promising `method` and `uri` from the push
+    /// cache. The reference held by the cache is passed to the caller.
+    pub fn claim_push(
+        &mut self,
+        store: &mut Store,
+        method: &Method,
+        uri: &Uri,
+    ) -> Option<store::Key> {
+        use super::peer::PollMessage::*;
+
+        let pos = self
+            .push_cache
+            .iter()
+            .position(|push| push.method == *method && push.uri == *uri)?;
+        let key = self.push_cache.remove(pos)?.key;
+
+        // Drop the promised request, the response comes next.
+        match store[key].pending_recv.pop_front(&mut self.buffer) {
+            Some(Event::Headers(Server(_))) => {}
+            // When streams are cached, it is verified that the first frame
+            // is a HEADERS frame.
+            _ => panic!("Headers not set on pushed stream"),
+        }
+
+        Some(key)
     }
 
     /// Ensures that `id` is not in the `Idle` state.
@@ -935,6 +1094,25 @@ impl Recv {
         while stream.pending_recv.pop_front(&mut self.buffer).is_some() {
             // drop it
         }
+
+        stream.pending_extension_frames.clear();
+    }
+
+    /// Queues an extension frame received on an open stream.
+    pub fn recv_extension(&mut self, frame: frame::Extension, stream: &mut Stream) {
+        if stream.pending_extension_frames.len() >= MAX_PENDING_EXTENSION_FRAMES {
+            tracing::debug!(
+                "recv_extension; too many pending extension frames; stream={:?}",
+                stream.id
+            );
+            return;
+        }
+
+        stream.pending_extension_frames.push_back(frame);
+
+        if let Some(task) = stream.extension_task.take() {
+            task.wake();
+        }
     }
 
     /// Get the max ID of streams we can receive.
@@ -1229,6 +1407,23 @@ impl Recv {
         }
     }
 
+    pub fn poll_extension_frame(
+        &mut self,
+        cx: &Context,
+        stream: &mut Stream,
+    ) -> Poll<Option<Result<frame::Extension, proto::Error>>> {
+        if let Some(frame) = stream.pending_extension_frames.pop_front() {
+            return Poll::Ready(Some(Ok(frame)));
+        }
+
+        if stream.state.ensure_recv_open()? {
+            stream.extension_task = Some(cx.waker().clone());
+            Poll::Pending
+        } else {
+            Poll::Ready(None)
+        }
+    }
+
     fn schedule_recv<T>(
         &mut self,
         cx: &Context,
@@ -3,13 +3,15 @@ use super::{
     StreamIdOverflow, WindowSize,
 };
 use crate::codec::UserError;
+use crate::ext::{Priority, PseudoHeaderOrder, StreamDependency};
 use crate::frame::{self, Reason};
 use crate::proto::{self, Error, Initiator};
 
-use bytes::Buf;
+use bytes::{Buf, Bytes};
 use tokio::io::AsyncWrite;
 
 use std::cmp::Ordering;
+use std::collections::VecDeque;
 use std::io;
 use std::task::{Context, Poll, Waker};
 
@@ -38,6 +40,16 @@ pub(super) struct Send {
 
     /// If extended connect protocol is enabled.
     is_extended_connect_protocol_enabled: bool,
+
+    /// Order in which request pseudo headers are encoded
+    pseudo_header_order: PseudoHeaderOrder,
+
+    /// Stream dependency sent with request HEADERS by default
+    request_stream_dependency: Option<StreamDependency>,
+
+    /// Streams whose priority changed and must be sent in a
+    /// `PRIORITY_UPDATE` frame
+    pending_priority_updates: VecDeque<StreamId>,
 }
 
 /// A value to detect which public API has called `poll_reset`.
@@ -57,6 +69,9 @@ impl Send {
             prioritize: Prioritize::new(config),
             is_push_enabled: true,
             is_extended_connect_protocol_enabled: false,
+            pseudo_header_order: config.pseudo_header_order,
+            request_stream_dependency: config.request_stream_dependency,
+            pending_priority_updates: VecDeque::new(),
         }
     }
 
@@ -346,8 +361,110 @@ impl Send {
         T: AsyncWrite + Unpin,
         B: Buf,
     {
-        self.prioritize
-            .poll_complete(cx, buffer, store, counts, dst)
+        loop {
+            ready!(self.poll_priority_updates(cx, store, dst))?;
+            ready!(self
+                .prioritize
+                .poll_complete(cx, buffer, store, counts, dst))?;
+
+            // Writing frames may have started streams with an update waiting
+            // for their HEADERS frame to go out first.
+            if !self.has_ready_priority_updates(store) {
+                return Poll::Ready(Ok(()));
+            }
+        }
+    }
+
+    /// Applies a stream dependency received in a `PRIORITY` or `HEADERS`
+    /// frame, or `None` for a `HEADERS` frame without one.
+    pub fn recv_stream_dependency(
+        &mut self,
+        id: StreamId,
+        dependency: Option<&frame::StreamDependency>,
+        is_opened: bool,
+    ) {
+        self.prioritize.reprioritize(id, dependency, is_opened);
+    }
+
+    /// Sets the priority used to schedule the stream's frames.
+    ///
+    /// On the client, the new priority is also sent to the server in a
+    /// `PRIORITY_UPDATE` frame.
+    pub fn set_priority(
+        &mut self,
+        priority: Priority,
+        stream: &mut store::Ptr,
+        counts: &mut Counts,
+        task: &mut Option<Waker>,
+    ) {
+        if stream.send_priority == Some(priority) {
+            return;
+        }
+
+        stream.send_priority = Some(priority);
+
+        if counts.peer().is_server() || stream.state.is_closed() {
+            return;
+        }
+
+        if !self.pending_priority_updates.contains(&stream.id) {
+            self.pending_priority_updates.push_back(stream.id);
+        }
+
+        if let Some(task) = task.take() {
+            task.wake();
+        }
+    }
+
+    /// Writes the `PRIORITY_UPDATE` frames of the streams that have sent
+    /// their request HEADERS.
+    fn poll_priority_updates<T, B>(
+        &mut self,
+        cx: &mut Context,
+        store: &mut Store,
+        dst: &mut Codec<T, Prioritized<B>>,
+    ) -> Poll<io::Result<()>>
+    where
+        T: AsyncWrite + Unpin,
+        B: Buf,
+    {
+        let mut i = 0;
+
+        while i < self.pending_priority_updates.len() {
+            let id = self.pending_priority_updates[i];
+
+            let priority = match store.find_mut(&id) {
+                Some(stream) if stream.state.is_closed() => None,
+                Some(stream) if !stream.is_send_started => {
+                    // Updating the priority of an idle stream would make
+                    // the server buffer it, so wait for the HEADERS frame.
+                    i += 1;
+                    continue;
+                }
+                Some(stream) => Some(stream.send_priority.unwrap_or_default()),
+                None => None,
+            };
+
+            if let Some(priority) = priority {
+                ready!(dst.poll_ready(cx))?;
+
+                let value = Bytes::from(priority.to_string());
+                dst.buffer(frame::PriorityUpdate::new(id, value).into())
+                    .expect("invalid PRIORITY_UPDATE frame");
+            }
+
+            self.pending_priority_updates.remove(i);
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    fn has_ready_priority_updates(&self, store: &mut Store) -> bool {
+        self.pending_priority_updates.iter().any(|id| {
+            store
+                .find_mut(id)
+                .map_or(false, |stream| stream.is_send_started)
+        })
     }
 
     /// Request capacity to send data
@@ -380,6 +497,11 @@ impl Send {
     }
 
     /// Current available stream send capacity
+    /// Returns the connection window available to send data.
+    pub fn connection_window(&self) -> WindowSize {
+        self.prioritize.connection_window()
+    }
+
     pub fn capacity(&self, stream: &mut store::Ptr) -> WindowSize {
         stream.capacity(self.prioritize.max_buffer_size())
     }
@@ -463,6 +585,7 @@ impl Send {
     ) {
         // Clear all pending outbound frames
         self.prioritize.clear_queue(buffer, stream);
+        self.prioritize.unschedule(stream);
         self.prioritize.reclaim_all_capacity(stream, counts);
     }
 
@@ -635,4 +758,12 @@ impl Send {
     pub(crate) fn is_extended_connect_protocol_enabled(&self) -> bool {
         self.is_extended_connect_protocol_enabled
     }
+
+    pub(crate) fn pseudo_header_order(&self) -> PseudoHeaderOrder {
+        self.pseudo_header_order
+    }
+
+    pub(crate) fn request_stream_dependency(&self) -> Option<StreamDependency> {
+        self.request_stream_dependency
+    }
 }
@@ -204,6 +204,21 @@ impl State {
         Ok(initial)
     }
 
+    /// Transition from Idle -> HalfClosedRemote, for the stream of an
+    /// HTTP/1.1 request upgraded to HTTP/2, which was fully received.
+    pub fn recv_upgrade(&mut self) -> Result<(), Error> {
+        match self.inner {
+            Idle => {
+                self.inner = HalfClosedRemote(AwaitingHeaders);
+                Ok(())
+            }
+            ref state => {
+                proto_err!(conn: "recv_upgrade: in unexpected state {:?}", state);
+                Err(Error::library_go_away(Reason::PROTOCOL_ERROR))
+            }
+        }
+    }
+
     /// Transition from Idle -> ReservedRemote
     pub fn reserve_remote(&mut self) -> Result<(), Error> {
         match self.inner {
@@ -447,6 +462,7 @@ impl State {
         match self.inner {
             Closed(Cause::Error(Error::Reset(_, reason, _)))
             | Closed(Cause::Error(Error::GoAway(_, reason, _)))
+            | Closed(Cause::Error(Error::Unprocessed(_, reason)))
             | Closed(Cause::ScheduledLibraryReset(reason)) => Ok(Some(reason)),
             Closed(Cause::Error(ref e)) => Err(e.clone().into()),
             Open {
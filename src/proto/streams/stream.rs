@@ -1,7 +1,9 @@
+use crate::padding::Padding;
 use crate::Reason;
 
 use super::*;
 
+use std::collections::VecDeque;
 use std::fmt;
 use std::task::{Context, Waker};
 use std::time::Instant;
@@ -44,6 +46,9 @@ pub(super) struct Stream {
     /// Amount of send capacity that has been requested, but not yet allocated.
     pub requested_send_capacity: WindowSize,
 
+    /// Total amount of data sent on the stream.
+    pub data_sent: u64,
+
     /// Amount of data buffered at the prioritization layer.
     /// TODO: Technically this could be greater than the window size...
     pub buffered_send_data: usize,
@@ -73,6 +78,17 @@ pub(super) struct Stream {
     /// Set to true when a push is pending for this stream
     pub is_pending_push: bool,
 
+    /// The RFC 9218 priority used to schedule the stream's frames, if one
+    /// was signaled
+    pub send_priority: Option<Priority>,
+
+    /// The padding policy of the stream's frames, overriding the one of the
+    /// connection
+    pub padding: Option<Padding>,
+
+    /// Set to true once the stream's first frame has been popped for sending
+    pub is_send_started: bool,
+
     // ===== Fields related to receiving =====
     /// Next node in the accept linked list
     pub next_pending_accept: Option<store::Key>,
@@ -85,6 +101,9 @@ pub(super) struct Stream {
 
     pub in_flight_recv_data: WindowSize,
 
+    /// Total amount of data received on the stream, padding included.
+    pub data_received: u64,
+
     /// Next node in the linked list of streams waiting to send window updates.
     pub next_window_update: Option<store::Key>,
 
@@ -112,6 +131,16 @@ pub(super) struct Stream {
     /// The stream's pending push promises
     pub pending_push_promises: store::Queue<NextAccept>,
 
+    /// Streams pushed by this stream that may still be open, when the
+    /// number of concurrent pushes is limited
+    pub pushed_streams: Vec<StreamId>,
+
+    /// Extension frames pending for this stream to read
+    pub pending_extension_frames: VecDeque<frame::Extension>,
+
+    /// Task tracking received extension frames
+    pub extension_task: Option<Waker>,
+
     /// Validate content-length headers
     pub content_length: ContentLength,
 }
@@ -169,6 +198,7 @@ impl Stream {
             is_pending_send: false,
             send_flow,
             requested_send_capacity: 0,
+            data_sent: 0,
             buffered_send_data: 0,
             send_task: None,
             pending_send: buffer::Deque::new(),
@@ -178,12 +208,16 @@ impl Stream {
             is_pending_open: false,
             next_open: None,
             is_pending_push: false,
+            send_priority: None,
+            padding: None,
+            is_send_started: false,
 
             // ===== Fields related to receiving =====
             next_pending_accept: None,
             is_pending_accept: false,
             recv_flow,
             in_flight_recv_data: 0,
+            data_received: 0,
             next_window_update: None,
             is_pending_window_update: false,
             reset_at: None,
@@ -193,6 +227,9 @@ impl Stream {
             recv_task: None,
             push_task: None,
             pending_push_promises: store::Queue::new(),
+            pushed_streams: Vec::new(),
+            pending_extension_frames: VecDeque::new(),
+            extension_task: None,
             content_length: ContentLength::Omitted,
         }
     }
@@ -307,6 +344,8 @@ impl Stream {
         let _res = self.send_flow.send_data(len);
         debug_assert!(_res.is_ok());
 
+        self.data_sent += u64::from(len);
+
         // Decrement the stream's buffered data counter
         debug_assert!(self.buffered_send_data >= len as usize);
         self.buffered_send_data -= len as usize;
@@ -335,6 +374,7 @@ impl Stream {
     }
 
     /// Returns `Err` when the decrement cannot be completed due to overflow.
+    #[allow(clippy::collapsible_match)]
     pub fn dec_content_length(&mut self, len: usize) -> Result<(), ()> {
         match self.content_length {
             ContentLength::Remaining(ref mut rem) => match rem.checked_sub(len as u64) {
@@ -374,6 +414,10 @@ impl Stream {
         if let Some(task) = self.recv_task.take() {
             task.wake();
         }
+
+        if let Some(task) = self.extension_task.take() {
+            task.wake();
+        }
     }
 
     pub(super) fn notify_push(&mut self) {
@@ -439,6 +483,17 @@ impl fmt::Debug for Stream {
                 !self.pending_push_promises.is_empty(),
                 &self.pending_push_promises,
             )
+            .h2_field_if_then(
+                "pushed_streams",
+                !self.pushed_streams.is_empty(),
+                &self.pushed_streams,
+            )
+            .h2_field_if_then(
+                "pending_extension_frames",
+                !self.pending_extension_frames.is_empty(),
+                &self.pending_extension_frames,
+            )
+            .h2_field_some("extension_task", &self.extension_task.as_ref().map(|_| ()))
             .field("content_length", &self.content_length)
             .finish()
     }
@@ -2,18 +2,23 @@ use super::recv::RecvHeaderBlockError;
 use super::store::{self, Entry, Resolve, Store};
 use super::{Buffer, Config, Counts, Prioritized, Recv, Send, Stream, StreamId};
 use crate::codec::{Codec, SendError, UserError};
-use crate::ext::{Protocol, PseudoHeadersOverride};
+use crate::ext::{
+    HeaderIndexingOverride, OrderedHeaders, Priority, Protocol, PseudoHeadersOverride,
+    StreamDependency,
+};
 use crate::frame::{self, Frame, Reason};
+use crate::padding::Padding;
 use crate::proto::{peer, Error, Initiator, Open, Peer, WindowSize};
+use crate::stats::{ConnectionStats, StreamStats};
 use crate::{client, proto, server};
 
 use bytes::{Buf, Bytes};
-use http::{HeaderMap, Request, Response};
+use http::{HeaderMap, Method, Request, Response, Uri};
 use std::task::{Context, Poll, Waker};
 use tokio::io::AsyncWrite;
 
 use std::sync::{Arc, Mutex};
-use std::{fmt, io};
+use std::{fmt, io, mem};
 
 #[derive(Debug)]
 pub(crate) struct Streams<B, P>
@@ -127,6 +132,54 @@ where
             .set_target_connection_window(size, &mut me.actions.task)
     }
 
+    /// Returns the target size of the connection-level receive window.
+    pub fn target_connection_window_size(&self) -> WindowSize {
+        let me = self.inner.lock().unwrap();
+        me.actions.recv.target_connection_window()
+    }
+
+    /// Accounts for a connection-level WINDOW_UPDATE sent outside of the
+    /// regular flow control logic.
+    pub fn send_connection_window_update(&mut self, size: WindowSize) -> Result<(), Reason> {
+        let mut me = self.inner.lock().unwrap();
+        me.actions.recv.inc_connection_window(size)
+    }
+
+    /// Opens stream 1, which carries the response to an HTTP/1.1 request
+    /// upgraded to HTTP/2.
+    pub fn recv_upgrade(&mut self) -> Result<StreamRef<B>, Error> {
+        let mut me = self.inner.lock().unwrap();
+        let me = &mut *me;
+
+        let id = StreamId::from(1);
+        let id = match me.actions.recv.open(id, Open::Headers, &mut me.counts)? {
+            Some(id) => id,
+            None => return Err(Error::library_reset(id, Reason::REFUSED_STREAM)),
+        };
+
+        let stream = Stream::new(
+            id,
+            me.actions.send.init_window_sz(),
+            me.actions.recv.init_window_sz(),
+        );
+        let key = me.store.insert(id, stream).key();
+
+        let actions = &mut me.actions;
+        me.counts
+            .transition(me.store.resolve(key), |counts, stream| {
+                actions.recv.recv_upgrade(stream, counts)
+            })?;
+
+        // TODO: ideally, OpaqueStreamRefs::new would do this, but we're holding
+        // the lock, so it can't.
+        me.refs += 1;
+
+        Ok(StreamRef {
+            opaque: OpaqueStreamRef::new(self.inner.clone(), &mut me.store.resolve(key)),
+            send_buffer: self.send_buffer.clone(),
+        })
+    }
+
     pub fn next_incoming(&mut self) -> Option<StreamRef<B>> {
         let mut me = self.inner.lock().unwrap();
         let me = &mut *me;
@@ -224,10 +277,14 @@ where
         use super::stream::ContentLength;
         use http::Method;
 
-        let protocol = request.extensions_mut().remove::<Protocol>();
-        let pseudo_overrides = request
-            .extensions_mut()
-            .remove::<PseudoHeadersOverride>();
+        let extensions = request.extensions_mut();
+        let mut send_extensions = client::SendExtensions {
+            protocol: extensions.remove::<Protocol>(),
+            pseudo_overrides: extensions.remove::<PseudoHeadersOverride>(),
+            stream_dep: extensions.remove::<StreamDependency>(),
+            ordered_headers: extensions.remove::<OrderedHeaders>(),
+            header_indexing: extensions.remove::<HeaderIndexingOverride>(),
+        };
 
         // Clear before taking lock, incase extensions contain a StreamRef.
         request.extensions_mut().clear();
@@ -265,6 +322,16 @@ where
 
         let stream_id = me.actions.send.open()?;
 
+        if send_extensions.stream_dep.is_none() {
+            send_extensions.stream_dep = me.actions.send.request_stream_dependency();
+        }
+
+        let pseudo_order = send_extensions
+            .pseudo_overrides
+            .as_ref()
+            .and_then(|overrides| overrides.order)
+            .unwrap_or_else(|| me.actions.send.pseudo_header_order());
+
         let mut stream = Stream::new(
             stream_id,
             me.actions.send.init_window_sz(),
@@ -275,12 +342,24 @@ where
             stream.content_length = ContentLength::Head;
         }
 
+        // The priority is read from the fields that are actually sent.
+        let priority = match send_extensions.ordered_headers {
+            Some(ref fields) => fields
+                .iter()
+                .find(|&(name, _)| name == "priority")
+                .map(|(_, value)| value),
+            None => request.headers().get("priority"),
+        };
+        if let Some(value) = priority {
+            stream.send_priority = Some(Priority::parse(value.as_bytes()));
+        }
+
         // Convert the message
         let headers = client::Peer::convert_send_message(
             stream_id,
             request,
-            protocol,
-            pseudo_overrides,
+            send_extensions,
+            pseudo_order,
             end_of_stream,
         )?;
 
@@ -391,6 +470,23 @@ impl<B> DynStreams<'_, B> {
         me.recv_push_promise(self.send_buffer, frame)
     }
 
+    pub fn recv_priority(&mut self, frame: frame::Priority) {
+        let mut me = self.inner.lock().unwrap();
+        me.recv_priority(frame)
+    }
+
+    /// Queues an extension frame on its stream, or returns it if the stream
+    /// is not open.
+    pub fn recv_extension(&mut self, frame: frame::Extension) -> Option<frame::Extension> {
+        let mut me = self.inner.lock().unwrap();
+        me.recv_extension(frame)
+    }
+
+    pub fn recv_priority_update(&mut self, frame: frame::PriorityUpdate) -> Result<(), Error> {
+        let mut me = self.inner.lock().unwrap();
+        me.recv_priority_update(self.peer, frame)
+    }
+
     pub fn recv_eof(&mut self, clear_pending_accept: bool) -> Result<(), ()> {
         let mut me = self.inner.lock().map_err(|_| ())?;
         me.recv_eof(self.send_buffer, clear_pending_accept)
@@ -484,6 +580,10 @@ impl Inner {
             }
         };
 
+        self.actions
+            .send
+            .recv_stream_dependency(id, frame.stream_dependency(), true);
+
         let stream = self.store.resolve(key);
 
         if stream.state.is_local_error() {
@@ -652,6 +752,52 @@ impl Inner {
         })
     }
 
+    fn recv_priority(&mut self, frame: frame::Priority) {
+        let id = frame.stream_id();
+        let is_opened = self.store.find_mut(&id).is_some();
+
+        self.actions
+            .send
+            .recv_stream_dependency(id, Some(frame.dependency()), is_opened);
+    }
+
+    fn recv_extension(&mut self, frame: frame::Extension) -> Option<frame::Extension> {
+        match self.store.find_mut(&frame.stream_id()) {
+            Some(mut stream) if stream.is_recv && !stream.state.is_closed() => {
+                self.actions.recv.recv_extension(frame, &mut stream);
+                None
+            }
+            _ => Some(frame),
+        }
+    }
+
+    fn recv_priority_update(
+        &mut self,
+        peer: peer::Dyn,
+        frame: frame::PriorityUpdate,
+    ) -> Result<(), Error> {
+        let id = frame.prioritized_id();
+
+        if !peer.is_server() {
+            proto_err!(conn: "recv_priority_update: client received PRIORITY_UPDATE");
+            return Err(Error::library_go_away(Reason::PROTOCOL_ERROR));
+        }
+
+        if peer.is_local_init(id) {
+            proto_err!(conn: "recv_priority_update: cannot prioritize pushed stream; id={:?}", id);
+            return Err(Error::library_go_away(Reason::PROTOCOL_ERROR));
+        }
+
+        // Updates for streams that are not open yet are ignored, the
+        // request's `priority` header applies when it arrives. Updates for
+        // closed streams are ignored as well.
+        if let Some(mut stream) = self.store.find_mut(&id) {
+            stream.send_priority = Some(Priority::parse(frame.field_value()));
+        }
+
+        Ok(())
+    }
+
     fn recv_window_update<B>(
         &mut self,
         send_buffer: &SendBuffer<B>,
@@ -714,7 +860,10 @@ impl Inner {
             })
         });
 
-        actions.conn_error = Some(err);
+        // Streams opened after a GOAWAY was received remain unprocessed.
+        if !matches!(actions.conn_error, Some(proto::Error::Unprocessed(..))) {
+            actions.conn_error = Some(err);
+        }
 
         last_processed_id
     }
@@ -733,7 +882,9 @@ impl Inner {
 
         actions.send.recv_go_away(last_stream_id)?;
 
-        let err = Error::remote_go_away(frame.debug_data().clone(), frame.reason());
+        // The streams above the last stream ID were not processed by the
+        // peer, and may be retried on another connection.
+        let err = Error::unprocessed(frame.debug_data().clone(), frame.reason());
 
         let peer = counts.peer();
         self.store.for_each(|stream| {
@@ -745,6 +896,7 @@ impl Inner {
             }
         });
 
+        // Neither are the streams opened from now on.
         actions.conn_error = Some(err);
 
         Ok(())
@@ -793,6 +945,22 @@ impl Inner {
         // Ensure that we can reserve streams
         self.actions.recv.ensure_can_reserve()?;
 
+        let is_over_push_limit = match self.actions.recv.max_concurrent_pushes_per_stream() {
+            Some(max) => {
+                // Forget the pushed streams that are closed.
+                let mut pushed = mem::take(&mut self.store[parent_key].pushed_streams);
+                pushed.retain(|id| {
+                    self.store
+                        .find_mut(id)
+                        .map_or(false, |stream| !stream.state.is_closed())
+                });
+                let is_over_limit = pushed.len() >= max;
+                self.store[parent_key].pushed_streams = pushed;
+                is_over_limit
+            }
+            None => false,
+        };
+
         // Next, open the stream.
         //
         // If `None` is returned, then the stream is being refused. There is no
@@ -821,19 +989,32 @@ impl Inner {
             let actions = &mut self.actions;
 
             self.counts.transition(stream, |counts, stream| {
-                let stream_valid = actions.recv.recv_push_promise(frame, stream);
+                let stream_valid =
+                    actions
+                        .recv
+                        .recv_push_promise(frame, stream, is_over_push_limit);
 
                 match stream_valid {
-                    Ok(()) => Ok(Some(stream.key())),
-                    _ => {
+                    Ok(None) => Ok(Some(stream.key())),
+                    Ok(Some(reason)) => {
+                        // The push is declined, which is not an error of the
+                        // peer.
+                        let mut send_buffer = send_buffer.inner.lock().unwrap();
+                        actions.send.send_reset(
+                            reason,
+                            Initiator::Library,
+                            &mut send_buffer,
+                            stream,
+                            counts,
+                            &mut actions.task,
+                        );
+                        actions.recv.enqueue_reset_expiration(stream, counts);
+                        Ok(None)
+                    }
+                    Err(err) => {
                         let mut send_buffer = send_buffer.inner.lock().unwrap();
                         actions
-                            .reset_on_recv_stream_err(
-                                &mut *send_buffer,
-                                stream,
-                                counts,
-                                stream_valid,
-                            )
+                            .reset_on_recv_stream_err(&mut *send_buffer, stream, counts, Err(err))
                             .map(|()| None)
                     }
                 }
@@ -841,12 +1022,34 @@ impl Inner {
         };
         // If we're successful, push the headers and stream...
         if let Some(child) = child_key {
-            let mut ppp = self.store[parent_key].pending_push_promises.take();
-            ppp.push(&mut self.store.resolve(child));
+            if self
+                .actions
+                .recv
+                .max_concurrent_pushes_per_stream()
+                .is_some()
+            {
+                self.store[parent_key].pushed_streams.push(promised_id);
+            }
+
+            if self.actions.recv.is_push_cache_enabled() {
+                // The stream was cached by `recv_push_promise`, make room for
+                // it.
+                if let Some(evicted) = self.actions.recv.pop_push_cache_overflow() {
+                    let actions = &mut self.actions;
+                    let mut stream = self.store.resolve(evicted);
+                    stream.ref_dec();
+                    self.counts.transition(stream, |counts, stream| {
+                        maybe_cancel(stream, actions, counts);
+                    });
+                }
+            } else {
+                let mut ppp = self.store[parent_key].pending_push_promises.take();
+                ppp.push(&mut self.store.resolve(child));
 
-            let parent = &mut self.store.resolve(parent_key);
-            parent.pending_push_promises = ppp;
-            parent.notify_push();
+                let parent = &mut self.store.resolve(parent_key);
+                parent.pending_push_promises = ppp;
+                parent.notify_push();
+            }
         };
 
         Ok(())
@@ -1000,6 +1203,21 @@ where
         }
         Poll::Ready(Ok(()))
     }
+
+    /// Takes the pushed stream promising `method` and `uri` from the push
+    /// cache.
+    pub fn claim_push(&mut self, method: &Method, uri: &Uri) -> Option<OpaqueStreamRef> {
+        let mut me = self.inner.lock().unwrap();
+        let me = &mut *me;
+
+        let key = me.actions.recv.claim_push(&mut me.store, method, uri)?;
+        let mut stream = me.store.resolve(key);
+
+        // The reference held by the cache is replaced by the returned one.
+        stream.ref_dec();
+        me.refs += 1;
+        Some(OpaqueStreamRef::new(self.inner.clone(), &mut stream))
+    }
 }
 
 impl<B, P> Streams<B, P>
@@ -1045,6 +1263,14 @@ where
         me.counts.has_streams()
     }
 
+    /// Fills in the connection-level windows and stream counts of `stats`.
+    pub fn record_stats(&self, stats: &mut ConnectionStats) {
+        let me = self.inner.lock().unwrap();
+        stats.send_window = me.actions.send.connection_window();
+        stats.recv_window = me.actions.recv.connection_window();
+        stats.peak_concurrent_streams = me.counts.peak_streams();
+    }
+
     pub fn has_streams_or_other_references(&self) -> bool {
         let me = self.inner.lock().unwrap();
         me.counts.has_streams() || me.refs > 1
@@ -1297,6 +1523,24 @@ impl<B> StreamRef<B> {
         me.store.resolve(self.opaque.key).is_pending_open
     }
 
+    /// Sets the priority used to schedule the stream's frames
+    pub fn set_priority(&mut self, priority: Priority) {
+        let mut me = self.opaque.inner.lock().unwrap();
+        let me = &mut *me;
+
+        let mut stream = me.store.resolve(self.opaque.key);
+
+        me.actions
+            .send
+            .set_priority(priority, &mut stream, &mut me.counts, &mut me.actions.task)
+    }
+
+    /// Sets the padding policy of the stream's frames
+    pub fn set_padding(&mut self, padding: Padding) {
+        let mut me = self.opaque.inner.lock().unwrap();
+        me.store[self.opaque.key].padding = Some(padding);
+    }
+
     /// Request capacity to send data
     pub fn reserve_capacity(&mut self, capacity: WindowSize) {
         let mut me = self.opaque.inner.lock().unwrap();
@@ -1347,6 +1591,10 @@ impl<B> StreamRef<B> {
         self.opaque.clone()
     }
 
+    pub fn stats(&self) -> StreamStats {
+        self.opaque.stats()
+    }
+
     pub fn stream_id(&self) -> StreamId {
         self.opaque.stream_id()
     }
@@ -1440,6 +1688,18 @@ impl OpaqueStreamRef {
         me.actions.recv.poll_trailers(cx, &mut stream)
     }
 
+    pub fn poll_extension_frame(
+        &mut self,
+        cx: &Context,
+    ) -> Poll<Option<Result<frame::Extension, proto::Error>>> {
+        let mut me = self.inner.lock().unwrap();
+        let me = &mut *me;
+
+        let mut stream = me.store.resolve(self.key);
+
+        me.actions.recv.poll_extension_frame(cx, &mut stream)
+    }
+
     pub(crate) fn available_recv_capacity(&self) -> isize {
         let me = self.inner.lock().unwrap();
         let me = &*me;
@@ -1482,6 +1742,17 @@ impl OpaqueStreamRef {
     pub fn stream_id(&self) -> StreamId {
         self.inner.lock().unwrap().store[self.key].id
     }
+
+    pub fn stats(&self) -> StreamStats {
+        let me = self.inner.lock().unwrap();
+        let stream = &me.store[self.key];
+        StreamStats {
+            bytes_sent: stream.data_sent,
+            bytes_received: stream.data_received,
+            send_window: stream.send_flow.window_size(),
+            recv_window: stream.recv_flow.window_size(),
+        }
+    }
 }
 
 impl fmt::Debug for OpaqueStreamRef {
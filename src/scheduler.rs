@@ -0,0 +1,130 @@
+//! Pluggable scheduling of sent frames.
+//!
+//! By default, a connection shares its write opportunities between the
+//! streams that have frames to send in a round-robin fashion, following the
+//! [RFC 9218] priorities of the streams. A [`SendScheduler`] replaces this
+//! strategy, and is installed with [`client::Builder::scheduler`] or
+//! [`server::Builder::scheduler`].
+//!
+//! # Examples
+//!
+//! A scheduler that sends the frames of the oldest stream first, so that
+//! streams are drained one at a time:
+//!
+//! ```
+//! use h2::scheduler::SendScheduler;
+//! use h2::StreamId;
+//! use std::collections::{BTreeSet, HashMap};
+//!
+//! #[derive(Default)]
+//! struct Fifo {
+//!     next_seq: u64,
+//!     seqs: HashMap<StreamId, u64>,
+//!     ready: BTreeSet<(u64, StreamId)>,
+//! }
+//!
+//! impl SendScheduler for Fifo {
+//!     fn stream_opened(&mut self, id: StreamId) {
+//!         self.seqs.insert(id, self.next_seq);
+//!         self.next_seq += 1;
+//!     }
+//!
+//!     fn stream_closed(&mut self, id: StreamId) {
+//!         if let Some(seq) = self.seqs.remove(&id) {
+//!             self.ready.remove(&(seq, id));
+//!         }
+//!     }
+//!
+//!     fn frame_ready(&mut self, id: StreamId) {
+//!         self.ready.insert((self.seqs[&id], id));
+//!     }
+//!
+//!     fn next_stream(&mut self) -> Option<StreamId> {
+//!         let first = *self.ready.iter().next()?;
+//!         self.ready.remove(&first);
+//!         Some(first.1)
+//!     }
+//! }
+//!
+//! let mut builder = h2::server::Builder::new();
+//! builder.scheduler(Fifo::default);
+//! ```
+//!
+//! [RFC 9218]: https://www.rfc-editor.org/rfc/rfc9218.html
+//! [`client::Builder::scheduler`]: crate::client::Builder::scheduler
+//! [`server::Builder::scheduler`]: crate::server::Builder::scheduler
+
+use crate::StreamId;
+
+use std::fmt;
+use std::sync::Arc;
+
+/// Decides which stream sends the next frame of a connection.
+///
+/// The connection notifies the scheduler of the streams it sends frames for,
+/// and asks it for the next stream each time it can write a frame.
+///
+/// A stream is *opened* when its first frame is written. The frames that
+/// open streams are always written first, in stream ID order, as required
+/// by the protocol, and are not scheduled. Once open, each time the stream
+/// has a frame to send, [`frame_ready`] is called, and the stream is *ready*
+/// until [`next_stream`] returns it. It is then ready again after the frame
+/// was written, if it has more frames to send. A stream is *closed* once it
+/// has nothing more to send, or was reset; it is not ready anymore then.
+///
+/// [`frame_ready`]: SendScheduler::frame_ready
+/// [`next_stream`]: SendScheduler::next_stream
+pub trait SendScheduler: Send + 'static {
+    /// Called when the first frame of a stream was written.
+    fn stream_opened(&mut self, id: StreamId) {
+        let _ = id;
+    }
+
+    /// Called when a stream has nothing more to send.
+    fn stream_closed(&mut self, id: StreamId) {
+        let _ = id;
+    }
+
+    /// Called when `capacity` bytes of connection flow control capacity were
+    /// assigned to a stream, which may not be open yet.
+    fn capacity_assigned(&mut self, id: StreamId, capacity: u32) {
+        let _ = (id, capacity);
+    }
+
+    /// Called when an open stream has a frame to send.
+    fn frame_ready(&mut self, id: StreamId);
+
+    /// Returns the ready stream that sends the next frame, which is not ready
+    /// anymore, or `None` if no stream is ready.
+    ///
+    /// Returning `None` while streams are ready stops the connection from
+    /// writing their frames until another stream is ready. Identifiers of
+    /// streams that are not ready are skipped, but once the scheduler returned
+    /// more of them than there are open streams, the connection stops writing
+    /// frames as if it returned `None`.
+    fn next_stream(&mut self) -> Option<StreamId>;
+}
+
+/// Creates the scheduler of each connection of a builder.
+#[derive(Clone)]
+pub(crate) struct NewScheduler(Arc<dyn Fn() -> Box<dyn SendScheduler> + Send + Sync>);
+
+impl NewScheduler {
+    pub(crate) fn new<F, S>(new_scheduler: F) -> Self
+    where
+        F: Fn() -> S + Send + Sync + 'static,
+        S: SendScheduler,
+    {
+        NewScheduler(Arc::new(move || Box::new(new_scheduler())))
+    }
+
+    pub(crate) fn build(&self) -> Box<dyn SendScheduler> {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for NewScheduler {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("NewScheduler").finish()
+    }
+}
@@ -116,8 +116,13 @@
 //! [`TcpListener`]: https://docs.rs/tokio-core/0.1/tokio_core/net/struct.TcpListener.html
 
 use crate::codec::{Codec, UserError};
+use crate::ext::{ExtensionFrame, ExtensionFrameHandlers, Priority};
 use crate::frame::{self, Pseudo, PushPromiseHeaderError, Reason, Settings, StreamId};
+use crate::padding::Padding;
 use crate::proto::{self, Config, Error, Prioritized};
+use crate::scheduler::{NewScheduler, SendScheduler};
+use crate::stats::ConnectionStats;
+use crate::timer::{SharedTimer, Timer};
 use crate::{FlowControl, PingPong, RecvStream, SendStream};
 
 use bytes::{Buf, Bytes};
@@ -154,6 +159,42 @@ pub struct Handshake<T, B: Buf = Bytes> {
     span: tracing::Span,
 }
 
+/// In progress HTTP/2 connection handshake future, for a connection upgraded
+/// from HTTP/1.1.
+///
+/// This type implements `Future`, yielding a `Connection` instance, and the
+/// [`SendResponse`] of the upgraded request on stream 1, once the handshake
+/// has completed.
+///
+/// See [`Builder::handshake_upgrade`] for more details.
+#[must_use = "futures do nothing unless polled"]
+pub struct HandshakeUpgrade<T, B: Buf = Bytes> {
+    /// The handshake of the upgraded connection.
+    handshake: Handshake<T, B>,
+    /// The settings of the `HTTP2-Settings` header of the upgraded request.
+    settings: Result<Settings, frame::Error>,
+}
+
+/// The protocol of a connection, as detected by [`detect_preface`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Detected {
+    /// The client sent the HTTP/2 connection preface, assuming prior
+    /// knowledge that the server supports HTTP/2.
+    Http2,
+    /// The client sent something else, such as an HTTP/1.1 request.
+    Http1,
+}
+
+/// An I/O handle that replays the bytes read by [`detect_preface`] before
+/// reading from the underlying I/O.
+///
+/// Writes go to the underlying I/O directly.
+#[derive(Debug)]
+pub struct Rewind<T> {
+    prefix: Bytes,
+    io: T,
+}
+
 /// Accepts inbound HTTP/2 streams on a connection.
 ///
 /// A `Connection` is backed by an I/O resource (usually a TCP socket) and
@@ -258,6 +299,34 @@ pub struct Builder {
     ///
     /// When this gets exceeded, we issue GOAWAYs.
     local_max_error_reset_streams: Option<usize>,
+
+    /// Schedule sends by the RFC 7540 dependency tree.
+    rfc7540_priorities: bool,
+
+    /// Whether the receive windows grow to the estimated bandwidth-delay
+    /// product.
+    adaptive_window: bool,
+
+    /// Interval of keep-alive PINGs, if enabled.
+    keep_alive_interval: Option<Duration>,
+
+    /// Time to wait for a response to a keep-alive PING.
+    keep_alive_timeout: Duration,
+
+    /// Whether keep-alive PINGs are sent without open streams.
+    keep_alive_while_idle: bool,
+
+    /// Timer of the keep-alive PINGs, instead of the one of Tokio.
+    timer: Option<SharedTimer>,
+
+    /// Padding of the sent DATA, HEADERS and PUSH_PROMISE frames.
+    padding: Padding,
+
+    /// Creates the scheduler of sent frames of each connection.
+    scheduler: Option<NewScheduler>,
+
+    /// Handlers of received extension frames.
+    extension_frame_handlers: ExtensionFrameHandlers,
 }
 
 /// Send a response back to the client
@@ -369,6 +438,73 @@ where
     Builder::new().handshake(io)
 }
 
+/// Reads the start of a connection to detect whether the client sends the
+/// HTTP/2 connection preface, telling HTTP/2 with [prior knowledge] apart
+/// from HTTP/1 on the same port.
+///
+/// The bytes read are not lost: the returned [`Rewind`] replays them before
+/// reading from `io`, so it can be passed to [`handshake`] or to an HTTP/1
+/// server as is. The detection stops at the first byte that differs from the
+/// preface, and returns [`Detected::Http1`] if `io` is closed before the
+/// preface is complete.
+///
+/// [prior knowledge]: http://httpwg.org/specs/rfc7540.html#known-http
+///
+/// # Examples
+///
+/// ```
+/// # use tokio::io::{AsyncRead, AsyncWrite};
+/// # use h2::server::{self, Detected};
+/// #
+/// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T) -> Result<(), h2::Error> {
+/// let (detected, io) = server::detect_preface(my_io).await?;
+/// if detected == Detected::Http2 {
+///     let connection = server::handshake(io).await?;
+///     // Accept inbound HTTP/2 streams with `connection`.
+/// # drop(connection);
+/// } else {
+///     // Serve HTTP/1 over `io`.
+/// }
+/// # Ok(())
+/// # }
+/// #
+/// # pub fn main() {}
+/// ```
+pub async fn detect_preface<T>(mut io: T) -> Result<(Detected, Rewind<T>), crate::Error>
+where
+    T: AsyncRead + Unpin,
+{
+    use tokio::io::AsyncReadExt;
+
+    let mut buf = [0; PREFACE.len()];
+    let mut pos = 0;
+
+    let detected = loop {
+        if pos == PREFACE.len() {
+            break Detected::Http2;
+        }
+
+        let n = io
+            .read(&mut buf[pos..])
+            .await
+            .map_err(crate::Error::from_io)?;
+        if n == 0 || buf[pos..pos + n] != PREFACE[pos..pos + n] {
+            pos += n;
+            break Detected::Http1;
+        }
+
+        pos += n;
+    };
+
+    tracing::trace!("detect_preface; detected={:?}", detected);
+
+    let rewind = Rewind {
+        prefix: Bytes::copy_from_slice(&buf[..pos]),
+        io,
+    };
+    Ok((detected, rewind))
+}
+
 // ===== impl Connection =====
 
 impl<T, B> Connection<T, B>
@@ -553,6 +689,88 @@ where
         self.connection.take_user_pings().map(PingPong::new)
     }
 
+    /// Queues a frame of a type that is not defined by this crate, such as
+    /// the frames of protocol extensions, to be sent with the next poll of
+    /// the connection.
+    ///
+    /// A `stream_id` of zero sends a frame that applies to the connection.
+    /// The frame is sent as is, regardless of the state of the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `kind` is a frame type defined by this crate, if
+    /// `stream_id` has its reserved bit set, or if the payload is larger
+    /// than the maximum frame size of the peer.
+    pub fn send_extension_frame(
+        &mut self,
+        kind: u8,
+        flags: u8,
+        stream_id: u32,
+        payload: Bytes,
+    ) -> Result<(), crate::Error> {
+        let frame = proto::extension_frame(kind, flags, stream_id, payload)?;
+        self.connection.send_extension_frame(frame)?;
+        Ok(())
+    }
+
+    /// Queues an [`ALTSVC` frame][1] to be sent with the next poll of the
+    /// connection, advertising the alternative services listed by the
+    /// `Alt-Svc` field value `field_value` for `origin`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `origin` is empty or longer than 65535 octets, or
+    /// if the frame is larger than the maximum frame size of the client.
+    ///
+    /// [1]: https://www.rfc-editor.org/rfc/rfc7838.html#section-4
+    pub fn send_altsvc<O, V>(&mut self, origin: O, field_value: V) -> Result<(), crate::Error>
+    where
+        O: Into<Bytes>,
+        V: Into<Bytes>,
+    {
+        let origin = origin.into();
+
+        if origin.is_empty() || origin.len() > u16::MAX as usize {
+            return Err(UserError::InvalidOrigin.into());
+        }
+
+        let frame = frame::AltSvc::new(origin, field_value.into());
+        self.connection.send_extension_frame(frame)?;
+        Ok(())
+    }
+
+    /// Queues an [`ORIGIN` frame][1] to be sent with the next poll of the
+    /// connection, advertising the ASCII serializations of the origins the
+    /// server is authoritative for, such as `https://example.com`.
+    ///
+    /// Each frame adds its origins to the origin set advertised by the
+    /// previous ones; origins cannot be removed from the set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an origin is empty or longer than 65535 octets,
+    /// or if the frame is larger than the maximum frame size of the client.
+    ///
+    /// [1]: https://www.rfc-editor.org/rfc/rfc8336.html#section-2
+    pub fn send_origin<I>(&mut self, origins: I) -> Result<(), crate::Error>
+    where
+        I: IntoIterator,
+        I::Item: Into<Bytes>,
+    {
+        let origins: Vec<Bytes> = origins.into_iter().map(Into::into).collect();
+
+        if origins
+            .iter()
+            .any(|o| o.is_empty() || o.len() > u16::MAX as usize)
+        {
+            return Err(UserError::InvalidOrigin.into());
+        }
+
+        self.connection
+            .send_extension_frame(frame::Origin::new(origins))?;
+        Ok(())
+    }
+
     /// Checks if there are any streams
     pub fn has_streams(&self) -> bool {
         self.connection.has_streams()
@@ -586,6 +804,27 @@ where
         self.connection.max_recv_streams()
     }
 
+    /// Returns the [Akamai HTTP/2 fingerprint][1] of the client.
+    ///
+    /// The fingerprint has the format
+    /// `SETTINGS|WINDOW_UPDATE|PRIORITY|PSEUDO_HEADER_ORDER`, for example
+    /// `1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p`. It is computed
+    /// from the frames received on this connection up to and including the
+    /// first `HEADERS` frame, so this returns `None` until a request was
+    /// received.
+    ///
+    /// [1]: https://www.blackhat.com/docs/eu-17/materials/eu-17-Shuster-Passive-Fingerprinting-Of-HTTP2-Clients-wp.pdf
+    pub fn peer_fingerprint(&self) -> Option<String> {
+        self.connection.peer_fingerprint()
+    }
+
+    /// Returns a snapshot of the statistics of the connection.
+    ///
+    /// See the [`stats`](crate::stats) module for more details.
+    pub fn stats(&self) -> ConnectionStats {
+        self.connection.stats()
+    }
+
     // Could disappear at anytime.
     #[doc(hidden)]
     #[cfg(feature = "unstable")]
@@ -657,6 +896,15 @@ impl Builder {
             max_send_buffer_size: proto::DEFAULT_MAX_SEND_BUFFER_SIZE,
 
             local_max_error_reset_streams: Some(proto::DEFAULT_LOCAL_RESET_COUNT_MAX),
+            rfc7540_priorities: false,
+            adaptive_window: false,
+            keep_alive_interval: None,
+            keep_alive_timeout: Duration::from_secs(proto::DEFAULT_KEEP_ALIVE_TIMEOUT_SECS),
+            keep_alive_while_idle: false,
+            timer: None,
+            padding: Padding::default(),
+            scheduler: None,
+            extension_frame_handlers: ExtensionFrameHandlers::default(),
         }
     }
 
@@ -728,6 +976,173 @@ impl Builder {
         self
     }
 
+    /// Enables adaptive flow control, which grows the receive windows to the
+    /// bandwidth-delay product of the connection.
+    ///
+    /// The bandwidth-delay product is estimated by sending a `PING` frame
+    /// when `DATA` frames are received, and counting the data received until
+    /// it is acknowledged. When that data nears the estimate, the sender was
+    /// likely blocked on flow control, and both the connection window and the
+    /// initial window of streams are set to twice that data, up to 16MB.
+    ///
+    /// The windows start at the sizes set with [`initial_window_size`] and
+    /// [`initial_connection_window_size`], and are only grown.
+    ///
+    /// By default, adaptive flow control is disabled.
+    ///
+    /// [`initial_window_size`]: Builder::initial_window_size
+    /// [`initial_connection_window_size`]: Builder::initial_connection_window_size
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use h2::server::*;
+    /// #
+    /// # fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Handshake<T>
+    /// # {
+    /// // `server_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let server_fut = Builder::new()
+    ///     .adaptive_window(true)
+    ///     .handshake(my_io);
+    /// # server_fut
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn adaptive_window(&mut self, enabled: bool) -> &mut Self {
+        self.adaptive_window = enabled;
+        self
+    }
+
+    /// Enables keep-alive, sending a `PING` frame when nothing was received
+    /// from the peer for `interval`.
+    ///
+    /// If nothing is received within the [`keep_alive_timeout`] after the
+    /// `PING` is sent, a `GOAWAY` frame is sent if the connection is still
+    /// writable, and the connection fails with an error for which
+    /// [`Error::is_keep_alive_timeout`] returns `true`. This detects peers
+    /// that became unreachable without closing the connection.
+    ///
+    /// `PING` frames are only sent while there are open streams, unless
+    /// [`keep_alive_while_idle`] is enabled.
+    ///
+    /// The keep-alive timer is the one of Tokio, unless a [`timer`] is set.
+    ///
+    /// By default, keep-alive is disabled.
+    ///
+    /// [`keep_alive_timeout`]: Builder::keep_alive_timeout
+    /// [`keep_alive_while_idle`]: Builder::keep_alive_while_idle
+    /// [`Error::is_keep_alive_timeout`]: crate::Error::is_keep_alive_timeout
+    /// [`timer`]: Builder::timer
+    ///
+    /// # Panics
+    ///
+    /// Unless a [`timer`] is set, the handshake and the connection panic when
+    /// they are polled outside of a Tokio runtime with the time driver
+    /// enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use h2::server::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Handshake<T>
+    /// # {
+    /// // `server_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let server_fut = Builder::new()
+    ///     .keep_alive_interval(Duration::from_secs(30))
+    ///     .keep_alive_timeout(Duration::from_secs(10))
+    ///     .handshake(my_io);
+    /// # server_fut
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn keep_alive_interval(&mut self, interval: Duration) -> &mut Self {
+        self.keep_alive_interval = Some(interval);
+        self
+    }
+
+    /// Sets how long to wait for a frame in response to a keep-alive `PING`
+    /// before failing the connection.
+    ///
+    /// This has no effect unless [`keep_alive_interval`] is set.
+    ///
+    /// The default value is 20 seconds.
+    ///
+    /// [`keep_alive_interval`]: Builder::keep_alive_interval
+    pub fn keep_alive_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.keep_alive_timeout = timeout;
+        self
+    }
+
+    /// Sets whether keep-alive `PING` frames are sent while there are no
+    /// open streams.
+    ///
+    /// This has no effect unless [`keep_alive_interval`] is set.
+    ///
+    /// By default, `PING` frames are only sent while there are open streams.
+    ///
+    /// [`keep_alive_interval`]: Builder::keep_alive_interval
+    pub fn keep_alive_while_idle(&mut self, enabled: bool) -> &mut Self {
+        self.keep_alive_while_idle = enabled;
+        self
+    }
+
+    /// Sets the timer used by keep-alive.
+    ///
+    /// See the [`timer`] module for more details.
+    ///
+    /// By default, the timer of the current Tokio runtime is used.
+    ///
+    /// [`timer`]: crate::timer
+    pub fn timer<M: Timer>(&mut self, timer: M) -> &mut Self {
+        self.timer = Some(SharedTimer::new(timer));
+        self
+    }
+
+    /// Sets the padding policy of the `DATA`, `HEADERS` and `PUSH_PROMISE`
+    /// frames sent on connections.
+    ///
+    /// Streams can override the policy, see the [`padding`] module for
+    /// details.
+    ///
+    /// By default, frames are not padded.
+    ///
+    /// [`padding`]: crate::padding
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use h2::server::*;
+    /// # use h2::padding::Padding;
+    /// #
+    /// # fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T)
+    /// # -> Handshake<T>
+    /// # {
+    /// // `server_fut` is a future representing the completion of the HTTP/2
+    /// // handshake.
+    /// let server_fut = Builder::new()
+    ///     .padding(Padding::block(64))
+    ///     .handshake(my_io);
+    /// # server_fut
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn padding(&mut self, padding: Padding) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+
     /// Indicates the size (in octets) of the largest HTTP/2 frame payload that the
     /// configured server is able to accept.
     ///
@@ -1040,6 +1455,92 @@ impl Builder {
         self
     }
 
+    /// Sets the `SETTINGS_NO_RFC7540_PRIORITIES` setting.
+    ///
+    /// Setting it to `true` tells the client that the server ignores the
+    /// [RFC 7540] priority scheme, so that the client signals priorities
+    /// with the `priority` header and `PRIORITY_UPDATE` frames of
+    /// [RFC 9218] instead.
+    ///
+    /// By default, the setting is not sent.
+    ///
+    /// [RFC 7540]: https://httpwg.org/specs/rfc7540.html#StreamPriority
+    /// [RFC 9218]: https://www.rfc-editor.org/rfc/rfc9218.html
+    pub fn no_rfc7540_priorities(&mut self, enabled: bool) -> &mut Self {
+        self.settings.set_no_rfc7540_priorities(Some(enabled));
+        self
+    }
+
+    /// Enables scheduling of sent frames by [RFC 7540] priorities.
+    ///
+    /// When enabled, the server builds the stream dependency tree from the
+    /// `PRIORITY` frames and the priority information of `HEADERS` frames
+    /// sent by the client. A stream is then served before the streams that
+    /// depend on it, and sibling streams share the connection by weight.
+    ///
+    /// [RFC 9218] priorities signaled by the client, as well as priorities
+    /// set with [`SendResponse::set_priority`], are ignored in this mode.
+    ///
+    /// This is disabled by default, in which case `PRIORITY` frames are
+    /// ignored. It has no effect if a [`scheduler`] is set, which takes
+    /// precedence.
+    ///
+    /// [`scheduler`]: Builder::scheduler
+    /// [RFC 7540]: https://httpwg.org/specs/rfc7540.html#StreamPriority
+    /// [RFC 9218]: https://www.rfc-editor.org/rfc/rfc9218.html
+    pub fn rfc7540_priorities(&mut self, enabled: bool) -> &mut Self {
+        self.rfc7540_priorities = enabled;
+        self
+    }
+
+    /// Sets the strategy deciding which stream sends the next frame.
+    ///
+    /// `new_scheduler` is called to create the scheduler of each connection.
+    /// See the [`scheduler`] module for more details.
+    ///
+    /// By default, streams take turns following their [RFC 9218]
+    /// priorities, or their [RFC 7540] priorities if
+    /// [`rfc7540_priorities`] is enabled. A scheduler takes precedence over
+    /// both.
+    ///
+    /// [`scheduler`]: crate::scheduler
+    /// [`rfc7540_priorities`]: Builder::rfc7540_priorities
+    /// [RFC 7540]: https://httpwg.org/specs/rfc7540.html#StreamPriority
+    /// [RFC 9218]: https://www.rfc-editor.org/rfc/rfc9218.html
+    pub fn scheduler<F, S>(&mut self, new_scheduler: F) -> &mut Self
+    where
+        F: Fn() -> S + Send + Sync + 'static,
+        S: SendScheduler,
+    {
+        self.scheduler = Some(NewScheduler::new(new_scheduler));
+        self
+    }
+
+    /// Registers the handler of received frames of type `kind`, which is
+    /// not defined by this crate, such as the frames of protocol extensions.
+    ///
+    /// The handler is called with the frames that apply to the connection,
+    /// and with the frames received on streams that are not open. Frames
+    /// received on open streams are read with
+    /// [`RecvStream::poll_extension_frame`] instead. Frames of types without
+    /// a handler are ignored.
+    ///
+    /// The handler is called while the connection is polled, and should not
+    /// block.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `kind` is a frame type defined by this crate.
+    ///
+    /// [`RecvStream::poll_extension_frame`]: crate::RecvStream::poll_extension_frame
+    pub fn extension_frame_handler<F>(&mut self, kind: u8, handler: F) -> &mut Self
+    where
+        F: Fn(ExtensionFrame) + Send + Sync + 'static,
+    {
+        self.extension_frame_handlers.insert(kind, handler);
+        self
+    }
+
     /// Creates a new configured HTTP/2 server backed by `io`.
     ///
     /// It is expected that `io` already be in an appropriate state to commence
@@ -1103,6 +1604,59 @@ impl Builder {
     {
         Connection::handshake2(io, self.clone())
     }
+
+    /// Creates a new configured HTTP/2 server backed by `io`, a connection
+    /// upgraded from HTTP/1.1 (h2c).
+    ///
+    /// The client sent an HTTP/1.1 request with the `Upgrade: h2c` and
+    /// `HTTP2-Settings` headers, and the `101 Switching Protocols` response
+    /// must already have been written to `io`. `settings_header` is the value
+    /// of the `HTTP2-Settings` header, whose settings are applied as if the
+    /// client had sent them in a SETTINGS frame.
+    ///
+    /// The upgraded request is stream 1, which is half-closed as the request
+    /// was fully received. Along with the connection, the returned future
+    /// yields the [`SendResponse`] used to send its response over HTTP/2. The
+    /// connection does not accept the request of stream 1.
+    ///
+    /// The handshake fails with a `PROTOCOL_ERROR` if `settings_header` is not
+    /// a valid SETTINGS payload encoded in base64url.
+    ///
+    /// See [RFC 7540, section 3.2](https://httpwg.org/specs/rfc7540.html#discover-http)
+    /// for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tokio::io::{AsyncRead, AsyncWrite};
+    /// # use h2::server::*;
+    /// # use http::Response;
+    /// #
+    /// # async fn doc<T: AsyncRead + AsyncWrite + Unpin>(my_io: T, settings_header: &[u8])
+    /// # -> Result<(), h2::Error>
+    /// # {
+    /// // The `101 Switching Protocols` response was written to `my_io`.
+    /// let (connection, mut respond) = Builder::new()
+    ///     .handshake_upgrade::<_, bytes::Bytes>(my_io, settings_header)
+    ///     .await?;
+    /// respond.send_response(Response::new(()), true)?;
+    /// // Accept inbound HTTP/2 streams with `connection`.
+    /// # drop(connection);
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # pub fn main() {}
+    /// ```
+    pub fn handshake_upgrade<T, B>(&self, io: T, settings_header: &[u8]) -> HandshakeUpgrade<T, B>
+    where
+        T: AsyncRead + AsyncWrite + Unpin,
+        B: Buf,
+    {
+        HandshakeUpgrade {
+            handshake: Connection::handshake2(io, self.clone()),
+            settings: Settings::load_upgrade_header(settings_header),
+        }
+    }
 }
 
 impl Default for Builder {
@@ -1280,6 +1834,31 @@ impl<B: Buf> SendResponse<B> {
         self.inner.send_reset(reason)
     }
 
+    /// Sets the [RFC 9218] priority used to send the response.
+    ///
+    /// The priority starts out as the one signaled by the client with the
+    /// request's `priority` header, and `PRIORITY_UPDATE` frames received
+    /// from the client later replace it.
+    ///
+    /// [RFC 9218]: https://www.rfc-editor.org/rfc/rfc9218.html
+    pub fn set_priority(&mut self, priority: Priority) {
+        self.inner.set_priority(priority)
+    }
+
+    /// Sets the padding of the frames sent on the stream, the response
+    /// `HEADERS` and the `PUSH_PROMISE` frames included, overriding the
+    /// policy of the connection.
+    ///
+    /// The padding also applies to the [`SendStream`] returned by
+    /// `send_response`.
+    ///
+    /// See the [`padding`](crate::padding) module for details.
+    ///
+    /// [`SendStream`]: crate::SendStream
+    pub fn set_padding(&mut self, padding: Padding) {
+        self.inner.set_padding(padding)
+    }
+
     /// Polls to be notified when the client resets this stream.
     ///
     /// If stream is still open, this returns `Poll::Pending`, and
@@ -1504,7 +2083,22 @@ where
                             local_error_reset_streams_max: self
                                 .builder
                                 .local_max_error_reset_streams,
+                            pseudo_header_order: Default::default(),
+                            request_stream_dependency: None,
+                            rfc7540_priorities: self.builder.rfc7540_priorities,
+                            adaptive_window: self.builder.adaptive_window,
+                            keep_alive_interval: self.builder.keep_alive_interval,
+                            keep_alive_timeout: self.builder.keep_alive_timeout,
+                            keep_alive_while_idle: self.builder.keep_alive_while_idle,
+                            timer: self.builder.timer.clone(),
+                            padding: self.builder.padding,
+                            push_policy: None,
+                            max_concurrent_pushes_per_stream: None,
+                            push_cache_size: 0,
+                            scheduler: self.builder.scheduler.clone(),
+                            extension_frame_handlers: self.builder.extension_frame_handlers.clone(),
                             settings: self.builder.settings.clone(),
+                            preface: Vec::new(),
                         },
                     );
 
@@ -1534,6 +2128,115 @@ where
     }
 }
 
+// ===== impl HandshakeUpgrade =====
+
+impl<T, B> Future for HandshakeUpgrade<T, B>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+    B: Buf,
+{
+    type Output = Result<(Connection<T, B>, SendResponse<B>), crate::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+
+        let settings = match &this.settings {
+            Ok(settings) => settings,
+            Err(err) => {
+                proto_err!(conn: "handshake_upgrade: invalid HTTP2-Settings header; err={:?}", err);
+                return Poll::Ready(Err(Error::library_go_away(Reason::PROTOCOL_ERROR).into()));
+            }
+        };
+
+        let mut connection = ready!(Pin::new(&mut this.handshake).poll(cx))?;
+        let inner = connection.connection.recv_upgrade(settings)?;
+
+        tracing::trace!("connection upgraded; stream_id={:?}", inner.stream_id());
+        Poll::Ready(Ok((connection, SendResponse { inner })))
+    }
+}
+
+impl<T, B> fmt::Debug for HandshakeUpgrade<T, B>
+where
+    T: AsyncRead + AsyncWrite + fmt::Debug,
+    B: fmt::Debug + Buf,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "server::HandshakeUpgrade")
+    }
+}
+
+// ===== impl Rewind =====
+
+impl<T> Rewind<T> {
+    /// Returns a reference to the underlying I/O.
+    pub fn get_ref(&self) -> &T {
+        &self.io
+    }
+
+    /// Returns a mutable reference to the underlying I/O.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.io
+    }
+
+    /// Returns the bytes not replayed yet, and the underlying I/O.
+    pub fn into_parts(self) -> (Bytes, T) {
+        (self.prefix, self.io)
+    }
+}
+
+impl<T> AsyncRead for Rewind<T>
+where
+    T: AsyncRead + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        if self.prefix.is_empty() {
+            return Pin::new(&mut self.io).poll_read(cx, buf);
+        }
+
+        let n = std::cmp::min(self.prefix.len(), buf.remaining());
+        buf.put_slice(&self.prefix.split_to(n));
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T> AsyncWrite for Rewind<T>
+where
+    T: AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.io).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.io).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.io.is_write_vectored()
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_shutdown(cx)
+    }
+}
+
 impl Peer {
     pub fn convert_send_message(
         id: StreamId,
@@ -1,14 +1,18 @@
 use crate::codec::UserError;
+use crate::ext::{ExtensionFrame, Priority};
 use crate::frame::Reason;
+use crate::padding::Padding;
 use crate::proto::{self, WindowSize};
+use crate::stats::StreamStats;
 
 use bytes::{Buf, Bytes};
 use http::HeaderMap;
 
 use std::fmt;
-#[cfg(feature = "stream")]
+use std::io;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 
 /// Sends the body stream and trailers to the remote peer.
 ///
@@ -108,7 +112,7 @@ pub struct SendStream<B> {
 /// new stream.
 ///
 /// [Section 5.1.1]: https://tools.ietf.org/html/rfc7540#section-5.1.1
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct StreamId(u32);
 
 impl From<StreamId> for u32 {
@@ -196,6 +200,40 @@ pub struct FlowControl {
     inner: proto::OpaqueStreamRef,
 }
 
+/// Writes the body of a message to a [`SendStream`] as an [`AsyncWrite`].
+///
+/// Returned by [`SendStream::into_async_write`]. Each write waits for send
+/// capacity to be assigned to the stream, and sends as many bytes as the
+/// capacity allows; the connection splits them into `DATA` frames of the
+/// maximum frame size of the peer. Shutting the writer down ends the stream.
+///
+/// With the `stream` feature, the writer is also a `Sink` of [`Bytes`],
+/// which sends each item in as many writes as needed.
+///
+/// Dropping the writer without shutting it down leaves the stream open,
+/// and resets it once all its handles are dropped.
+#[derive(Debug)]
+pub struct SendStreamWriter {
+    inner: SendStream<Bytes>,
+    /// Data given to the `Sink` that was not sent yet.
+    pending: Bytes,
+    /// Whether `END_STREAM` was sent.
+    is_shutdown: bool,
+}
+
+/// Reads the body of a message from a [`RecvStream`] as an [`AsyncRead`].
+///
+/// Returned by [`RecvStream::into_async_read`]. The flow control capacity
+/// of the received data is released as it is read. Reading returns the end
+/// of the body once the stream ends, or is reset by the peer with
+/// `NO_ERROR`. Trailers are ignored.
+#[derive(Debug)]
+pub struct RecvStreamReader {
+    inner: RecvStream,
+    /// Received data that was not read yet.
+    buf: Bytes,
+}
+
 /// A handle to send and receive PING frames with the peer.
 // NOT Clone on purpose
 pub struct PingPong {
@@ -356,6 +394,29 @@ impl<B: Buf> SendStream<B> {
         self.inner.send_reset(reason)
     }
 
+    /// Sets the [RFC 9218] priority of the stream.
+    ///
+    /// The connection sends the frames of streams with a lower urgency
+    /// first. Streams of the same urgency that are not incremental are sent
+    /// one after the other, while incremental ones share the connection.
+    ///
+    /// On a client, the new priority is also sent to the server in a
+    /// `PRIORITY_UPDATE` frame. The initial priority of a request is given
+    /// by its `priority` header.
+    ///
+    /// [RFC 9218]: https://www.rfc-editor.org/rfc/rfc9218.html
+    pub fn set_priority(&mut self, priority: Priority) {
+        self.inner.set_priority(priority)
+    }
+
+    /// Sets the padding of the `DATA` and trailing `HEADERS` frames sent on
+    /// the stream, overriding the policy of the connection.
+    ///
+    /// See the [`padding`](crate::padding) module for details.
+    pub fn set_padding(&mut self, padding: Padding) {
+        self.inner.set_padding(padding)
+    }
+
     /// Polls to be notified when the client resets this stream.
     ///
     /// If stream is still open, this returns `Poll::Pending`, and
@@ -380,6 +441,29 @@ impl<B: Buf> SendStream<B> {
     pub fn stream_id(&self) -> StreamId {
         StreamId::from_internal(self.inner.stream_id())
     }
+
+    /// Returns a snapshot of the statistics of this stream.
+    ///
+    /// # Panics
+    ///
+    /// If the lock on the stream store has been poisoned.
+    pub fn stats(&self) -> StreamStats {
+        self.inner.stats()
+    }
+}
+
+impl SendStream<Bytes> {
+    /// Converts the stream into an [`AsyncWrite`], which takes care of send
+    /// capacity.
+    ///
+    /// See [`SendStreamWriter`] for details.
+    pub fn into_async_write(self) -> SendStreamWriter {
+        SendStreamWriter {
+            inner: self,
+            pending: Bytes::new(),
+            is_shutdown: false,
+        }
+    }
 }
 
 // ===== impl StreamId =====
@@ -435,6 +519,31 @@ impl RecvStream {
         }
     }
 
+    /// Get the next extension frame received on the stream.
+    ///
+    /// See [`poll_extension_frame`](RecvStream::poll_extension_frame).
+    pub async fn extension_frame(&mut self) -> Option<Result<ExtensionFrame, crate::Error>> {
+        crate::poll_fn(move |cx| self.poll_extension_frame(cx)).await
+    }
+
+    /// Poll for the next extension frame received on the stream.
+    ///
+    /// Only the frames of the types registered with
+    /// `extension_frame_handler` on the client or server builder are
+    /// received; up to 64 of them are buffered until they are polled, and
+    /// later ones are dropped. Returns `None` once the receive half of the
+    /// stream is closed and the buffered frames were polled.
+    pub fn poll_extension_frame(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<ExtensionFrame, crate::Error>>> {
+        self.inner
+            .inner
+            .poll_extension_frame(cx)
+            .map_ok(ExtensionFrame::new)
+            .map_err(Into::into)
+    }
+
     /// Returns true if the receive half has reached the end of stream.
     ///
     /// A return value of `true` means that calls to `poll` and `poll_trailers`
@@ -458,6 +567,26 @@ impl RecvStream {
     pub fn stream_id(&self) -> StreamId {
         self.inner.stream_id()
     }
+
+    /// Returns a snapshot of the statistics of this stream.
+    ///
+    /// # Panics
+    ///
+    /// If the lock on the stream store has been poisoned.
+    pub fn stats(&self) -> StreamStats {
+        self.inner.inner.stats()
+    }
+
+    /// Converts the stream into an [`AsyncRead`], which takes care of
+    /// releasing flow control capacity.
+    ///
+    /// See [`RecvStreamReader`] for details.
+    pub fn into_async_read(self) -> RecvStreamReader {
+        RecvStreamReader {
+            inner: self,
+            buf: Bytes::new(),
+        }
+    }
 }
 
 #[cfg(feature = "stream")]
@@ -488,6 +617,202 @@ impl Drop for RecvStream {
     }
 }
 
+// ===== impl SendStreamWriter =====
+
+impl SendStreamWriter {
+    /// Returns a reference to the underlying stream.
+    pub fn get_ref(&self) -> &SendStream<Bytes> {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying stream.
+    ///
+    /// Sending data directly on the stream may interleave it with the data
+    /// written with the writer.
+    pub fn get_mut(&mut self) -> &mut SendStream<Bytes> {
+        &mut self.inner
+    }
+
+    /// Polls for send capacity for up to `len` bytes, returning how many
+    /// bytes may be sent, or `None` if the stream cannot send anymore.
+    fn poll_send_capacity(
+        &mut self,
+        cx: &mut Context<'_>,
+        len: usize,
+    ) -> Poll<Option<Result<usize, crate::Error>>> {
+        self.inner.reserve_capacity(len);
+
+        // `poll_capacity` only completes when capacity is assigned, which
+        // may have happened before this call.
+        while self.inner.capacity() == 0 {
+            match ready!(self.inner.poll_capacity(cx)) {
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => return Poll::Ready(None),
+            }
+        }
+
+        Poll::Ready(Some(Ok(self.inner.capacity().min(len))))
+    }
+
+    /// Sends the data given to the `Sink`.
+    fn poll_send_pending(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), crate::Error>> {
+        while !self.pending.is_empty() {
+            let n = match ready!(self.poll_send_capacity(cx, self.pending.len())) {
+                Some(res) => res?,
+                None => return Poll::Ready(Err(UserError::InactiveStreamId.into())),
+            };
+
+            let data = self.pending.split_to(n);
+            self.inner.send_data(data, false)?;
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    fn send_eos(&mut self) -> Result<(), crate::Error> {
+        if !self.is_shutdown {
+            self.inner.send_data(Bytes::new(), true)?;
+            self.is_shutdown = true;
+        }
+
+        Ok(())
+    }
+}
+
+impl AsyncWrite for SendStreamWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.is_shutdown {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+
+        ready!(self.poll_send_pending(cx)).map_err(into_io_error)?;
+
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let n = match ready!(self.poll_send_capacity(cx, buf.len())) {
+            Some(res) => res.map_err(into_io_error)?,
+            None => return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into())),
+        };
+
+        self.inner
+            .send_data(Bytes::copy_from_slice(&buf[..n]), false)
+            .map_err(into_io_error)?;
+
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // Sent frames are flushed by the connection.
+        self.poll_send_pending(cx).map_err(into_io_error)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.poll_send_pending(cx)).map_err(into_io_error)?;
+        Poll::Ready(self.send_eos().map_err(into_io_error))
+    }
+}
+
+#[cfg(feature = "stream")]
+impl futures_sink::Sink<Bytes> for SendStreamWriter {
+    type Error = crate::Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.poll_send_pending(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Bytes) -> Result<(), Self::Error> {
+        assert!(
+            self.pending.is_empty(),
+            "start_send called before poll_ready"
+        );
+
+        if self.is_shutdown {
+            return Err(UserError::UnexpectedFrameType.into());
+        }
+
+        self.pending = item;
+        Ok(())
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.poll_send_pending(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        ready!(self.poll_send_pending(cx))?;
+        Poll::Ready(self.send_eos())
+    }
+}
+
+// ===== impl RecvStreamReader =====
+
+impl RecvStreamReader {
+    /// Returns a reference to the underlying stream.
+    pub fn get_ref(&self) -> &RecvStream {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying stream.
+    ///
+    /// Data received directly from the stream is not read by the reader,
+    /// and its capacity must be released by the caller.
+    pub fn get_mut(&mut self) -> &mut RecvStream {
+        &mut self.inner
+    }
+}
+
+impl AsyncRead for RecvStreamReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        while self.buf.is_empty() {
+            match ready!(self.inner.poll_data(cx)) {
+                Some(Ok(data)) => self.buf = data,
+                // A reset without error ends the stream.
+                Some(Err(e)) if e.reason() == Some(Reason::NO_ERROR) => return Poll::Ready(Ok(())),
+                Some(Err(e)) => return Poll::Ready(Err(into_io_error(e))),
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+
+        let n = buf.remaining().min(self.buf.len());
+        buf.put_slice(&self.buf[..n]);
+        self.buf.advance(n);
+
+        self.inner
+            .flow_control()
+            .release_capacity(n)
+            .map_err(into_io_error)?;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Converts an error of a stream into an `io::Error`, keeping the kind of
+/// I/O errors, and reporting resets as `ConnectionReset`.
+fn into_io_error(e: crate::Error) -> io::Error {
+    if e.is_io() {
+        return e.into_io().unwrap();
+    }
+
+    let kind = if e.is_reset() {
+        io::ErrorKind::ConnectionReset
+    } else {
+        io::ErrorKind::Other
+    };
+
+    io::Error::new(kind, e)
+}
+
 // ===== impl FlowControl =====
 
 impl FlowControl {
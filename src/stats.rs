@@ -0,0 +1,293 @@
+//! Statistics of connections and streams.
+//!
+//! [`client::Connection::stats`] and [`server::Connection::stats`] return a
+//! [`ConnectionStats`] snapshot of the counters of a connection, while
+//! [`SendStream::stats`] and [`RecvStream::stats`] return a [`StreamStats`]
+//! snapshot of a stream. Snapshots are not updated as the connection makes
+//! progress; call the method again for fresh values.
+//!
+//! [`client::Connection::stats`]: crate::client::Connection::stats
+//! [`server::Connection::stats`]: crate::server::Connection::stats
+//! [`SendStream::stats`]: crate::SendStream::stats
+//! [`RecvStream::stats`]: crate::RecvStream::stats
+
+use crate::frame::{self, Reason};
+
+use std::time::Duration;
+
+/// A snapshot of the statistics of a connection.
+#[derive(Clone, Debug, Default)]
+pub struct ConnectionStats {
+    pub(crate) sent: TrafficStats,
+    pub(crate) received: TrafficStats,
+    pub(crate) hpack_compression_ratio: Option<f64>,
+    pub(crate) send_window: u32,
+    pub(crate) recv_window: u32,
+    pub(crate) peak_concurrent_streams: usize,
+    pub(crate) ping_rtt: Option<Duration>,
+}
+
+/// Counters of the frames sent, or received, on a connection.
+#[derive(Clone, Debug, Default)]
+pub struct TrafficStats {
+    frames: FrameCounts,
+    bytes: u64,
+    resets: Vec<(Reason, u64)>,
+}
+
+/// Numbers of frames, by frame type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameCounts {
+    data: u64,
+    headers: u64,
+    priority: u64,
+    reset: u64,
+    settings: u64,
+    push_promise: u64,
+    ping: u64,
+    go_away: u64,
+    window_update: u64,
+    continuation: u64,
+    altsvc: u64,
+    origin: u64,
+    priority_update: u64,
+    unknown: u64,
+}
+
+/// A snapshot of the statistics of a stream.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StreamStats {
+    pub(crate) bytes_sent: u64,
+    pub(crate) bytes_received: u64,
+    pub(crate) send_window: u32,
+    pub(crate) recv_window: u32,
+}
+
+// ===== impl ConnectionStats =====
+
+impl ConnectionStats {
+    /// Returns the counters of the frames sent to the peer.
+    pub fn sent(&self) -> &TrafficStats {
+        &self.sent
+    }
+
+    /// Returns the counters of the frames received from the peer.
+    pub fn received(&self) -> &TrafficStats {
+        &self.received
+    }
+
+    /// Returns the size of the header blocks sent, divided by the size of
+    /// the header fields they encode, or `None` if no header was sent yet.
+    ///
+    /// The lower the ratio, the better HPACK compressed the headers.
+    pub fn hpack_compression_ratio(&self) -> Option<f64> {
+        self.hpack_compression_ratio
+    }
+
+    /// Returns the connection-level window available to send data, in
+    /// octets.
+    pub fn send_window(&self) -> u32 {
+        self.send_window
+    }
+
+    /// Returns the connection-level window granted to the peer to send
+    /// data, in octets.
+    pub fn recv_window(&self) -> u32 {
+        self.recv_window
+    }
+
+    /// Returns the highest number of streams that were open at the same
+    /// time, counting the streams initiated by both peers.
+    pub fn peak_concurrent_streams(&self) -> usize {
+        self.peak_concurrent_streams
+    }
+
+    /// Returns the round-trip time of the last `PING` sent by this
+    /// connection and acknowledged by the peer, if any.
+    pub fn ping_rtt(&self) -> Option<Duration> {
+        self.ping_rtt
+    }
+}
+
+// ===== impl TrafficStats =====
+
+impl TrafficStats {
+    /// Returns the numbers of frames, by frame type.
+    pub fn frames(&self) -> &FrameCounts {
+        &self.frames
+    }
+
+    /// Returns the number of octets of the frames, headers included.
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Returns the number of `RST_STREAM` frames with the error code
+    /// `reason`.
+    pub fn resets(&self, reason: Reason) -> u64 {
+        self.resets
+            .iter()
+            .find(|&&(r, _)| r == reason)
+            .map_or(0, |&(_, count)| count)
+    }
+
+    /// Returns the numbers of `RST_STREAM` frames, by error code.
+    pub fn resets_by_reason(&self) -> impl Iterator<Item = (Reason, u64)> + '_ {
+        self.resets.iter().copied()
+    }
+
+    pub(crate) fn record_frame(&mut self, kind: frame::Kind) {
+        self.frames.record(kind);
+    }
+
+    pub(crate) fn record_reset(&mut self, reason: Reason) {
+        match self.resets.iter_mut().find(|&&mut (r, _)| r == reason) {
+            Some(&mut (_, ref mut count)) => *count += 1,
+            None => self.resets.push((reason, 1)),
+        }
+    }
+
+    pub(crate) fn record_bytes(&mut self, len: usize) {
+        self.bytes += len as u64;
+    }
+}
+
+// ===== impl FrameCounts =====
+
+impl FrameCounts {
+    /// Returns the number of `DATA` frames.
+    pub fn data(&self) -> u64 {
+        self.data
+    }
+
+    /// Returns the number of `HEADERS` frames.
+    pub fn headers(&self) -> u64 {
+        self.headers
+    }
+
+    /// Returns the number of `PRIORITY` frames.
+    pub fn priority(&self) -> u64 {
+        self.priority
+    }
+
+    /// Returns the number of `RST_STREAM` frames.
+    pub fn reset(&self) -> u64 {
+        self.reset
+    }
+
+    /// Returns the number of `SETTINGS` frames, acknowledgements included.
+    pub fn settings(&self) -> u64 {
+        self.settings
+    }
+
+    /// Returns the number of `PUSH_PROMISE` frames.
+    pub fn push_promise(&self) -> u64 {
+        self.push_promise
+    }
+
+    /// Returns the number of `PING` frames, acknowledgements included.
+    pub fn ping(&self) -> u64 {
+        self.ping
+    }
+
+    /// Returns the number of `GOAWAY` frames.
+    pub fn go_away(&self) -> u64 {
+        self.go_away
+    }
+
+    /// Returns the number of `WINDOW_UPDATE` frames.
+    pub fn window_update(&self) -> u64 {
+        self.window_update
+    }
+
+    /// Returns the number of `CONTINUATION` frames.
+    pub fn continuation(&self) -> u64 {
+        self.continuation
+    }
+
+    /// Returns the number of `ALTSVC` frames.
+    pub fn altsvc(&self) -> u64 {
+        self.altsvc
+    }
+
+    /// Returns the number of `ORIGIN` frames.
+    pub fn origin(&self) -> u64 {
+        self.origin
+    }
+
+    /// Returns the number of `PRIORITY_UPDATE` frames.
+    pub fn priority_update(&self) -> u64 {
+        self.priority_update
+    }
+
+    /// Returns the number of frames of types not defined by this crate.
+    pub fn unknown(&self) -> u64 {
+        self.unknown
+    }
+
+    /// Returns the total number of frames.
+    pub fn total(&self) -> u64 {
+        self.data
+            + self.headers
+            + self.priority
+            + self.reset
+            + self.settings
+            + self.push_promise
+            + self.ping
+            + self.go_away
+            + self.window_update
+            + self.continuation
+            + self.altsvc
+            + self.origin
+            + self.priority_update
+            + self.unknown
+    }
+
+    fn record(&mut self, kind: frame::Kind) {
+        use crate::frame::Kind::*;
+
+        let count = match kind {
+            Data => &mut self.data,
+            Headers => &mut self.headers,
+            Priority => &mut self.priority,
+            Reset => &mut self.reset,
+            Settings => &mut self.settings,
+            PushPromise => &mut self.push_promise,
+            Ping => &mut self.ping,
+            GoAway => &mut self.go_away,
+            WindowUpdate => &mut self.window_update,
+            Continuation => &mut self.continuation,
+            AltSvc => &mut self.altsvc,
+            Origin => &mut self.origin,
+            PriorityUpdate => &mut self.priority_update,
+            Unknown => &mut self.unknown,
+        };
+        *count += 1;
+    }
+}
+
+// ===== impl StreamStats =====
+
+impl StreamStats {
+    /// Returns the number of octets of `DATA` frames sent on the stream.
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+
+    /// Returns the number of octets of `DATA` frames received on the stream,
+    /// padding included.
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received
+    }
+
+    /// Returns the stream-level window available to send data, in octets.
+    pub fn send_window(&self) -> u32 {
+        self.send_window
+    }
+
+    /// Returns the stream-level window granted to the peer to send data, in
+    /// octets.
+    pub fn recv_window(&self) -> u32 {
+        self.recv_window
+    }
+}
@@ -0,0 +1,92 @@
+//! Pluggable timers.
+//!
+//! Keep-alive, enabled with [`client::Builder::keep_alive_interval`] or
+//! [`server::Builder::keep_alive_interval`], needs a timer to know when to
+//! send a `PING` frame and when the peer failed to respond to it. By default,
+//! the timer of Tokio is used, which panics outside of a Tokio runtime with
+//! the time driver enabled. A [`Timer`] replaces it, and is installed with
+//! [`client::Builder::timer`] or [`server::Builder::timer`].
+//!
+//! # Examples
+//!
+//! A timer that always uses the time driver of a given Tokio runtime, even
+//! when the connection is polled outside of it:
+//!
+//! ```
+//! use h2::timer::{Sleep, Timer};
+//! use std::pin::Pin;
+//! use std::time::Instant;
+//! use tokio::runtime::Handle;
+//!
+//! struct RuntimeTimer(Handle);
+//!
+//! impl Timer for RuntimeTimer {
+//!     fn sleep_until(&self, deadline: Instant) -> Pin<Box<dyn Sleep>> {
+//!         let _guard = self.0.enter();
+//!         Box::pin(tokio::time::sleep_until(deadline.into()))
+//!     }
+//! }
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let mut builder = h2::client::Builder::new();
+//! builder.timer(RuntimeTimer(Handle::current()));
+//! # }
+//! ```
+//!
+//! [`client::Builder::keep_alive_interval`]: crate::client::Builder::keep_alive_interval
+//! [`server::Builder::keep_alive_interval`]: crate::server::Builder::keep_alive_interval
+//! [`client::Builder::timer`]: crate::client::Builder::timer
+//! [`server::Builder::timer`]: crate::server::Builder::timer
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Creates the futures that a connection waits on to act at a later time.
+pub trait Timer: Send + Sync + 'static {
+    /// Returns a future that completes once `deadline` is reached.
+    fn sleep_until(&self, deadline: Instant) -> Pin<Box<dyn Sleep>>;
+}
+
+/// A future returned by a [`Timer`], completing at its deadline.
+pub trait Sleep: Future<Output = ()> + Send + Sync {}
+
+impl<F> Sleep for F where F: Future<Output = ()> + Send + Sync {}
+
+/// The timer of the connections of a builder.
+#[derive(Clone)]
+pub(crate) struct SharedTimer(Arc<dyn Timer>);
+
+/// Uses the timer of the current Tokio runtime.
+struct TokioTimer;
+
+impl SharedTimer {
+    pub(crate) fn new<M: Timer>(timer: M) -> Self {
+        SharedTimer(Arc::new(timer))
+    }
+
+    pub(crate) fn sleep_until(&self, deadline: Instant) -> Pin<Box<dyn Sleep>> {
+        self.0.sleep_until(deadline)
+    }
+}
+
+impl Default for SharedTimer {
+    fn default() -> Self {
+        SharedTimer::new(TokioTimer)
+    }
+}
+
+impl fmt::Debug for SharedTimer {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("SharedTimer").finish()
+    }
+}
+
+impl Timer for TokioTimer {
+    fn sleep_until(&self, deadline: Instant) -> Pin<Box<dyn Sleep>> {
+        Box::pin(tokio::time::sleep_until(deadline.into()))
+    }
+}
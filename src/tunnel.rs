@@ -0,0 +1,144 @@
+//! Byte stream tunnels over HTTP/2 streams.
+//!
+//! A [`Tunnel`] reads the `DATA` frames received on a stream, and writes
+//! `DATA` frames on it, as an [`AsyncRead`] and [`AsyncWrite`] byte stream.
+//! It is how the [`CONNECT`] method and the extended CONNECT of [RFC 8441]
+//! carry TCP connections, WebSockets or MASQUE proxies over HTTP/2, and can
+//! be used with any I/O utility, such as [`tokio::io::copy_bidirectional`].
+//!
+//! The tunnel combines a [`SendStreamWriter`] and a [`RecvStreamReader`],
+//! which handle flow control: received data is released as it is read, and
+//! written data is sent as the peer grants capacity. Shutting the tunnel
+//! down ends the stream with `END_STREAM`, while dropping it before both
+//! directions are finished resets the stream with `CANCEL`.
+//!
+//! # Examples
+//!
+//! A server accepting a WebSocket, with the extended CONNECT protocol
+//! enabled by [`server::Builder::enable_connect_protocol`]:
+//!
+//! ```
+//! use h2::ext::Protocol;
+//! use h2::server::SendResponse;
+//! use h2::tunnel::Tunnel;
+//! use h2::RecvStream;
+//! use bytes::Bytes;
+//! use http::{Method, Request, Response, StatusCode};
+//!
+//! # async fn doc(request: Request<RecvStream>, mut respond: SendResponse<Bytes>) {
+//! let is_websocket = request.method() == Method::CONNECT
+//!     && request.extensions().get::<Protocol>().map(Protocol::as_str) == Some("websocket");
+//!
+//! if !is_websocket {
+//!     let response = Response::builder()
+//!         .status(StatusCode::BAD_REQUEST)
+//!         .body(())
+//!         .unwrap();
+//!     let _ = respond.send_response(response, true);
+//!     return;
+//! }
+//!
+//! let tunnel = Tunnel::accept(request, respond, Response::new(())).unwrap();
+//! // `tunnel` carries the frames of the WebSocket.
+//! # }
+//! ```
+//!
+//! [`CONNECT`]: https://httpwg.org/specs/rfc9113.html#CONNECT
+//! [RFC 8441]: https://www.rfc-editor.org/rfc/rfc8441.html
+//! [`tokio::io::copy_bidirectional`]: https://docs.rs/tokio/1/tokio/io/fn.copy_bidirectional.html
+//! [`server::Builder::enable_connect_protocol`]: crate::server::Builder::enable_connect_protocol
+
+use crate::client::SendRequest;
+use crate::server::SendResponse;
+use crate::{Reason, RecvStream, RecvStreamReader, SendStream, SendStreamWriter, StreamId};
+
+use bytes::Bytes;
+use http::{Request, Response};
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// A byte stream over the `DATA` frames of an HTTP/2 stream.
+///
+/// See the [module documentation](self) for details.
+#[derive(Debug)]
+pub struct Tunnel {
+    writer: SendStreamWriter,
+    reader: RecvStreamReader,
+}
+
+impl Tunnel {
+    /// Creates a tunnel over the send and receive halves of a stream.
+    pub fn new(send: SendStream<Bytes>, recv: RecvStream) -> Tunnel {
+        Tunnel {
+            writer: send.into_async_write(),
+            reader: recv.into_async_read(),
+        }
+    }
+
+    /// Sends a `CONNECT` request, and returns the response with a tunnel
+    /// over the stream of the request.
+    ///
+    /// The tunnel is established only if the response status is `2xx`;
+    /// otherwise it reads the body of the response, and should be dropped
+    /// once done.
+    pub async fn connect(
+        send_request: &mut SendRequest<Bytes>,
+        request: Request<()>,
+    ) -> Result<(Response<()>, Tunnel), crate::Error> {
+        crate::poll_fn(|cx| send_request.poll_ready(cx)).await?;
+        let (response, send) = send_request.send_request(request, false)?;
+        let (parts, recv) = response.await?.into_parts();
+        Ok((Response::from_parts(parts, ()), Tunnel::new(send, recv)))
+    }
+
+    /// Sends the response to a `CONNECT` request, which should have a `2xx`
+    /// status, and returns a tunnel over the stream of the request.
+    pub fn accept(
+        request: Request<RecvStream>,
+        mut respond: SendResponse<Bytes>,
+        response: Response<()>,
+    ) -> Result<Tunnel, crate::Error> {
+        let send = respond.send_response(response, false)?;
+        Ok(Tunnel::new(send, request.into_body()))
+    }
+
+    /// Returns the stream ID of the tunnel.
+    pub fn stream_id(&self) -> StreamId {
+        self.writer.get_ref().stream_id()
+    }
+
+    /// Resets the stream of the tunnel with `reason`.
+    pub fn send_reset(&mut self, reason: Reason) {
+        self.writer.get_mut().send_reset(reason)
+    }
+}
+
+impl AsyncRead for Tunnel {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.reader).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for Tunnel {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.writer).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.writer).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.writer).poll_shutdown(cx)
+    }
+}
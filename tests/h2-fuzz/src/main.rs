@@ -28,6 +28,7 @@ impl<'a> MockIo<'a> {
 }
 
 impl<'a> AsyncRead for MockIo<'a> {
+    #[allow(clippy::waker_clone_wake)]
     fn poll_read(
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
@@ -55,6 +56,7 @@ impl<'a> AsyncRead for MockIo<'a> {
 }
 
 impl<'a> AsyncWrite for MockIo<'a> {
+    #[allow(clippy::waker_clone_wake)]
     fn poll_write(
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
@@ -51,6 +51,51 @@ where
     frame::WindowUpdate::new(id.into(), sz)
 }
 
+pub fn priority<T>(id: T, dependency_id: T, weight: u8, is_exclusive: bool) -> frame::Priority
+where
+    T: Into<StreamId>,
+{
+    let dependency = frame::StreamDependency::new(dependency_id.into(), weight, is_exclusive);
+    frame::Priority::new(id.into(), dependency)
+}
+
+pub fn priority_update<T>(id: T, value: &'static str) -> frame::PriorityUpdate
+where
+    T: Into<StreamId>,
+{
+    frame::PriorityUpdate::new(id.into(), Bytes::from_static(value.as_bytes()))
+}
+
+pub fn altsvc<T>(id: T, origin: &'static str, value: &'static str) -> frame::AltSvc
+where
+    T: Into<StreamId>,
+{
+    let id = id.into();
+    let value = Bytes::from_static(value.as_bytes());
+
+    if id.is_zero() {
+        frame::AltSvc::new(Bytes::from_static(origin.as_bytes()), value)
+    } else {
+        frame::AltSvc::for_stream(id, value)
+    }
+}
+
+pub fn origin(origins: &[&'static str]) -> frame::Origin {
+    frame::Origin::new(
+        origins
+            .iter()
+            .map(|o| Bytes::from_static(o.as_bytes()))
+            .collect(),
+    )
+}
+
+pub fn extension<T>(id: T, kind: u8, flags: u8, payload: &'static [u8]) -> frame::Extension
+where
+    T: Into<StreamId>,
+{
+    frame::Extension::new(kind, flags, id.into(), Bytes::from_static(payload))
+}
+
 pub fn go_away<T>(id: T) -> Mock<frame::GoAway>
 where
     T: Into<StreamId>,
@@ -191,6 +236,21 @@ impl Mock<frame::Headers> {
         self
     }
 
+    pub fn pseudo_order(mut self, order: h2::ext::PseudoHeaderOrder) -> Self {
+        self.0.pseudo_mut().set_order(order);
+        self
+    }
+
+    /// `weight` is the wire value, i.e. the actual weight minus one.
+    pub fn priority(mut self, dependency_id: u32, weight: u8, is_exclusive: bool) -> Self {
+        self.0.set_stream_dependency(frame::StreamDependency::new(
+            dependency_id.into(),
+            weight,
+            is_exclusive,
+        ));
+        self
+    }
+
     pub fn into_fields(self) -> HeaderMap {
         self.0.into_parts().1
     }
@@ -218,6 +278,11 @@ impl Mock<frame::Data> {
         self
     }
 
+    pub fn pad_len(mut self, pad_len: u8) -> Self {
+        self.0.set_pad_len(pad_len);
+        self
+    }
+
     pub fn eos(mut self) -> Self {
         self.0.set_end_stream(true);
         self
@@ -390,6 +455,11 @@ impl Mock<frame::Settings> {
         self
     }
 
+    pub fn no_rfc7540_priorities(mut self, val: bool) -> Self {
+        self.0.set_no_rfc7540_priorities(Some(val));
+        self
+    }
+
     pub fn header_table_size(mut self, val: u32) -> Self {
         self.0.set_header_table_size(Some(val));
         self
@@ -1,3 +1,5 @@
+#![allow(clippy::legacy_numeric_constants)]
+
 use crate::SendFrame;
 
 use h2::frame::{self, Frame};
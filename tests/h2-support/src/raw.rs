@@ -33,12 +33,14 @@ impl Chunk for u8 {
     }
 }
 
+#[allow(clippy::needless_lifetimes)]
 impl<'a> Chunk for &'a [u8] {
     fn push(&self, dst: &mut Vec<u8>) {
         dst.extend(*self)
     }
 }
 
+#[allow(clippy::needless_lifetimes)]
 impl<'a> Chunk for &'a str {
     fn push(&self, dst: &mut Vec<u8>) {
         dst.extend(self.as_bytes())
@@ -16,6 +16,7 @@ pub async fn concat(mut body: h2::RecvStream) -> Result<Bytes, h2::Error> {
     Ok(vec.into())
 }
 
+#[allow(clippy::waker_clone_wake)]
 pub async fn yield_once() {
     let mut yielded = false;
     futures::future::poll_fn(move |cx| {
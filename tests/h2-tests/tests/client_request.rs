@@ -1,7 +1,13 @@
 use futures::future::{ready, Either};
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
+use h2::ext::{
+    HeaderIndexing, HeaderIndexingOverride, HpackIndexing, OrderedHeaders, StreamDependency,
+};
+use h2::profiles::Profile;
 use h2_support::prelude::*;
+use http::header::{ACCEPT, AUTHORIZATION, COOKIE};
+use http::HeaderValue;
 use std::pin::Pin;
 use std::task::Context;
 use std::{io, panic};
@@ -96,6 +102,7 @@ async fn recv_invalid_server_stream_id() {
 }
 
 #[tokio::test]
+#[allow(clippy::legacy_numeric_constants)]
 async fn request_stream_id_overflows() {
     h2_support::trace_init!();
     let (io, mut srv) = mock::new();
@@ -1956,6 +1963,7 @@ async fn server_drop_connection_unexpectedly_return_unexpected_eof_err() {
 }
 
 #[tokio::test]
+#[allow(clippy::let_unit_value)]
 async fn server_drop_connection_after_go_away() {
     h2_support::trace_init!();
     let (io, mut srv) = mock::new();
@@ -1994,6 +2002,7 @@ async fn server_drop_connection_after_go_away() {
 }
 
 #[tokio::test]
+#[allow(clippy::bool_comparison)]
 async fn reset_before_headers_reaches_peer_without_headers() {
     // Repro: body future errors immediately and hyper/h2 converts that into a
     // RST_STREAM before the queued HEADERS are ever written, so the peer sees
@@ -2059,6 +2068,407 @@ async fn reset_before_headers_reaches_peer_without_headers() {
     join(srv, client).await;
 }
 
+#[tokio::test]
+async fn initial_settings_are_sent_as_configured() {
+    h2_support::trace_init!();
+
+    let mock = mock_io::Builder::new()
+        .write(b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n")
+        // Settings frame, in the configured order, including a duplicate and
+        // an identifier unknown to h2
+        .write(&[
+            0, 0, 24, 4, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 4, 0, 0x60, 0, 0, 0, 9, 0, 0, 0, 1, 0,
+            2, 0, 0, 0, 0,
+        ])
+        .read(SETTINGS)
+        .read(SETTINGS_ACK)
+        .write(SETTINGS_ACK)
+        .build();
+
+    let (_client, h2) = client::Builder::new()
+        // ignored in favor of the explicit list
+        .max_concurrent_streams(100)
+        .initial_settings([(0x2, 0), (0x4, 6_291_456), (0x9, 1), (0x2, 0)])
+        .handshake::<_, Bytes>(mock)
+        .await
+        .unwrap();
+
+    h2.await.unwrap();
+}
+
+#[tokio::test]
+async fn invalid_initial_settings_fail_handshake() {
+    h2_support::trace_init!();
+
+    // Nothing is written to the connection.
+    let mock = mock_io::Builder::new().build();
+
+    let err = client::Builder::new()
+        .initial_settings([(0x2, 2)])
+        .handshake::<_, Bytes>(mock)
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "user error: invalid SETTINGS value");
+}
+
+#[tokio::test]
+async fn invalid_preface_window_update_fails_handshake() {
+    h2_support::trace_init!();
+
+    // The connection window starts at 65,535 and cannot exceed 2^31 - 1.
+    for increments in [&[0][..], &[1 << 30, (1 << 30) - 65_535]] {
+        let mock = mock_io::Builder::new().build();
+
+        let mut builder = client::Builder::new();
+        for &increment in increments {
+            builder.preface_window_update(increment);
+        }
+        let err = builder.handshake::<_, Bytes>(mock).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "user error: invalid WINDOW_UPDATE increment"
+        );
+    }
+}
+
+#[tokio::test]
+async fn preface_frames_are_sent_after_settings() {
+    h2_support::trace_init!();
+
+    let mock = mock_io::Builder::new()
+        .handshake()
+        // Connection WINDOW_UPDATE
+        .write(&[0, 0, 4, 8, 0, 0, 0, 0, 0, 0, 0xEF, 0, 1])
+        // PRIORITY stream 3, depends on 0, weight 201
+        .write(&[0, 0, 5, 2, 0, 0, 0, 0, 3, 0, 0, 0, 0, 200])
+        // PRIORITY stream 5, depends exclusively on 3, weight 256
+        .write(&[0, 0, 5, 2, 0, 0, 0, 0, 5, 0x80, 0, 0, 3, 255])
+        .write(SETTINGS_ACK)
+        .build();
+
+    let (_client, h2) = client::Builder::new()
+        .preface_window_update(15_663_105)
+        .preface_priority(3, StreamDependency::new(0, 201, false))
+        .preface_priority(5, StreamDependency::new(3, 256, true))
+        .handshake::<_, Bytes>(mock)
+        .await
+        .unwrap();
+
+    h2.await.unwrap();
+}
+
+#[tokio::test]
+async fn request_headers_carry_stream_dependency() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://example.com/")
+                .priority(0, 255, true)
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::handshake(io).await.expect("handshake");
+
+        let mut request = Request::builder()
+            .uri("https://example.com/")
+            .body(())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(StreamDependency::new(0, 256, true));
+
+        let (response, _) = client.send_request(request, true).unwrap();
+        h2.drive(response).await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn request_depending_on_itself_is_rejected() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+    };
+
+    let h2 = async move {
+        let (mut client, h2) = client::handshake(io).await.expect("handshake");
+
+        let mut request = Request::builder()
+            .uri("https://example.com/")
+            .body(())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(StreamDependency::new(1, 16, false));
+
+        let err = client.send_request(request, true).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "user error: stream cannot depend on itself"
+        );
+
+        let _: () = h2.await.expect("h2");
+        drop(client);
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn profile_configures_connection_preface() {
+    h2_support::trace_init!();
+
+    let mock = mock_io::Builder::new()
+        .write(b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n")
+        .write(&[
+            0, 0, 24, 4, 0, 0, 0, 0, 0, // SETTINGS head
+            0, 1, 0, 1, 0, 0, // HEADER_TABLE_SIZE 65536
+            0, 2, 0, 0, 0, 0, // ENABLE_PUSH 0
+            0, 4, 0, 0x60, 0, 0, // INITIAL_WINDOW_SIZE 6291456
+            0, 6, 0, 4, 0, 0, // MAX_HEADER_LIST_SIZE 262144
+        ])
+        .write(&[0, 0, 4, 8, 0, 0, 0, 0, 0, 0, 0xEF, 0, 1])
+        .read(SETTINGS)
+        .read(SETTINGS_ACK)
+        .write(SETTINGS_ACK)
+        .build();
+
+    let (_client, h2) = client::Builder::new()
+        .profile(Profile::Chrome131)
+        .handshake::<_, Bytes>(mock)
+        .await
+        .unwrap();
+
+    h2.await.unwrap();
+}
+
+#[tokio::test]
+async fn profile_settings_ignore_later_setting_methods() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        srv.send_frame(frames::settings()).await;
+        srv.read_preface().await.unwrap();
+        let settings = assert_settings!(srv.next().await.unwrap().unwrap());
+        assert_eq!(settings.initial_window_size(), Some(6_291_456));
+        assert_eq!(settings.max_concurrent_streams(), None);
+        srv.recv_frame(frames::window_update(0, 15_663_105)).await;
+        srv.send_frame(frames::settings_ack()).await;
+        srv.recv_frame(frames::settings_ack()).await;
+    };
+
+    let h2 = async move {
+        let (_client, h2) = client::Builder::new()
+            .profile(Profile::Chrome131)
+            .initial_window_size(1_000)
+            .max_concurrent_streams(10)
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+
+        h2.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn profile_sets_request_stream_dependency() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        srv.send_frame(frames::settings()).await;
+        srv.read_preface().await.unwrap();
+        let settings = assert_settings!(srv.next().await.unwrap().unwrap());
+        assert_eq!(settings.initial_window_size(), Some(131_072));
+        srv.recv_frame(frames::window_update(0, 12_517_377)).await;
+        srv.send_frame(frames::settings_ack()).await;
+        srv.recv_frame(frames::settings_ack()).await;
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://example.com/")
+                .pseudo_order(Profile::Firefox133.pseudo_header_order())
+                .priority(0, 41, false)
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::Builder::new()
+            .profile(Profile::Firefox133)
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+
+        let request = Request::builder()
+            .uri("https://example.com/")
+            .body(())
+            .unwrap();
+
+        let (response, _) = client.send_request(request, true).unwrap();
+        h2.drive(response).await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn local_fingerprint_matches_profile() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        srv.send_frame(frames::settings()).await;
+        srv.read_preface().await.unwrap();
+        assert_settings!(srv.next().await.unwrap().unwrap());
+        srv.recv_frame(frames::window_update(0, 15_663_105)).await;
+        srv.send_frame(frames::settings_ack()).await;
+        srv.recv_frame(frames::settings_ack()).await;
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://example.com/")
+                .pseudo_order(Profile::Chrome131.pseudo_header_order())
+                .priority(0, 255, true)
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::Builder::new()
+            .profile(Profile::Chrome131)
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+
+        let request = Request::builder()
+            .uri("https://example.com/")
+            .body(())
+            .unwrap();
+
+        let (response, _) = client.send_request(request, true).unwrap();
+        h2.drive(response).await.unwrap();
+
+        assert_eq!(
+            h2.local_fingerprint().as_deref(),
+            Some("1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p")
+        );
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn ordered_headers_replace_request_headers() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        let mut fields = HeaderMap::new();
+        fields.append("cookie", "a=1".parse().unwrap());
+        fields.append("cookie", "b=2".parse().unwrap());
+        fields.append("accept", "*/*".parse().unwrap());
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://example.com/")
+                .fields(fields)
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::handshake(io).await.expect("handshake");
+
+        let mut headers = OrderedHeaders::new();
+        headers.push(COOKIE, HeaderValue::from_static("a=1"));
+        headers.push(ACCEPT, HeaderValue::from_static("*/*"));
+        headers.push(COOKIE, HeaderValue::from_static("b=2"));
+
+        let mut request = Request::builder()
+            .uri("https://example.com/")
+            .header("x-ignored", "1")
+            .body(())
+            .unwrap();
+        request.extensions_mut().insert(headers);
+
+        let (response, _) = client.send_request(request, true).unwrap();
+        h2.drive(response).await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn header_indexing_override_round_trips() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        for id in [1, 3] {
+            srv.recv_frame(
+                frames::headers(id)
+                    .request("GET", "https://example.com/")
+                    .field("authorization", "secret")
+                    .field("cookie", "a=1")
+                    .eos(),
+            )
+            .await;
+            srv.send_frame(frames::headers(id).response(200).eos())
+                .await;
+        }
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::handshake(io).await.expect("handshake");
+
+        for _ in 0..2 {
+            let indexing = HeaderIndexingOverride::new()
+                .set(
+                    AUTHORIZATION,
+                    HeaderIndexing::new(HpackIndexing::NeverIndexed).static_name(false),
+                )
+                .set(COOKIE, HeaderIndexing::new(HpackIndexing::Incremental));
+
+            let mut request = Request::builder()
+                .uri("https://example.com/")
+                .header(AUTHORIZATION, "secret")
+                .header(COOKIE, "a=1")
+                .body(())
+                .unwrap();
+            request.extensions_mut().insert(indexing);
+
+            let (response, _) = client.send_request(request, true).unwrap();
+            h2.drive(response).await.unwrap();
+        }
+    };
+
+    join(srv, h2).await;
+}
+
 const SETTINGS: &[u8] = &[0, 0, 0, 4, 0, 0, 0, 0, 0];
 const SETTINGS_ACK: &[u8] = &[0, 0, 0, 4, 1, 0, 0, 0, 0];
 
@@ -2075,3 +2485,157 @@ impl MockH2 for mock_io::Builder {
             .read(SETTINGS_ACK)
     }
 }
+
+#[tokio::test]
+async fn go_away_marks_unprocessed_requests() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://example.com/")
+                .eos(),
+        )
+        .await;
+        srv.recv_frame(
+            frames::headers(3)
+                .request("GET", "https://example.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::go_away(1).data("restarting")).await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::handshake(io).await.unwrap();
+        let request = || {
+            Request::builder()
+                .uri("https://example.com/")
+                .body(())
+                .unwrap()
+        };
+        let (response1, _) = client.send_request(request(), true).unwrap();
+        let (response3, _) = client.send_request(request(), true).unwrap();
+
+        let err = h2.drive(response3).await.unwrap_err();
+        assert!(err.is_unprocessed());
+        assert!(err.is_go_away());
+        assert!(err.is_remote());
+        assert_eq!(err.reason(), Some(Reason::NO_ERROR));
+
+        // The GOAWAY frame was received while driving the connection above.
+        let go_away = futures::future::poll_fn(|cx| h2.poll_goaway(cx))
+            .await
+            .unwrap();
+        assert_eq!(u32::from(go_away.last_stream_id()), 1);
+        assert_eq!(go_away.reason(), Reason::NO_ERROR);
+        assert_eq!(go_away.debug_data(), "restarting");
+
+        let response1 = h2.drive(response1).await.unwrap();
+        assert_eq!(response1.status(), StatusCode::OK);
+
+        drop(client);
+        h2.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn request_after_go_away_is_unprocessed() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://example.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::go_away(1)).await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::handshake(io).await.unwrap();
+        let request = || {
+            Request::builder()
+                .uri("https://example.com/")
+                .body(())
+                .unwrap()
+        };
+        let (response1, _) = client.send_request(request(), true).unwrap();
+        let response1 = h2.drive(response1).await.unwrap();
+        assert_eq!(response1.status(), StatusCode::OK);
+
+        let err = poll_fn(|cx| client.poll_ready(cx)).await.unwrap_err();
+        assert!(err.is_unprocessed(), "{:?}", err);
+        assert!(err.is_go_away());
+        assert!(err.is_remote());
+
+        let err = client.send_request(request(), true).unwrap_err();
+        assert!(err.is_unprocessed(), "{:?}", err);
+
+        h2.await.unwrap();
+
+        // Closing the connection does not change how new requests fail.
+        let err = client.send_request(request(), true).unwrap_err();
+        assert!(err.is_unprocessed(), "{:?}", err);
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn refused_stream_is_unprocessed() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://example.com/")
+                .eos(),
+        )
+        .await;
+        srv.recv_frame(
+            frames::headers(3)
+                .request("GET", "https://example.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::reset(1).refused()).await;
+        srv.send_frame(frames::reset(3).cancel()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::handshake(io).await.unwrap();
+        let request = || {
+            Request::builder()
+                .uri("https://example.com/")
+                .body(())
+                .unwrap()
+        };
+        let (response1, _) = client.send_request(request(), true).unwrap();
+        let (response3, _) = client.send_request(request(), true).unwrap();
+
+        let err = h2.drive(response1).await.unwrap_err();
+        assert!(err.is_unprocessed());
+        let err = h2.drive(response3).await.unwrap_err();
+        assert!(!err.is_unprocessed());
+
+        drop(client);
+        h2.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
@@ -0,0 +1,356 @@
+use futures::StreamExt;
+use h2_support::prelude::*;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+#[tokio::test]
+async fn server_handles_connection_extension_frame() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received2 = received.clone();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        // The first type is registered, the other is not.
+        client
+            .send_frame(frames::extension(0, 0xf2, 0, b"connection"))
+            .await;
+        client.send_frame(frames::extension(0, 0xfe, 0, b"?")).await;
+        client.send_frame(frames::ping([1; 8])).await;
+        client.recv_frame(frames::ping([1; 8]).pong()).await;
+    };
+
+    let srv = async move {
+        let mut s = server::Builder::new()
+            .extension_frame_handler(0xf2, move |frame| {
+                received2.lock().unwrap().push(frame);
+            })
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+        assert!(s.next().await.is_none());
+    };
+
+    join(client, srv).await;
+
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].kind(), 0xf2);
+    assert_eq!(received[0].flags(), 0);
+    assert_eq!(received[0].payload(), &b"connection"[..]);
+}
+
+#[tokio::test]
+async fn client_recv_extension_frame_on_open_stream() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://http2.akamai.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200)).await;
+        srv.send_frame(frames::extension(1, 0xf0, 0x1, b"hello"))
+            .await;
+        srv.send_frame(frames::data(1, "world").eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, h2) = client::Builder::new()
+            .extension_frame_handler(0xf0, |_| panic!("frame of an open stream"))
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+        let request = Request::builder()
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+        let response = async move {
+            let mut body = response.await.unwrap().into_body();
+
+            let frame = body.extension_frame().await.unwrap().unwrap();
+            assert_eq!(frame.kind(), 0xf0);
+            assert_eq!(frame.flags(), 0x1);
+            assert_eq!(frame.stream_id(), body.stream_id());
+            assert_eq!(frame.into_payload(), "hello");
+
+            assert_eq!(body.data().await.unwrap().unwrap(), "world");
+            assert!(body.extension_frame().await.is_none());
+        };
+        join(response, async { h2.await.unwrap() }).await;
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn send_extension_frame() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://http2.akamai.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+        srv.recv_frame(frames::extension(0, 0xf3, 0x2, b"payload"))
+            .await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::handshake(io).await.unwrap();
+        let request = Request::builder()
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+        let response = h2.drive(response).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Frame types defined by the crate are rejected.
+        let err = h2
+            .send_extension_frame(0x0, 0, 0, Bytes::from_static(b"data"))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "user error: invalid extension frame type or stream ID"
+        );
+        let err = h2
+            .send_extension_frame(0xf0, 0, 1 << 31, Bytes::new())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "user error: invalid extension frame type or stream ID"
+        );
+
+        h2.send_extension_frame(0xf3, 0x2, 0, Bytes::from_static(b"payload"))
+            .unwrap();
+        drop(client);
+        h2.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn server_sends_origin_and_altsvc() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/")
+                    .eos(),
+            )
+            .await;
+        client
+            .recv_frame(frames::origin(&[
+                "https://example.com",
+                "https://cdn.example.com",
+            ]))
+            .await;
+        client
+            .recv_frame(frames::altsvc(0, "https://example.com", "h3=\":443\""))
+            .await;
+        client
+            .recv_frame(frames::headers(1).response(200).eos())
+            .await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+        let (_req, mut stream) = srv.next().await.unwrap().unwrap();
+
+        let err = srv.send_altsvc("", "h3=\":443\"").unwrap_err();
+        assert_eq!(err.to_string(), "user error: invalid origin");
+
+        srv.send_origin(["https://example.com", "https://cdn.example.com"])
+            .unwrap();
+        srv.send_altsvc("https://example.com", "h3=\":443\"")
+            .unwrap();
+        stream.send_response(Response::new(()), true).unwrap();
+        assert!(srv.next().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn client_polls_origin_set_and_altsvc() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+    let (done_tx, done_rx) = futures::channel::oneshot::channel();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.send_frame(frames::origin(&["https://a.example.com"]))
+            .await;
+        srv.send_frame(frames::origin(&[
+            "https://a.example.com",
+            "https://b.example.com",
+        ]))
+        .await;
+        // An ALTSVC frame without an origin on stream 0 is ignored.
+        srv.send_bytes(&[0, 0, 2, 0xa, 0, 0, 0, 0, 0, 0, 0]).await;
+        srv.send_frame(frames::altsvc(0, "https://a.example.com", "clear"))
+            .await;
+        srv.send_frame(frames::altsvc(1, "", "h2=\":8443\"")).await;
+        done_rx.await.unwrap();
+    };
+
+    let h2 = async move {
+        let (_client, mut h2) = client::handshake(io).await.unwrap();
+
+        // The origins of both frames are returned once.
+        let origin_set = poll_fn(|cx| {
+            assert!(Pin::new(&mut h2).poll(cx).is_pending());
+            h2.poll_origin_set(cx)
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            origin_set,
+            ["https://a.example.com", "https://b.example.com"]
+        );
+
+        let altsvc = poll_fn(|cx| {
+            assert!(Pin::new(&mut h2).poll(cx).is_pending());
+            h2.poll_altsvc(cx)
+        })
+        .await
+        .unwrap();
+        assert_eq!(altsvc.stream_id().as_u32(), 0);
+        assert_eq!(altsvc.origin(), "https://a.example.com");
+        assert_eq!(altsvc.field_value(), "clear");
+
+        let altsvc = poll_fn(|cx| {
+            assert!(Pin::new(&mut h2).poll(cx).is_pending());
+            h2.poll_altsvc(cx)
+        })
+        .await
+        .unwrap();
+        assert_eq!(altsvc.stream_id().as_u32(), 1);
+        assert!(altsvc.origin().is_empty());
+        assert_eq!(altsvc.field_value(), "h2=\":8443\"");
+
+        done_tx.send(()).unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn client_origin_set_accumulates() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+    let (polled_tx, polled_rx) = futures::channel::oneshot::channel();
+    let (done_tx, done_rx) = futures::channel::oneshot::channel();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.send_frame(frames::origin(&["https://a.example.com"]))
+            .await;
+        polled_rx.await.unwrap();
+        srv.send_frame(frames::origin(&[
+            "https://b.example.com",
+            "https://a.example.com",
+        ]))
+        .await;
+        // A frame that adds no origins does not change the origin set.
+        srv.send_frame(frames::origin(&["https://b.example.com"]))
+            .await;
+        srv.send_frame(frames::altsvc(0, "https://a.example.com", "clear"))
+            .await;
+        done_rx.await.unwrap();
+    };
+
+    let h2 = async move {
+        let (_client, mut h2) = client::handshake(io).await.unwrap();
+
+        let origin_set = poll_fn(|cx| {
+            assert!(Pin::new(&mut h2).poll(cx).is_pending());
+            h2.poll_origin_set(cx)
+        })
+        .await
+        .unwrap();
+        assert_eq!(origin_set, ["https://a.example.com"]);
+        polled_tx.send(()).unwrap();
+
+        let origin_set = poll_fn(|cx| {
+            assert!(Pin::new(&mut h2).poll(cx).is_pending());
+            h2.poll_origin_set(cx)
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            origin_set,
+            ["https://a.example.com", "https://b.example.com"]
+        );
+
+        // The ALTSVC frame follows the last ORIGIN frame.
+        poll_fn(|cx| {
+            assert!(Pin::new(&mut h2).poll(cx).is_pending());
+            h2.poll_altsvc(cx)
+        })
+        .await
+        .unwrap();
+        assert!(poll_fn(|cx| std::task::Poll::Ready(h2.poll_origin_set(cx)))
+            .await
+            .is_pending());
+
+        done_tx.send(()).unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn client_extension_polls_end_once_closed() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.send_frame(frames::altsvc(0, "https://a.example.com", "clear"))
+            .await;
+        srv.send_frame(frames::go_away(0)).await;
+    };
+
+    let h2 = async move {
+        let (_client, mut h2) = client::handshake(io).await.unwrap();
+        (&mut h2).await.unwrap();
+
+        // The frames received before the connection closed are still
+        // returned first.
+        let altsvc = poll_fn(|cx| h2.poll_altsvc(cx)).await.unwrap();
+        assert_eq!(altsvc.field_value(), "clear");
+        assert!(poll_fn(|cx| h2.poll_altsvc(cx)).await.is_none());
+        assert!(poll_fn(|cx| h2.poll_origin_set(cx)).await.is_none());
+
+        let go_away = poll_fn(|cx| h2.poll_goaway(cx)).await.unwrap();
+        assert_eq!(go_away.reason(), Reason::NO_ERROR);
+        assert!(poll_fn(|cx| h2.poll_goaway(cx)).await.is_none());
+    };
+
+    join(srv, h2).await;
+}
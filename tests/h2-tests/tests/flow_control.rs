@@ -1225,6 +1225,77 @@ async fn decrease_target_window_size() {
     join(srv, client).await;
 }
 
+#[tokio::test]
+async fn preface_window_update_increases_connection_window() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        srv.send_frame(frames::settings()).await;
+        srv.read_preface().await.unwrap();
+        let settings = assert_settings!(srv.next().await.unwrap().unwrap());
+        assert_default_settings!(settings);
+        srv.recv_frame(frames::window_update(0, 65_535)).await;
+        srv.send_frame(frames::settings_ack()).await;
+        srv.recv_frame(frames::settings_ack()).await;
+
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://http2.akamai.com/")
+                .eos(),
+        )
+        .await;
+        srv.recv_frame(
+            frames::headers(3)
+                .request("GET", "https://http2.akamai.com/")
+                .eos(),
+        )
+        .await;
+
+        // Fill both stream windows. Without the preface WINDOW_UPDATE, this
+        // would overflow the connection window.
+        for id in [1, 3] {
+            srv.send_frame(frames::headers(id).response(200)).await;
+            srv.send_frame(frames::data(id, vec![0; 16_384])).await;
+            srv.send_frame(frames::data(id, vec![0; 16_384])).await;
+            srv.send_frame(frames::data(id, vec![0; 16_384])).await;
+            srv.send_frame(frames::data(id, vec![0; 16_383]).eos())
+                .await;
+        }
+    };
+
+    let client = async move {
+        let (mut client, mut conn) = client::Builder::new()
+            .preface_window_update(65_535)
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+
+        let request = Request::builder()
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (resp1, _) = client.send_request(request, true).unwrap();
+
+        let request = Request::builder()
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (resp2, _) = client.send_request(request, true).unwrap();
+
+        for resp in [resp1, resp2] {
+            let res = conn.drive(resp).await.expect("response");
+            let bytes = conn
+                .drive(util::concat(res.into_body()))
+                .await
+                .expect("concat");
+            assert_eq!(bytes.len(), 65_535);
+        }
+    };
+
+    join(srv, client).await;
+}
+
 #[tokio::test]
 async fn client_update_initial_window_size() {
     h2_support::trace_init!();
@@ -2311,3 +2382,147 @@ async fn too_many_window_update_resets_causes_go_away() {
 
     join(srv, client).await;
 }
+
+#[tokio::test]
+async fn recv_stream_reader_releases_capacity() {
+    use tokio::io::AsyncReadExt;
+
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://http2.akamai.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200)).await;
+        srv.send_frame(frames::data(1, "hello")).await;
+        srv.send_frame(frames::data(1, " world").eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, h2) = client::handshake(io).await.unwrap();
+        let request = Request::builder()
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+        let response = async move {
+            let mut reader = response.await.unwrap().into_body().into_async_read();
+
+            let mut body = String::new();
+            reader.read_to_string(&mut body).await.unwrap();
+            assert_eq!(body, "hello world");
+            assert_eq!(reader.get_mut().flow_control().used_capacity(), 0);
+        };
+        join(response, async { h2.await.unwrap() }).await;
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn send_stream_writer_sink_chunks_by_capacity() {
+    use futures::SinkExt;
+
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(frames::headers(1).request("POST", "https://http2.akamai.com/"))
+            .await;
+        for _ in 0..3 {
+            srv.recv_frame(frames::data(1, vec![0; 16_384])).await;
+        }
+        srv.recv_frame(frames::data(1, vec![0; 16_383])).await;
+        srv.send_frame(frames::window_update(0, 10_000)).await;
+        srv.send_frame(frames::window_update(1, 10_000)).await;
+        srv.recv_frame(frames::data(1, vec![0; 4_465])).await;
+        srv.recv_frame(frames::data(1, vec![0; 5_000])).await;
+        srv.recv_frame(frames::data(1, "").eos()).await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::handshake(io).await.unwrap();
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (response, stream) = client.send_request(request, false).unwrap();
+
+        let mut writer = stream.into_async_write();
+        h2.drive(writer.send(Bytes::from(vec![0; 70_000])))
+            .await
+            .unwrap();
+        h2.drive(writer.send(Bytes::from(vec![0; 5_000])))
+            .await
+            .unwrap();
+        writer.close().await.unwrap();
+
+        let response = h2.drive(response).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn adaptive_window_grows_windows() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://http2.akamai.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200)).await;
+        srv.send_frame(frames::data(1, vec![0; 16_384])).await;
+        srv.recv_frame(frames::ping(frame::Ping::BDP)).await;
+        srv.send_frame(frames::data(1, vec![0; 16_384])).await;
+        srv.send_frame(frames::data(1, vec![0; 16_384])).await;
+        srv.send_frame(frames::ping(frame::Ping::BDP).pong()).await;
+
+        // 49,152 bytes were received during the round trip, which nears the
+        // initial window, so the windows are grown to twice that.
+        srv.recv_frame(frames::settings().initial_window_size(98_304))
+            .await;
+        srv.recv_frame(frames::window_update(0, 32_769)).await;
+        srv.send_frame(frames::settings_ack()).await;
+        srv.send_frame(frames::data(1, vec![0; 16_384]).eos()).await;
+    };
+
+    let h2 = async move {
+        let mut builder = client::Builder::new();
+        builder.adaptive_window(true);
+        let (mut client, h2) = builder.handshake::<_, Bytes>(io).await.unwrap();
+        let request = Request::builder()
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+        let response = async move {
+            let mut body = response.await.unwrap().into_body();
+            let mut len = 0;
+            while let Some(data) = body.data().await {
+                len += data.unwrap().len();
+            }
+            assert_eq!(len, 65_536);
+        };
+        join(response, async { h2.await.unwrap() }).await;
+    };
+
+    join(srv, h2).await;
+}
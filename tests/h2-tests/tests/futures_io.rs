@@ -0,0 +1,73 @@
+use futures::StreamExt;
+use h2::compat::FuturesIo;
+use h2_support::prelude::*;
+use tokio_util::compat::TokioAsyncReadCompatExt;
+
+#[tokio::test]
+async fn client_handshake_over_futures_io() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://http2.akamai.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200)).await;
+        srv.send_frame(frames::data(1, "hello").eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, h2) = client::handshake(FuturesIo::new(io.compat()))
+            .await
+            .unwrap();
+        let request = Request::builder()
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+        let response = async move {
+            let mut body = response.await.unwrap().into_body();
+            assert_eq!(body.data().await.unwrap().unwrap(), "hello");
+        };
+        join(response, async { h2.await.unwrap() }).await;
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn server_handshake_over_futures_io() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://http2.akamai.com/")
+                    .eos(),
+            )
+            .await;
+        client
+            .recv_frame(frames::headers(1).response(200).eos())
+            .await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(FuturesIo::new(io.compat()))
+            .await
+            .expect("handshake");
+        let (_, mut respond) = srv.next().await.unwrap().unwrap();
+        respond.send_response(Response::new(()), true).unwrap();
+        assert!(srv.next().await.is_none());
+    };
+
+    join(client, srv).await;
+}
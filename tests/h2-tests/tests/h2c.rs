@@ -0,0 +1,111 @@
+use futures::StreamExt;
+use h2::server::Detected;
+use h2_support::prelude::*;
+use tokio::io::AsyncReadExt;
+
+// SETTINGS_INITIAL_WINDOW_SIZE = 100
+const SETTINGS_HEADER: &[u8] = b"AAQAAABk";
+
+#[tokio::test]
+async fn upgrade_responds_on_stream_1() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client.recv_frame(frames::data(1, vec![0; 100])).await;
+        client.send_frame(frames::window_update(1, 100)).await;
+        client.recv_frame(frames::data(1, vec![0; 50]).eos()).await;
+        client
+            .send_frame(
+                frames::headers(3)
+                    .request("GET", "https://example.com/")
+                    .eos(),
+            )
+            .await;
+        client
+            .recv_frame(frames::headers(3).response(200).eos())
+            .await;
+    };
+
+    let srv = async move {
+        let (mut srv, mut respond) = server::Builder::new()
+            .handshake_upgrade::<_, Bytes>(io, SETTINGS_HEADER)
+            .await
+            .expect("handshake");
+        assert_eq!(u32::from(respond.stream_id()), 1);
+
+        let mut stream = respond.send_response(Response::new(()), false).unwrap();
+        stream.send_data(vec![0; 150].into(), true).unwrap();
+
+        let (request, mut respond) = srv.next().await.unwrap().unwrap();
+        assert_eq!(request.uri(), "https://example.com/");
+        respond.send_response(Response::new(()), true).unwrap();
+        assert!(srv.next().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn upgrade_with_invalid_settings_header_fails() {
+    h2_support::trace_init!();
+    let (io, _client) = mock::new();
+
+    let err = server::Builder::new()
+        .handshake_upgrade::<_, Bytes>(io, b"not base64!")
+        .await
+        .unwrap_err();
+    assert_eq!(err.reason(), Some(Reason::PROTOCOL_ERROR));
+}
+
+#[tokio::test]
+async fn detect_preface_prior_knowledge() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/")
+                    .eos(),
+            )
+            .await;
+        client
+            .recv_frame(frames::headers(1).response(200).eos())
+            .await;
+    };
+
+    let srv = async move {
+        let (detected, io) = server::detect_preface(io).await.unwrap();
+        assert_eq!(detected, Detected::Http2);
+
+        let mut srv = server::handshake(io).await.expect("handshake");
+        let (_, mut respond) = srv.next().await.unwrap().unwrap();
+        respond.send_response(Response::new(()), true).unwrap();
+        assert!(srv.next().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn detect_preface_http1() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+    let request = b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n";
+
+    client.send_bytes(request).await;
+
+    let (detected, mut io) = server::detect_preface(io).await.unwrap();
+    assert_eq!(detected, Detected::Http1);
+
+    let mut buf = vec![0; request.len()];
+    io.read_exact(&mut buf).await.unwrap();
+    assert_eq!(buf, request);
+}
@@ -234,6 +234,7 @@ async fn invalid_informational_status_returns_error() {
 }
 
 #[tokio::test]
+#[allow(clippy::never_loop)]
 async fn client_poll_informational_responses_none() {
     h2_support::trace_init!();
     let (io, mut srv) = mock::new();
@@ -308,6 +309,7 @@ async fn client_poll_informational_responses_none() {
 }
 
 #[tokio::test]
+#[allow(clippy::never_loop)]
 async fn client_poll_informational_responses() {
     h2_support::trace_init!();
     let (io, mut srv) = mock::new();
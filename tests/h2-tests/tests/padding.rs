@@ -0,0 +1,221 @@
+use futures::StreamExt;
+use h2::padding::Padding;
+use h2_support::prelude::*;
+
+#[tokio::test]
+async fn client_pads_data_frames() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        match srv.next().await.unwrap().unwrap() {
+            frame::Frame::Headers(headers) => assert_eq!(headers.stream_id(), 1),
+            frame => panic!("unexpected frame: {:?}", frame),
+        }
+        srv.recv_frame(frames::data(1, "hello").pad_len(10).eos())
+            .await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::Builder::new()
+            .padding(Padding::fixed(10))
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (response, mut stream) = client.send_request(request, false).unwrap();
+        stream.send_data("hello".into(), true).unwrap();
+
+        let response = h2.drive(response).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The padding and its length field count against flow control.
+        assert_eq!(stream.stats().send_window(), 65_535 - 16);
+        assert_eq!(h2.stats().send_window(), 65_535 - 16);
+
+        drop(client);
+        h2.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn stream_padding_overrides_connection() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(frames::headers(1).request("POST", "https://http2.akamai.com/"))
+            .await;
+        srv.recv_frame(frames::data(1, "hello").eos()).await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::Builder::new()
+            .padding(Padding::fixed(10))
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (response, mut stream) = client.send_request(request, false).unwrap();
+        stream.set_padding(Padding::none());
+        stream.send_data("hello".into(), true).unwrap();
+
+        let response = h2.drive(response).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(stream.stats().send_window(), 65_535 - 5);
+
+        drop(client);
+        h2.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn data_padding_limited_by_window() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv
+            .assert_client_handshake_with_settings(frames::settings().initial_window_size(10))
+            .await;
+        assert_default_settings!(settings);
+        match srv.next().await.unwrap().unwrap() {
+            frame::Frame::Headers(headers) => assert_eq!(headers.stream_id(), 1),
+            frame => panic!("unexpected frame: {:?}", frame),
+        }
+        // Only 4 octets of padding fit in the window of 10 octets.
+        srv.recv_frame(frames::data(1, "hello").pad_len(4).eos())
+            .await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::Builder::new()
+            .padding(Padding::fixed(255))
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (response, mut stream) = client.send_request(request, false).unwrap();
+        stream.send_data("hello".into(), true).unwrap();
+
+        let response = h2.drive(response).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(stream.stats().send_window(), 0);
+
+        drop(client);
+        h2.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn server_pads_data_frames_to_block_size() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://http2.akamai.com/")
+                    .eos(),
+            )
+            .await;
+        match client.next().await.unwrap().unwrap() {
+            frame::Frame::Headers(headers) => assert_eq!(headers.stream_id(), 1),
+            frame => panic!("unexpected frame: {:?}", frame),
+        }
+        // The length field, the data and the padding fill 16 octets.
+        client
+            .recv_frame(frames::data(1, "hello").pad_len(10).eos())
+            .await;
+    };
+
+    let srv = async move {
+        let mut srv = server::Builder::new()
+            .padding(Padding::block(16))
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+        let (_, mut respond) = srv.next().await.unwrap().unwrap();
+        let mut stream = respond.send_response(Response::new(()), false).unwrap();
+        stream.send_data("hello".into(), true).unwrap();
+        assert!(srv.next().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn padded_frames_are_received_by_peer() {
+    h2_support::trace_init!();
+
+    // Returns the number of octets received by the client.
+    async fn exchange(padding: Padding) -> u64 {
+        let (client_io, server_io) = tokio::io::duplex(65_536);
+
+        let srv = async move {
+            let mut srv = server::Builder::new()
+                .padding(padding)
+                .handshake::<_, Bytes>(server_io)
+                .await
+                .unwrap();
+            let (request, mut respond) = srv.next().await.unwrap().unwrap();
+            assert_eq!(request.uri().path(), "/padded");
+            let mut stream = respond.send_response(Response::new(()), false).unwrap();
+            stream.send_data("hello".into(), true).unwrap();
+            assert!(srv.next().await.is_none());
+        };
+
+        let client = async move {
+            let (mut client, mut h2) = client::handshake(client_io).await.unwrap();
+            let request = Request::builder()
+                .uri("https://http2.akamai.com/padded")
+                .body(())
+                .unwrap();
+            let (response, _) = client.send_request(request, true).unwrap();
+            let mut body = h2.drive(response).await.unwrap().into_body();
+            assert_eq!(h2.drive(body.data()).await.unwrap().unwrap(), "hello");
+            let received = h2.stats().received().bytes();
+            drop(body);
+            drop(client);
+            h2.await.unwrap();
+            received
+        };
+
+        join(srv, client).await.1
+    }
+
+    let unpadded = exchange(Padding::none()).await;
+    let padded = exchange(Padding::fixed(20)).await;
+
+    // The response HEADERS and DATA frames each carry a length field and 20
+    // octets of padding.
+    assert_eq!(padded, unpadded + 2 * 21);
+}
@@ -183,3 +183,118 @@ async fn user_notifies_when_connection_closes() {
         "broken pipe",
     );
 }
+
+#[tokio::test]
+async fn keep_alive_pings_while_idle() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        for _ in 0..2 {
+            srv.recv_frame(frames::ping(frame::Ping::KEEP_ALIVE)).await;
+            srv.send_frame(frames::ping(frame::Ping::KEEP_ALIVE).pong())
+                .await;
+        }
+        srv.send_frame(frames::go_away(0)).await;
+    };
+
+    let h2 = async move {
+        let (_client, h2) = client::Builder::new()
+            .keep_alive_interval(Duration::from_millis(10))
+            .keep_alive_while_idle(true)
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+        h2.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn keep_alive_timeout_fails_connection() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://http2.akamai.com/")
+                .eos(),
+        )
+        .await;
+        srv.recv_frame(frames::ping(frame::Ping::KEEP_ALIVE)).await;
+        srv.recv_frame(frames::go_away(0)).await;
+    };
+
+    let h2 = async move {
+        let (mut client, h2) = client::Builder::new()
+            .keep_alive_interval(Duration::from_millis(10))
+            .keep_alive_timeout(Duration::from_millis(10))
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+        let request = Request::builder()
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+
+        let err = h2.await.unwrap_err();
+        assert!(err.is_keep_alive_timeout(), "{:?}", err);
+        let err = response.await.unwrap_err();
+        assert!(err.is_keep_alive_timeout(), "{:?}", err);
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn keep_alive_uses_builder_timer() {
+    use h2::timer::{Sleep, Timer};
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    struct CountingTimer(Arc<AtomicUsize>);
+
+    impl Timer for CountingTimer {
+        fn sleep_until(&self, deadline: Instant) -> Pin<Box<dyn Sleep>> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Box::pin(tokio::time::sleep_until(deadline.into()))
+        }
+    }
+
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+    let sleeps = Arc::new(AtomicUsize::new(0));
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(frames::ping(frame::Ping::KEEP_ALIVE)).await;
+        srv.send_frame(frames::ping(frame::Ping::KEEP_ALIVE).pong())
+            .await;
+        srv.send_frame(frames::go_away(0)).await;
+    };
+
+    let timer = CountingTimer(sleeps.clone());
+    let h2 = async move {
+        let (_client, h2) = client::Builder::new()
+            .keep_alive_interval(Duration::from_millis(10))
+            .keep_alive_while_idle(true)
+            .timer(timer)
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+        h2.await.unwrap();
+    };
+
+    join(srv, h2).await;
+    assert!(sleeps.load(Ordering::SeqCst) >= 2);
+}
@@ -508,3 +508,517 @@ async fn stream_count_over_max_stream_limit_does_not_starve_capacity() {
 
     select(task, t).await;
 }
+
+#[tokio::test]
+async fn server_sends_more_urgent_response_first() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/image.png")
+                    .field("priority", "u=5")
+                    .eos(),
+            )
+            .await;
+        client
+            .send_frame(
+                frames::headers(3)
+                    .request("GET", "https://example.com/style.css")
+                    .field("priority", "u=1")
+                    .eos(),
+            )
+            .await;
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client.recv_frame(frames::headers(3).response(200)).await;
+        client.recv_frame(frames::data(3, "css").eos()).await;
+        client.recv_frame(frames::data(1, "png").eos()).await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+        let (_, mut image) = srv.next().await.unwrap().unwrap();
+        let (_, mut style) = srv.next().await.unwrap().unwrap();
+
+        let rsp = || Response::builder().status(200).body(()).unwrap();
+        let mut image = image.send_response(rsp(), false).unwrap();
+        let mut style = style.send_response(rsp(), false).unwrap();
+        image.send_data("png".into(), true).unwrap();
+        style.send_data("css".into(), true).unwrap();
+
+        assert!(srv.next().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn server_applies_received_priority_update() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(frames::headers(1).request("POST", "https://example.com/"))
+            .await;
+        client
+            .send_frame(
+                frames::headers(3)
+                    .request("GET", "https://example.com/")
+                    .eos(),
+            )
+            .await;
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client.recv_frame(frames::headers(3).response(200)).await;
+        client.send_frame(frames::priority_update(1, "u=0")).await;
+        client.send_frame(frames::data(1, "hello").eos()).await;
+        client.recv_frame(frames::data(1, "one").eos()).await;
+        client.recv_frame(frames::data(3, "three").eos()).await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+        let (req, mut one) = srv.next().await.unwrap().unwrap();
+        let (_, mut three) = srv.next().await.unwrap().unwrap();
+
+        let rsp = || Response::builder().status(200).body(()).unwrap();
+        let mut one = one.send_response(rsp(), false).unwrap();
+        let mut three = three.send_response(rsp(), false).unwrap();
+
+        let body = async move {
+            let buf = util::concat(req.into_body()).await.unwrap();
+            assert_eq!(buf, "hello");
+
+            three.send_data("three".into(), true).unwrap();
+            one.send_data("one".into(), true).unwrap();
+        };
+
+        let mut srv = Box::pin(async move {
+            assert!(srv.next().await.is_none());
+        });
+        srv.drive(body).await;
+        srv.await;
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn client_sends_priority_update() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://example.com/")
+                .field("priority", "u=5"),
+        )
+        .await;
+        srv.recv_frame(frames::priority_update(1, "u=1, i")).await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::handshake(io).await.unwrap();
+        let request = Request::get("https://example.com/")
+            .header("priority", "u=5")
+            .body(())
+            .unwrap();
+        let (response, mut stream) = client.send_request(request, false).unwrap();
+
+        // Updating before the request is sent still sends the HEADERS
+        // frame first.
+        stream.set_priority(ext::Priority::new(1, true));
+        // Setting the same priority again does not send another frame.
+        stream.set_priority(ext::Priority::new(1, true));
+
+        let response = h2.drive(response).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn client_reads_priority_from_ordered_headers() {
+    use h2::ext::OrderedHeaders;
+    use http::HeaderValue;
+
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("POST", "https://example.com/")
+                .field("priority", "u=7"),
+        )
+        .await;
+        srv.recv_frame(
+            frames::headers(3)
+                .request("POST", "https://example.com/")
+                .field("priority", "u=3"),
+        )
+        .await;
+        srv.recv_frame(frames::data(3, "b").eos()).await;
+        srv.recv_frame(frames::data(1, "a").eos()).await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+        srv.send_frame(frames::headers(3).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::handshake(io).await.unwrap();
+
+        // The `priority` field of the header map is not sent, so the stream
+        // is scheduled with the one of the ordered fields.
+        let mut fields = OrderedHeaders::new();
+        fields.push(
+            http::header::HeaderName::from_static("priority"),
+            HeaderValue::from_static("u=7"),
+        );
+        let mut request = Request::post("https://example.com/")
+            .header("priority", "u=1")
+            .body(())
+            .unwrap();
+        request.extensions_mut().insert(fields);
+        let (response1, mut stream1) = client.send_request(request, false).unwrap();
+
+        let request = Request::post("https://example.com/")
+            .header("priority", "u=3")
+            .body(())
+            .unwrap();
+        let (response3, mut stream3) = client.send_request(request, false).unwrap();
+
+        stream1.send_data("a".into(), true).unwrap();
+        stream3.send_data("b".into(), true).unwrap();
+
+        let response = h2.drive(response1).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = h2.drive(response3).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn client_rejects_priority_update() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://example.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::priority_update(1, "u=0")).await;
+
+        let go_away = assert_go_away!(srv.next().await.unwrap().unwrap());
+        assert_eq!(go_away.reason(), Reason::PROTOCOL_ERROR);
+    };
+
+    let h2 = async move {
+        let (mut client, h2) = client::handshake(io).await.unwrap();
+        let request = Request::get("https://example.com/").body(()).unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+
+        let err = join(async move { h2.await.unwrap_err() }, response).await.0;
+        assert_eq!(err.reason(), Some(Reason::PROTOCOL_ERROR));
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn client_sends_no_rfc7540_priorities_setting() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_eq!(settings.is_no_rfc7540_priorities(), Some(true));
+    };
+
+    let h2 = async move {
+        let (_client, h2) = client::Builder::new()
+            .no_rfc7540_priorities(true)
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+        h2.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn server_serves_dependency_before_dependent_stream() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/image.png")
+                    .eos(),
+            )
+            .await;
+        client.send_frame(frames::priority(1, 3, 15, false)).await;
+        client
+            .send_frame(
+                frames::headers(3)
+                    .request("GET", "https://example.com/")
+                    .eos(),
+            )
+            .await;
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client.recv_frame(frames::headers(3).response(200)).await;
+        client.recv_frame(frames::data(3, "html").eos()).await;
+        client.recv_frame(frames::data(1, "png").eos()).await;
+    };
+
+    let srv = async move {
+        let mut srv = server::Builder::new()
+            .rfc7540_priorities(true)
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+        let (_, mut image) = srv.next().await.unwrap().unwrap();
+        let (_, mut page) = srv.next().await.unwrap().unwrap();
+
+        let rsp = || Response::builder().status(200).body(()).unwrap();
+        let mut image = image.send_response(rsp(), false).unwrap();
+        let mut page = page.send_response(rsp(), false).unwrap();
+        image.send_data("png".into(), true).unwrap();
+        page.send_data("html".into(), true).unwrap();
+
+        assert!(srv.next().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn server_applies_exclusive_dependency_of_headers() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/image.png")
+                    .eos(),
+            )
+            .await;
+        client
+            .send_frame(
+                frames::headers(3)
+                    .request("GET", "https://example.com/")
+                    .priority(0, 255, true)
+                    .eos(),
+            )
+            .await;
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client.recv_frame(frames::headers(3).response(200)).await;
+        client.recv_frame(frames::data(3, "html").eos()).await;
+        client.recv_frame(frames::data(1, "png").eos()).await;
+    };
+
+    let srv = async move {
+        let mut srv = server::Builder::new()
+            .rfc7540_priorities(true)
+            .handshake::<_, Bytes>(io)
+            .await
+            .expect("handshake");
+        let (_, mut image) = srv.next().await.unwrap().unwrap();
+        let (_, mut page) = srv.next().await.unwrap().unwrap();
+
+        let rsp = || Response::builder().status(200).body(()).unwrap();
+        let mut image = image.send_response(rsp(), false).unwrap();
+        let mut page = page.send_response(rsp(), false).unwrap();
+        image.send_data("png".into(), true).unwrap();
+        page.send_data("html".into(), true).unwrap();
+
+        assert!(srv.next().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn client_uses_custom_scheduler() {
+    use h2::scheduler::SendScheduler;
+    use std::collections::BTreeSet;
+    use std::sync::{Arc, Mutex};
+
+    // Serves the stream with the highest ID first.
+    struct Lifo {
+        ready: BTreeSet<h2::StreamId>,
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl SendScheduler for Lifo {
+        fn stream_opened(&mut self, id: h2::StreamId) {
+            let event = format!("opened {}", id.as_u32());
+            self.events.lock().unwrap().push(event);
+        }
+
+        fn stream_closed(&mut self, id: h2::StreamId) {
+            let event = format!("closed {}", id.as_u32());
+            self.events.lock().unwrap().push(event);
+            self.ready.remove(&id);
+        }
+
+        fn capacity_assigned(&mut self, id: h2::StreamId, capacity: u32) {
+            let event = format!("capacity {} {}", id.as_u32(), capacity);
+            self.events.lock().unwrap().push(event);
+        }
+
+        fn frame_ready(&mut self, id: h2::StreamId) {
+            self.ready.insert(id);
+        }
+
+        fn next_stream(&mut self) -> Option<h2::StreamId> {
+            let id = self.ready.iter().next_back().copied()?;
+            self.ready.remove(&id);
+            Some(id)
+        }
+    }
+
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(frames::headers(1).request("POST", "https://example.com/"))
+            .await;
+        srv.recv_frame(frames::headers(3).request("POST", "https://example.com/"))
+            .await;
+        srv.recv_frame(frames::data(3, "c")).await;
+        srv.recv_frame(frames::data(3, "d").eos()).await;
+        srv.recv_frame(frames::data(1, "a")).await;
+        srv.recv_frame(frames::data(1, "b").eos()).await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+        srv.send_frame(frames::headers(3).response(200).eos()).await;
+    };
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let scheduler_events = events.clone();
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::Builder::new()
+            .scheduler(move || Lifo {
+                ready: BTreeSet::new(),
+                events: scheduler_events.clone(),
+            })
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+
+        let request = || Request::post("https://example.com/").body(()).unwrap();
+        let (response1, mut stream1) = client.send_request(request(), false).unwrap();
+        let (response3, mut stream3) = client.send_request(request(), false).unwrap();
+        stream1.send_data("a".into(), false).unwrap();
+        stream1.send_data("b".into(), true).unwrap();
+        stream3.send_data("c".into(), false).unwrap();
+        stream3.send_data("d".into(), true).unwrap();
+
+        let response = h2.drive(response1).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = h2.drive(response3).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    };
+
+    join(srv, h2).await;
+
+    let events = events.lock().unwrap();
+    assert_eq!(
+        *events,
+        [
+            "capacity 1 2",
+            "opened 1",
+            "capacity 3 2",
+            "opened 3",
+            "closed 3",
+            "closed 1",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn client_survives_scheduler_returning_streams_that_are_not_ready() {
+    use h2::scheduler::SendScheduler;
+
+    // Keeps returning the last ready stream instead of forgetting it.
+    struct Peek {
+        last: Option<h2::StreamId>,
+    }
+
+    impl SendScheduler for Peek {
+        fn frame_ready(&mut self, id: h2::StreamId) {
+            self.last = Some(id);
+        }
+
+        fn next_stream(&mut self) -> Option<h2::StreamId> {
+            self.last
+        }
+    }
+
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(frames::headers(1).request("POST", "https://example.com/"))
+            .await;
+        srv.recv_frame(frames::data(1, "a")).await;
+        srv.send_frame(frames::headers(1).response(200).eos()).await;
+        srv.recv_frame(frames::data(1, "b").eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::Builder::new()
+            .scheduler(|| Peek { last: None })
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+
+        let request = Request::post("https://example.com/").body(()).unwrap();
+        let (response, mut stream) = client.send_request(request, false).unwrap();
+        stream.send_data("a".into(), false).unwrap();
+
+        // Stream 1 is not ready anymore once "a" was written, but the
+        // scheduler still returns it.
+        let response = h2.drive(response).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        stream.send_data("b".into(), true).unwrap();
+        drop(client);
+        h2.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
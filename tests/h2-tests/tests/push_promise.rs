@@ -464,3 +464,244 @@ async fn recv_push_promise_dup_stream_id() {
 
     join(mock, h2).await;
 }
+
+#[tokio::test]
+async fn push_policy_cancels_rejected_pushes() {
+    h2_support::trace_init!();
+
+    let (io, mut srv) = mock::new();
+    let mock = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://http2.akamai.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200)).await;
+        srv.send_frame(
+            frames::push_promise(1, 2).request("GET", "https://http2.akamai.com/script.js"),
+        )
+        .await;
+        srv.send_frame(
+            frames::push_promise(1, 4).request("GET", "https://http2.akamai.com/style.css"),
+        )
+        .await;
+        srv.recv_frame(frames::reset(2).cancel()).await;
+        srv.send_frame(frames::data(1, "").eos()).await;
+        srv.send_frame(frames::headers(4).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::Builder::new()
+            .push_policy(|request| request.uri().path().ends_with(".css"))
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (mut resp, _) = client.send_request(request, true).unwrap();
+        let pushed = resp.push_promises();
+        let check_pushed = async move {
+            let paths: Vec<_> = pushed
+                .and_then(|push| async move {
+                    let (request, response) = push.into_parts();
+                    response.await.map(|_| request.uri().path().to_owned())
+                })
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(paths, ["/style.css"]);
+        };
+
+        h2.drive(join(resp, check_pushed)).await.0.unwrap();
+    };
+
+    join(mock, h2).await;
+}
+
+#[tokio::test]
+async fn max_concurrent_pushes_per_stream_refuses_pushes() {
+    h2_support::trace_init!();
+
+    let (io, mut srv) = mock::new();
+    let mock = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://http2.akamai.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200)).await;
+        srv.send_frame(
+            frames::push_promise(1, 2).request("GET", "https://http2.akamai.com/style.css"),
+        )
+        .await;
+        srv.send_frame(
+            frames::push_promise(1, 4).request("GET", "https://http2.akamai.com/script.js"),
+        )
+        .await;
+        srv.recv_frame(frames::reset(4).refused()).await;
+        // Once the first push is closed, another one may be promised.
+        srv.send_frame(frames::headers(2).response(200).eos()).await;
+        srv.send_frame(
+            frames::push_promise(1, 6).request("GET", "https://http2.akamai.com/script.js"),
+        )
+        .await;
+        srv.send_frame(frames::data(1, "").eos()).await;
+        srv.send_frame(frames::headers(6).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::Builder::new()
+            .max_concurrent_pushes_per_stream(1)
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (mut resp, _) = client.send_request(request, true).unwrap();
+        let pushed = resp.push_promises();
+        let check_pushed = async move {
+            let ids: Vec<_> = pushed
+                .and_then(|push| async move {
+                    let (_, response) = push.into_parts();
+                    let id = u32::from(response.stream_id());
+                    response.await.map(|_| id)
+                })
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(ids, [2, 6]);
+        };
+
+        h2.drive(join(resp, check_pushed)).await.0.unwrap();
+    };
+
+    join(mock, h2).await;
+}
+
+#[tokio::test]
+async fn claim_cached_push() {
+    h2_support::trace_init!();
+
+    let (io, mut srv) = mock::new();
+    let mock = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://http2.akamai.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200)).await;
+        srv.send_frame(
+            frames::push_promise(1, 2).request("GET", "https://http2.akamai.com/style.css"),
+        )
+        .await;
+        srv.send_frame(frames::data(1, "").eos()).await;
+        srv.send_frame(frames::headers(2).response(200)).await;
+        srv.send_frame(frames::data(2, "promised_data").eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::Builder::new()
+            .push_cache_size(4)
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (resp, _) = client.send_request(request, true).unwrap();
+        let resp = h2.drive(resp).await.unwrap();
+        let body = h2.drive(util::concat(resp.into_body())).await.unwrap();
+        assert!(body.is_empty());
+
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("https://http2.akamai.com/style.css")
+            .body(())
+            .unwrap();
+        let pushed = client.claim_push(&request).unwrap();
+        assert_eq!(u32::from(pushed.stream_id()), 2);
+        assert!(client.claim_push(&request).is_none());
+
+        let resp = h2.drive(pushed).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = h2.drive(util::concat(resp.into_body())).await.unwrap();
+        assert_eq!(body, "promised_data");
+    };
+
+    join(mock, h2).await;
+}
+
+#[tokio::test]
+async fn push_cache_cancels_oldest_push() {
+    h2_support::trace_init!();
+
+    let (io, mut srv) = mock::new();
+    let mock = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://http2.akamai.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200)).await;
+        srv.send_frame(
+            frames::push_promise(1, 2).request("GET", "https://http2.akamai.com/style.css"),
+        )
+        .await;
+        srv.send_frame(
+            frames::push_promise(1, 4).request("GET", "https://http2.akamai.com/script.js"),
+        )
+        .await;
+        srv.recv_frame(frames::reset(2).cancel()).await;
+        srv.send_frame(frames::data(1, "").eos()).await;
+        srv.send_frame(frames::headers(4).response(200).eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::Builder::new()
+            .push_cache_size(1)
+            .handshake::<_, Bytes>(io)
+            .await
+            .unwrap();
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("https://http2.akamai.com/")
+            .body(())
+            .unwrap();
+        let (resp, _) = client.send_request(request, true).unwrap();
+        h2.drive(resp).await.unwrap();
+
+        let style = Request::get("https://http2.akamai.com/style.css")
+            .body(())
+            .unwrap();
+        assert!(client.claim_push(&style).is_none());
+
+        let script = Request::get("https://http2.akamai.com/script.js")
+            .body(())
+            .unwrap();
+        let pushed = client.claim_push(&script).unwrap();
+        let resp = h2.drive(pushed).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    };
+
+    join(mock, h2).await;
+}
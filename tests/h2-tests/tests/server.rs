@@ -1,6 +1,7 @@
 #![deny(warnings)]
 
 use futures::StreamExt;
+use h2::ext::{PseudoHeader, PseudoHeaderOrder};
 use h2_support::prelude::*;
 use tokio::io::AsyncWriteExt;
 
@@ -149,6 +150,66 @@ async fn serve_request() {
     join(client, srv).await;
 }
 
+#[tokio::test]
+async fn peer_fingerprint() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        client
+            .assert_server_handshake_with_settings(
+                frames::settings()
+                    .initial_window_size(131_072)
+                    .max_frame_size(16_384),
+            )
+            .await;
+        client
+            .send_frame(frames::window_update(0, 12_517_377))
+            .await;
+        client
+            .send_frame(frame::Priority::new(
+                3.into(),
+                frame::StreamDependency::new(0.into(), 200, false),
+            ))
+            .await;
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://example.com/")
+                    .pseudo_order(PseudoHeaderOrder::new([
+                        PseudoHeader::Method,
+                        PseudoHeader::Path,
+                        PseudoHeader::Authority,
+                        PseudoHeader::Scheme,
+                    ]))
+                    .eos(),
+            )
+            .await;
+        client
+            .recv_frame(frames::headers(1).response(200).eos())
+            .await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+        assert_eq!(srv.peer_fingerprint(), None);
+
+        let (_, mut stream) = srv.next().await.unwrap().unwrap();
+
+        assert_eq!(
+            srv.peer_fingerprint().as_deref(),
+            Some("4:131072;5:16384|12517377|3:0:0:201|m,p,a,s")
+        );
+
+        let rsp = http::Response::builder().status(200).body(()).unwrap();
+        stream.send_response(rsp, true).unwrap();
+
+        assert!(srv.next().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
 #[tokio::test]
 async fn serve_connect() {
     h2_support::trace_init!();
@@ -1620,6 +1681,7 @@ async fn reject_informational_status_header_in_request() {
 }
 
 #[tokio::test]
+#[allow(clippy::let_unit_value)]
 async fn client_drop_connection_without_close_notify() {
     h2_support::trace_init!();
 
@@ -1657,6 +1719,7 @@ async fn client_drop_connection_without_close_notify() {
 }
 
 #[tokio::test]
+#[allow(clippy::let_unit_value)]
 async fn init_window_size_smaller_than_default_should_use_default_before_ack() {
     h2_support::trace_init!();
 
@@ -0,0 +1,129 @@
+use futures::StreamExt;
+use h2_support::prelude::*;
+
+#[tokio::test]
+async fn client_connection_stats() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(
+            frames::headers(1)
+                .request("GET", "https://http2.akamai.com/")
+                .eos(),
+        )
+        .await;
+        srv.recv_frame(
+            frames::headers(3)
+                .request("GET", "https://http2.akamai.com/")
+                .eos(),
+        )
+        .await;
+        srv.send_frame(frames::headers(1).response(200)).await;
+        srv.send_frame(frames::data(1, "hello").eos()).await;
+        srv.send_frame(frames::reset(3).refused()).await;
+        srv.recv_frame(frames::ping(frame::Ping::USER)).await;
+        srv.send_frame(frames::ping(frame::Ping::USER).pong()).await;
+        srv.recv_frame(frames::go_away(0)).await;
+        srv.recv_eof().await;
+    };
+
+    let h2 = async move {
+        let (mut client, mut h2) = client::handshake(io).await.unwrap();
+        let request = || {
+            Request::builder()
+                .uri("https://http2.akamai.com/")
+                .body(())
+                .unwrap()
+        };
+        let (response1, _) = client.send_request(request(), true).unwrap();
+        let (response2, _) = client.send_request(request(), true).unwrap();
+
+        let mut body = h2.drive(response1).await.unwrap().into_body();
+        assert_eq!(h2.drive(body.data()).await.unwrap().unwrap(), "hello");
+        let stream_stats = body.stats();
+        assert_eq!(stream_stats.bytes_received(), 5);
+        assert_eq!(stream_stats.bytes_sent(), 0);
+
+        let err = h2.drive(response2).await.unwrap_err();
+        assert_eq!(err.reason(), Some(Reason::REFUSED_STREAM));
+
+        let mut ping_pong = client::Connection::ping_pong(&mut h2).unwrap();
+        ping_pong.send_ping(Ping::opaque()).unwrap();
+        h2.drive(futures::future::poll_fn(|cx| ping_pong.poll_pong(cx)))
+            .await
+            .unwrap();
+
+        let stats = h2.stats();
+        assert_eq!(stats.sent().frames().headers(), 2);
+        assert_eq!(stats.sent().frames().settings(), 2);
+        assert_eq!(stats.sent().frames().ping(), 1);
+        assert_eq!(stats.received().frames().headers(), 1);
+        assert_eq!(stats.received().frames().data(), 1);
+        assert_eq!(stats.received().frames().reset(), 1);
+        assert_eq!(stats.received().resets(Reason::REFUSED_STREAM), 1);
+        assert_eq!(stats.received().resets(Reason::CANCEL), 0);
+        assert_eq!(
+            stats.received().resets_by_reason().collect::<Vec<_>>(),
+            [(Reason::REFUSED_STREAM, 1)]
+        );
+        assert!(stats.sent().bytes() > 0);
+        assert!(stats.received().bytes() > 0);
+        assert!(stats.hpack_compression_ratio().unwrap() < 1.0);
+        assert_eq!(stats.send_window(), 65_535);
+        assert_eq!(stats.peak_concurrent_streams(), 2);
+        assert!(stats.ping_rtt().is_some());
+
+        drop(body);
+        drop(client);
+        h2.await.unwrap();
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn send_stream_stats() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(
+                frames::headers(1)
+                    .request("GET", "https://http2.akamai.com/")
+                    .eos(),
+            )
+            .await;
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client
+            .recv_frame(frames::data(1, "hello world").eos())
+            .await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+        let (_, mut respond) = srv.next().await.unwrap().unwrap();
+        let mut stream = respond.send_response(Response::new(()), false).unwrap();
+        stream.send_data("hello world".into(), true).unwrap();
+        assert!(srv.next().await.is_none());
+
+        let stats = stream.stats();
+        assert_eq!(stats.bytes_sent(), 11);
+        assert_eq!(stats.bytes_received(), 0);
+        assert_eq!(stats.send_window(), 65_535 - 11);
+
+        let stats = srv.stats();
+        assert_eq!(stats.sent().frames().data(), 1);
+        assert_eq!(stats.sent().frames().headers(), 1);
+        assert_eq!(stats.received().frames().headers(), 1);
+        assert_eq!(stats.send_window(), 65_535 - 11);
+        assert_eq!(stats.peak_concurrent_streams(), 1);
+    };
+
+    join(client, srv).await;
+}
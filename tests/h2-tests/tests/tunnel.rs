@@ -0,0 +1,203 @@
+use futures::StreamExt;
+use h2::tunnel::Tunnel;
+use h2_support::prelude::*;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[tokio::test]
+async fn client_connect_tunnel() {
+    h2_support::trace_init!();
+    let (io, mut srv) = mock::new();
+
+    let srv = async move {
+        let settings = srv.assert_client_handshake().await;
+        assert_default_settings!(settings);
+        srv.recv_frame(frames::headers(1).pseudo(frame::Pseudo {
+            method: Method::CONNECT.into(),
+            authority: util::byte_str("tunnel.example.com:443").into(),
+            ..Default::default()
+        }))
+        .await;
+        srv.send_frame(frames::headers(1).response(200)).await;
+        srv.send_frame(frames::data(1, "pong")).await;
+        srv.recv_frame(frames::data(1, "ping")).await;
+        srv.recv_frame(frames::data(1, "").eos()).await;
+        srv.send_frame(frames::data(1, "").eos()).await;
+    };
+
+    let h2 = async move {
+        let (mut client, h2) = client::handshake(io).await.unwrap();
+        let tunnel = async move {
+            let request = Request::builder()
+                .method(Method::CONNECT)
+                .uri("tunnel.example.com:443")
+                .body(())
+                .unwrap();
+            let (response, mut tunnel) = Tunnel::connect(&mut client, request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            tunnel.write_all(b"ping").await.unwrap();
+            tunnel.shutdown().await.unwrap();
+
+            let mut buf = Vec::new();
+            tunnel.read_to_end(&mut buf).await.unwrap();
+            assert_eq!(buf, b"pong");
+        };
+        join(tunnel, async { h2.await.unwrap() }).await;
+    };
+
+    join(srv, h2).await;
+}
+
+#[tokio::test]
+async fn server_accept_websocket_tunnel() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_eq!(settings.is_extended_connect_protocol_enabled(), Some(true));
+        client
+            .send_frame(frames::headers(1).pseudo(frame::Pseudo {
+                method: Method::CONNECT.into(),
+                scheme: util::byte_str("https").into(),
+                authority: util::byte_str("example.com").into(),
+                path: util::byte_str("/chat").into(),
+                protocol: Protocol::from("websocket").into(),
+                ..Default::default()
+            }))
+            .await;
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client.send_frame(frames::data(1, "hello")).await;
+        client.recv_frame(frames::data(1, "hello")).await;
+        client.send_frame(frames::data(1, "").eos()).await;
+        client.recv_frame(frames::data(1, "").eos()).await;
+    };
+
+    let srv = async move {
+        let mut builder = server::Builder::new();
+        builder.enable_connect_protocol();
+        let mut srv = builder.handshake::<_, Bytes>(io).await.expect("handshake");
+
+        let (request, respond) = srv.next().await.unwrap().unwrap();
+        assert_eq!(
+            request.extensions().get::<Protocol>().map(Protocol::as_str),
+            Some("websocket")
+        );
+
+        let tunnel = Tunnel::accept(request, respond, Response::new(())).unwrap();
+        let echo = async move {
+            let (mut reader, mut writer) = tokio::io::split(tunnel);
+            let n = tokio::io::copy(&mut reader, &mut writer).await.unwrap();
+            assert_eq!(n, 5);
+            writer.shutdown().await.unwrap();
+        };
+
+        join(echo, async { assert!(srv.next().await.is_none()) }).await;
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn dropping_tunnel_resets_stream() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(frames::headers(1).request("CONNECT", "tunnel.example.com:443"))
+            .await;
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client.recv_frame(frames::data(1, "partial")).await;
+        client.recv_frame(frames::reset(1).cancel()).await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+
+        let (request, respond) = srv.next().await.unwrap().unwrap();
+        let mut tunnel = Tunnel::accept(request, respond, Response::new(())).unwrap();
+        tunnel.write_all(b"partial").await.unwrap();
+        drop(tunnel);
+
+        assert!(srv.next().await.is_none());
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn tunnel_read_fails_on_reset() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(frames::headers(1).request("CONNECT", "tunnel.example.com:443"))
+            .await;
+        client.recv_frame(frames::headers(1).response(200)).await;
+        client
+            .send_frame(frames::reset(1).reason(Reason::CONNECT_ERROR))
+            .await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+
+        let (request, respond) = srv.next().await.unwrap().unwrap();
+        let mut tunnel = Tunnel::accept(request, respond, Response::new(())).unwrap();
+        let read = async move {
+            let mut buf = [0; 8];
+            let err = tunnel.read(&mut buf).await.unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::ConnectionReset);
+        };
+
+        join(read, async { assert!(srv.next().await.is_none()) }).await;
+    };
+
+    join(client, srv).await;
+}
+
+#[tokio::test]
+async fn tunnel_write_waits_for_capacity() {
+    h2_support::trace_init!();
+    let (io, mut client) = mock::new();
+
+    let client = async move {
+        let settings = client.assert_server_handshake().await;
+        assert_default_settings!(settings);
+        client
+            .send_frame(frames::headers(1).request("CONNECT", "tunnel.example.com:443"))
+            .await;
+        client.recv_frame(frames::headers(1).response(200)).await;
+        for _ in 0..3 {
+            client.recv_frame(frames::data(1, vec![0; 16_384])).await;
+        }
+        client.recv_frame(frames::data(1, vec![0; 16_383])).await;
+        client.send_frame(frames::window_update(0, 4_465)).await;
+        client.send_frame(frames::window_update(1, 4_465)).await;
+        client.recv_frame(frames::data(1, vec![0; 4_465])).await;
+        client.recv_frame(frames::data(1, "").eos()).await;
+        client.send_frame(frames::data(1, "").eos()).await;
+    };
+
+    let srv = async move {
+        let mut srv = server::handshake(io).await.expect("handshake");
+
+        let (request, respond) = srv.next().await.unwrap().unwrap();
+        let mut tunnel = Tunnel::accept(request, respond, Response::new(())).unwrap();
+        let write = async move {
+            tunnel.write_all(&[0; 70_000]).await.unwrap();
+            tunnel.shutdown().await.unwrap();
+            tunnel.read_to_end(&mut Vec::new()).await.unwrap();
+        };
+
+        join(write, async { assert!(srv.next().await.is_none()) }).await;
+    };
+
+    join(client, srv).await;
+}